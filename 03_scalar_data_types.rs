@@ -110,6 +110,32 @@ fn main() {
     //let tmp: u8 = 256;
     //the literal `256` does not fit into the type `u8` whose range is `0..=255`
 
+    // 练习3：
+    println!("{:b} {:b} {:b}", 1u64, 2u64, 3u64); // 1 10 11，二进制下 1 的个数分别是 1、1、2
+    println!("Set bits: {}", total_set_bits(&[1, 2, 3])); // 更多的例子见文件末尾的单元测试
+
+}
+
+// 练习3：
+// count_ones() 返回一个整数在二进制表示下有多少个 1（返回值是 u32）
+fn total_set_bits(nums: &[u64]) -> u64 {
+    nums.iter().map(|n| n.count_ones() as u64).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 练习3
+    #[test]
+    fn total_set_bits_sums_count_ones() {
+        assert_eq!(total_set_bits(&[1, 2, 3]), 4);
+        assert_eq!(total_set_bits(&[]), 0);
+        assert_eq!(total_set_bits(&[0, 0]), 0);
+        assert_eq!(total_set_bits(&[u64::MAX]), 64);
+        // 结果是 u64，多个 u64::MAX 相加也不会溢出
+        assert_eq!(total_set_bits(&[u64::MAX, u64::MAX, 0b1011]), 131);
+    }
 }
 
 /*
@@ -142,4 +168,9 @@ fn main() {
  *       `// println!("{}", overflow);`
  *       在 `cargo run` (debug模式)下运行，观察程序的 panic。这就是练习中提到的运行时整数溢出。
  *
+ * 3. 统计二进制中的 1:
+ *    编写函数 `total_set_bits(nums: &[u64]) -> u64`，用整数的 `count_ones()` 方法
+ *    统计切片中所有数字在二进制下一共有多少个 1。
+ *    例如 [1, 2, 3] 的二进制是 1、10、11，结果是 1 + 1 + 2 = 4；空切片返回 0。
+ *
  */
\ No newline at end of file
@@ -105,6 +105,97 @@ fn main() {
     let costs: [f64;5]=[44.1,39.8,38.5,40.9,36.8];
     let result = costs[0]+costs[1]+costs[2]+costs[3]+costs[4];
     println!("total costs:{}",result);
+
+    // 练习3：
+    let partial_row: [u8; 9] = [5, 3, 0, 0, 7, 0, 0, 0, 0];
+    let duplicate_row: [u8; 9] = [5, 3, 0, 0, 7, 0, 0, 3, 0];
+    println!("partial row valid: {}", is_valid_group(&partial_row));     // true，0 代表空格，可以重复
+    println!("duplicate row valid: {}", is_valid_group(&duplicate_row)); // false，3 出现了两次
+
+    // 练习4：
+    let points = [(1.0, 2.0), (-3.5, 4.0), (2.0, -1.0), (0.0, 0.0)];
+    println!("{:?}", bounding_box(&points)); // Some((-3.5, -1.0, 2.0, 4.0))
+    println!("{:?}", bounding_box(&[(5.0, 5.0)])); // Some((5.0, 5.0, 5.0, 5.0))，只有一个点时框退化成一个点
+    println!("{:?}", bounding_box(&[])); // None
+}
+
+// 练习3：
+// seen 是一个长度为 10 的布尔数组，seen[d] 表示数字 d 是否已经出现过
+fn is_valid_group(cells: &[u8; 9]) -> bool {
+    let mut seen = [false; 10];
+    for &cell in cells {
+        // 0 表示空格，不参与检查
+        if cell == 0 {
+            continue;
+        }
+        // 超出 1-9 的数字或者重复出现的数字都说明这一组无效
+        if cell > 9 || seen[cell as usize] {
+            return false;
+        }
+        seen[cell as usize] = true;
+    }
+    true
+}
+
+// 练习4：
+// 返回 (min_x, min_y, max_x, max_y)。用第一个点作为初始值，再依次用其他点扩大范围
+fn bounding_box(points: &[(f64, f64)]) -> Option<(f64, f64, f64, f64)> {
+    let &(first_x, first_y) = points.first()?;
+    let mut bounds = (first_x, first_y, first_x, first_y);
+    for &(x, y) in &points[1..] {
+        bounds.0 = bounds.0.min(x);
+        bounds.1 = bounds.1.min(y);
+        bounds.2 = bounds.2.max(x);
+        bounds.3 = bounds.3.max(y);
+    }
+    Some(bounds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 练习3
+    #[test]
+    fn complete_and_partial_groups_are_valid() {
+        assert!(is_valid_group(&[5, 3, 4, 6, 7, 8, 9, 1, 2]));
+        assert!(is_valid_group(&[5, 3, 0, 0, 7, 0, 0, 0, 0]));
+        assert!(is_valid_group(&[0; 9]));
+    }
+
+    #[test]
+    fn duplicates_make_a_group_invalid() {
+        assert!(!is_valid_group(&[5, 3, 0, 0, 7, 0, 0, 3, 0]));
+        assert!(!is_valid_group(&[9, 9, 0, 0, 0, 0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn digits_outside_one_to_nine_are_invalid() {
+        // 0 是空格，可以出现多次；10 以上不是数独里的数字
+        assert!(is_valid_group(&[0, 0, 1, 0, 0, 0, 0, 0, 0]));
+        assert!(!is_valid_group(&[1, 2, 3, 4, 5, 6, 7, 8, 10]));
+        assert!(!is_valid_group(&[255, 0, 0, 0, 0, 0, 0, 0, 0]));
+    }
+
+    // 练习4
+    #[test]
+    fn bounding_box_of_no_points_is_none() {
+        assert_eq!(bounding_box(&[]), None);
+    }
+
+    #[test]
+    fn bounding_box_of_one_point_is_that_point() {
+        assert_eq!(bounding_box(&[(5.0, 5.0)]), Some((5.0, 5.0, 5.0, 5.0)));
+        assert_eq!(bounding_box(&[(-2.5, 7.0)]), Some((-2.5, 7.0, -2.5, 7.0)));
+    }
+
+    #[test]
+    fn bounding_box_handles_negative_coordinates() {
+        let points = [(1.0, 2.0), (-3.5, 4.0), (2.0, -1.0), (0.0, 0.0)];
+        assert_eq!(bounding_box(&points), Some((-3.5, -1.0, 2.0, 4.0)));
+        // 全部是负数时，最大值也是负数，而不是被初始值 0 卡住
+        assert_eq!(bounding_box(&[(-1.0, -8.0), (-4.0, -2.0)]), Some((-4.0, -8.0, -1.0, -2.0)));
+    }
 }
 
 /*
@@ -122,4 +213,14 @@ fn main() {
  *    通过索引访问每个元素，并将它们相加，计算出总开销并打印出来。
  *    (我们后面会学习更简单的遍历方法，现在先用索引练习一下！)
  *
+ * 3. 检查数独的一组格子:
+ *    编写函数 `is_valid_group(cells: &[u8; 9]) -> bool`，检查数独的一行、一列或一个九宫格。
+ *    0 表示空格；其余数字 1-9 不能重复出现。
+ *    提示：用一个 `[bool; 10]` 数组记录每个数字是否已经出现过。
+ *
+ * 4. 点集的包围盒:
+ *    编写函数 `bounding_box(points: &[(f64, f64)]) -> Option<(f64, f64, f64, f64)>`，
+ *    返回能包住所有点的最小矩形 (min_x, min_y, max_x, max_y)。没有点时返回 `None`。
+ *    提示：元组的字段可以用 `.0`、`.1` 这样的写法读取和修改。
+ *
  */
\ No newline at end of file
@@ -100,6 +100,23 @@ fn main() {
 
     // 练习2：
     print_christmas_lyrics();
+
+    // 练习3：
+    println!("{:?}", fibonacci(10));      // [0, 1, 1, 2, 3, 5, 8, 13, 21, 34]
+    println!("{:?}", fibonacci_diffs(8)); // [1, 0, 1, 1, 2, 3, 5, 8]
+
+    // 练习4：
+    println!("{:?}", sum_range_step(0, 10, 2));  // Ok(20)，0 + 2 + 4 + 6 + 8
+    println!("{:?}", sum_range_step(10, 0, -3)); // Ok(22)，10 + 7 + 4 + 1
+    println!("{:?}", sum_range_step(0, 10, 0));  // Err("步长不能为 0")
+    println!("{:?}", sum_range_step(0, 10, -1)); // Err("步长 -1 永远到不了 10")
+
+    // 练习5：
+    println!("{:?}", combinations(5, 2));   // Some(10)
+    println!("{:?}", combinations(10, 0));  // Some(1)
+    println!("{:?}", combinations(3, 5));   // None，r > n
+    println!("{:?}", combinations(60, 30)); // Some(118264581564861424)，60! 本身远远超过 u64，但逐步计算不会溢出
+    println!("{:?}", combinations(100, 50)); // None，结果超出了 u64 的范围
 }
 // 练习1：
 fn fibonacci_sequence(n: u32){
@@ -160,6 +177,172 @@ fn print_christmas_lyrics() {
         }
     }
 }
+
+// 练习3：
+// 返回斐波那契数列的前 n 个数字（用 u64 保存，比 i32 能多算很多项）。
+// u64 最多放得下 F(93)，也就是前 94 项；n 更大时只返回这 94 项
+fn fibonacci(n: u32) -> Vec<u64> {
+    let mut numbers = Vec::new();
+    let mut a: u64 = 0;
+    let mut b: u64 = 1;
+    for _ in 0..n {
+        numbers.push(a);
+        // a + b 比这次放进去的 a 要多算两项，它溢出时 b 仍然放得下：
+        // 需要的话把 b 补上，然后结束，不用 + 避免在 debug 模式下 panic
+        let Some(next) = a.checked_add(b) else {
+            if (numbers.len() as u32) < n {
+                numbers.push(b);
+            }
+            break;
+        };
+        a = b;
+        b = next;
+    }
+    numbers
+}
+
+// 相邻两项的差：F(i+1) - F(i) = F(i-1)，所以差本身也是斐波那契数列（从 1, 0, 1, 1, 2 ... 开始）
+fn fibonacci_diffs(n: u32) -> Vec<u64> {
+    // n 个差需要 n + 1 个数字
+    let numbers = fibonacci(n + 1);
+    let mut diffs = Vec::new();
+    for i in 1..numbers.len() {
+        diffs.push(numbers[i] - numbers[i - 1]);
+    }
+    diffs
+}
+
+// 练习4：
+// 从 start（包含）开始，每次加 step，直到到达 end（不包含），把经过的数加起来。
+// step 可以是负数，表示从大往小数。
+fn sum_range_step(start: i64, end: i64, step: i64) -> Result<i64, String> {
+    if step == 0 {
+        return Err(String::from("步长不能为 0"));
+    }
+    // 方向不对的步长会离 end 越来越远，循环永远不会结束
+    if (start < end && step < 0) || (start > end && step > 0) {
+        return Err(format!("步长 {} 永远到不了 {}", step, end));
+    }
+
+    let mut sum: i64 = 0;
+    let mut current = start;
+    while (step > 0 && current < end) || (step < 0 && current > end) {
+        sum += current;
+        // checked_add 在溢出时返回 None，这时说明已经越过了 i64 的范围，也就不可能再小于 end 了
+        current = match current.checked_add(step) {
+            Some(next) => next,
+            None => break,
+        };
+    }
+    Ok(sum)
+}
+// 练习5：
+// C(n, r) = n! / (r! * (n - r)!)。直接算阶乘很快就会溢出，
+// 所以一边乘一边除：第 i 步之后 result 正好是 C(n - r + i, i)，每一步的除法都能整除。
+// 中间结果用 u128 保存，最终结果放不进 u64 时返回 None
+fn combinations(n: u64, r: u64) -> Option<u64> {
+    if r > n {
+        return None;
+    }
+    // C(n, r) == C(n, n - r)，取较小的那个可以少循环几次
+    let r = r.min(n - r);
+    let mut result: u128 = 1;
+    for i in 1..=r as u128 {
+        result = result.checked_mul(n as u128 - r as u128 + i)? / i;
+    }
+    u64::try_from(result).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 练习3
+    #[test]
+    fn fibonacci_first_terms() {
+        assert_eq!(fibonacci(0), Vec::<u64>::new());
+        assert_eq!(fibonacci(1), vec![0]);
+        assert_eq!(fibonacci(2), vec![0, 1]);
+        assert_eq!(fibonacci(10), vec![0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+    }
+
+    #[test]
+    fn fibonacci_stops_at_the_last_term_that_fits() {
+        // F(92) 和 F(93) 都放得下，算 F(94) 时会溢出
+        let numbers = fibonacci(93);
+        assert_eq!(numbers.len(), 93);
+        assert_eq!(numbers[92], 7540113804746346429);
+        assert_eq!(fibonacci(94)[93], 12200160415121876738);
+        assert_eq!(fibonacci(200).len(), 94);
+    }
+
+    #[test]
+    fn fibonacci_diffs_are_fibonacci_numbers() {
+        assert_eq!(fibonacci_diffs(8), vec![1, 0, 1, 1, 2, 3, 5, 8]);
+        assert_eq!(fibonacci_diffs(0), Vec::<u64>::new());
+        // fibonacci_diffs(92) 要用到 F(92)，第 92 个差是 F(90)
+        let diffs = fibonacci_diffs(92);
+        assert_eq!(diffs.len(), 92);
+        assert_eq!(diffs[91], 2880067194370816120);
+    }
+
+    // 练习4
+    #[test]
+    fn sum_range_step_counts_up_and_down() {
+        // 0 + 2 + 4 + 6 + 8
+        assert_eq!(sum_range_step(0, 10, 2), Ok(20));
+        // 10 + 7 + 4 + 1
+        assert_eq!(sum_range_step(10, 0, -3), Ok(22));
+        // 步长比整个范围还大，只加上 start
+        assert_eq!(sum_range_step(0, 3, 5), Ok(0));
+    }
+
+    #[test]
+    fn sum_range_step_of_an_empty_range_is_zero() {
+        assert_eq!(sum_range_step(5, 5, 1), Ok(0));
+        assert_eq!(sum_range_step(5, 5, -1), Ok(0));
+    }
+
+    #[test]
+    fn sum_range_step_rejects_bad_steps() {
+        assert_eq!(sum_range_step(0, 10, 0), Err(String::from("步长不能为 0")));
+        assert_eq!(sum_range_step(0, 10, -1), Err(String::from("步长 -1 永远到不了 10")));
+        assert!(sum_range_step(10, 0, 2).is_err());
+    }
+
+    #[test]
+    fn sum_range_step_stops_at_the_edge_of_i64() {
+        // 下一步会超出 i64 的范围，循环在那里结束，不会 panic
+        assert_eq!(sum_range_step(i64::MAX - 1, i64::MAX, i64::MAX), Ok(i64::MAX - 1));
+    }
+
+    // 练习5
+    #[test]
+    fn combinations_of_small_values() {
+        assert_eq!(combinations(5, 2), Some(10));
+        assert_eq!(combinations(5, 3), Some(10));
+        assert_eq!(combinations(10, 0), Some(1));
+        assert_eq!(combinations(0, 0), Some(1));
+        assert_eq!(combinations(7, 7), Some(1));
+    }
+
+    #[test]
+    fn combinations_with_r_greater_than_n_is_none() {
+        assert_eq!(combinations(3, 5), None);
+        assert_eq!(combinations(0, 1), None);
+    }
+
+    #[test]
+    fn combinations_returns_none_when_the_result_overflows() {
+        // 60! 本身远远超过 u64，但逐步计算不会溢出
+        assert_eq!(combinations(60, 30), Some(118264581564861424));
+        assert_eq!(combinations(100, 50), None);
+        // 中间结果超过 u128 时也返回 None，而不是 panic
+        assert_eq!(combinations(u64::MAX, u64::MAX / 2), None);
+        assert_eq!(combinations(u64::MAX, 1), Some(u64::MAX));
+    }
+}
+
 /*
  * =====================================================================================
  * 练习挑战 (Challenge Section)
@@ -174,4 +357,20 @@ fn print_christmas_lyrics() {
  *    使用循环（嵌套循环可能会有帮助）来打印出经典圣诞歌曲 "The Twelve Days of Christmas" 的全部歌词。
  *    你需要一个外层循环来控制天数（从第一天到第十二天），和一个内层循环来打印每天收到的礼物。
  *
+ * 3. 斐波那契数列的差:
+ *    编写函数 `fibonacci(n: u32) -> Vec<u64>` 返回前 n 个斐波那契数，
+ *    再编写 `fibonacci_diffs(n: u32) -> Vec<u64>`，返回相邻两项之间的 n 个差。
+ *    观察结果：这些差本身也是斐波那契数。
+ *
+ * 4. 带步长的区间求和:
+ *    编写函数 `sum_range_step(start: i64, end: i64, step: i64) -> Result<i64, String>`，
+ *    从 `start`（包含）开始每次加 `step`，到 `end`（不包含）为止，返回经过的所有数字之和。
+ *    步长为 0，或者步长的方向永远到不了 `end` 时返回 `Err`。
+ *    例如 `sum_range_step(0, 10, 2)` 返回 `Ok(20)`。
+ *
+ * 5. 组合数:
+ *    编写函数 `combinations(n: u64, r: u64) -> Option<u64>`，计算从 n 个元素中选 r 个的组合数 C(n, r)。
+ *    不要先算阶乘（很快就会溢出），而是在循环中一边乘一边除。`r > n` 时返回 `None`。
+ *    例如 C(5, 2) = 10，C(10, 0) = 1。
+ *
  */
\ No newline at end of file
@@ -104,6 +104,33 @@ fn main() {
         //2.因为rust规则：只有一个可变借用可以操作写入，所以str2暂时为不可以状态，因为下面还有继续使用string_ref。
     println!("After modification, the content is: {}", string_ref); // 使用 string_ref
     println!("Now we can use my_string again: {}", str2);//结果一致
+
+    // 练习3：
+    let mut library = Library::new();
+    library.add_document(1, "draft v1");
+    library.add_document(2, "notes");
+
+    // a) 借出文档 1，拿到一个“独占编辑权”的句柄
+    let handle = library.checkout(1).expect("文档 1 应该可以借出");
+    // b) 同一时间只能有一个借出者，第二次借出会失败
+    println!("Second checkout: {:?}", library.checkout(1)); // Err(AlreadyCheckedOut { by_handle: 1 })
+    // c) 借出期间随时可以读，读到的仍然是旧内容
+    println!("Read during checkout: {:?}", library.read(1)); // Some("draft v1")
+    // d) 归还句柄并提交新内容，handle 的所有权被移动进 checkin
+    println!("Checkin: {:?}", library.checkin(handle, String::from("draft v2"))); // Ok(())
+    println!("Read after checkin: {:?}", library.read(1)); // Some("draft v2")
+    // DocumentHandle 没有实现 Copy/Clone，所以同一个句柄不能归还两次：
+    // library.checkin(handle, String::from("again")); // error[E0382]: use of moved value: `handle`
+
+    // e) 管理员强制释放后，旧句柄就“过期”了
+    let stale = library.checkout(2).expect("文档 2 应该可以借出");
+    println!("Force release: {}", library.force_release(2)); // true
+    println!("Stale checkin: {:?}", library.checkin(stale, String::from("lost edit"))); // Err(StaleHandle { .. })
+
+    // f) 借出期间文档被删除，归还时会得到 NotFound
+    let orphan = library.checkout(2).expect("文档 2 已被释放，可以再次借出");
+    library.remove_document(2);
+    println!("Checkin deleted: {:?}", library.checkin(orphan, String::from("orphan"))); // Err(NotFound(2))
 }
 
 // 这个函数接收一个 String 的引用，返回其长度
@@ -126,6 +153,101 @@ fn add_suffix(s: &mut String) -> &String{
     s.push_str("!");
     s
 }
+// 练习3：
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq)]
+enum CheckoutError {
+    NotFound(u32),
+    AlreadyCheckedOut { by_handle: u64 },
+    StaleHandle { doc_id: u32, handle_id: u64 },
+}
+
+// 句柄代表“对某个文档的独占编辑权”，相当于运行时的 `&mut`。
+// 注意这里故意没有 #[derive(Clone, Copy)]：句柄只能被移动，
+// checkin 按值接收它之后，调用者手里就不再有这个句柄了，类型系统保证它不会被归还两次。
+#[derive(Debug)]
+struct DocumentHandle {
+    doc_id: u32,
+    handle_id: u64,
+}
+
+struct Document {
+    content: String,
+    // 当前借出者的句柄编号，None 表示没有被借出
+    checked_out_by: Option<u64>,
+}
+
+struct Library {
+    documents: HashMap<u32, Document>,
+    next_handle_id: u64,
+}
+
+impl Library {
+    fn new() -> Library {
+        Library {
+            documents: HashMap::new(),
+            next_handle_id: 1,
+        }
+    }
+
+    fn add_document(&mut self, id: u32, content: &str) {
+        self.documents.insert(
+            id,
+            Document {
+                content: content.to_string(),
+                checked_out_by: None,
+            },
+        );
+    }
+
+    fn remove_document(&mut self, id: u32) -> bool {
+        self.documents.remove(&id).is_some()
+    }
+
+    // 借出：同一时间只允许一个借出者，就像同一时间只允许一个可变引用
+    fn checkout(&mut self, id: u32) -> Result<DocumentHandle, CheckoutError> {
+        let doc = self.documents.get_mut(&id).ok_or(CheckoutError::NotFound(id))?;
+        if let Some(by_handle) = doc.checked_out_by {
+            return Err(CheckoutError::AlreadyCheckedOut { by_handle });
+        }
+        let handle_id = self.next_handle_id;
+        self.next_handle_id += 1;
+        doc.checked_out_by = Some(handle_id);
+        Ok(DocumentHandle { doc_id: id, handle_id })
+    }
+
+    // 归还：句柄必须和当前的借出记录一致，过期的或伪造的句柄都会被拒绝
+    fn checkin(&mut self, handle: DocumentHandle, new_content: String) -> Result<(), CheckoutError> {
+        let doc = self
+            .documents
+            .get_mut(&handle.doc_id)
+            .ok_or(CheckoutError::NotFound(handle.doc_id))?;
+        if doc.checked_out_by != Some(handle.handle_id) {
+            return Err(CheckoutError::StaleHandle {
+                doc_id: handle.doc_id,
+                handle_id: handle.handle_id,
+            });
+        }
+        doc.content = new_content;
+        doc.checked_out_by = None;
+        Ok(())
+    }
+
+    // 读取永远是允许的，就像不可变引用一样；返回的 &str 借用自 Library
+    fn read(&self, id: u32) -> Option<&str> {
+        self.documents.get(&id).map(|doc| doc.content.as_str())
+    }
+
+    // 管理员恢复：强制解除借出状态，返回该文档之前是否处于借出状态
+    fn force_release(&mut self, id: u32) -> bool {
+        match self.documents.get_mut(&id) {
+            Some(doc) => doc.checked_out_by.take().is_some(),
+            None => false,
+        }
+    }
+}
+
 // 这个函数尝试创建一个悬垂引用，但编译器会阻止我们
 // fn dangle() -> &String { // dangle 返回一个 String 的引用
 //     let s = String::from("dangle"); // s 是一个新的 String
@@ -133,6 +255,86 @@ fn add_suffix(s: &mut String) -> &String{
 // } // s 在这里离开作用域，被 drop，内存被释放。它的引用就指向了无效的内存！
 // Rust 编译器会报错：`this function's return type contains a borrowed value, but there is no value for it to be borrowed from`
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_library() -> Library {
+        let mut library = Library::new();
+        library.add_document(1, "draft v1");
+        library.add_document(2, "notes");
+        library
+    }
+
+    // 练习3
+    #[test]
+    fn second_checkout_fails_until_checkin() {
+        let mut library = sample_library();
+        let handle = library.checkout(1).unwrap();
+        assert_eq!(library.checkout(1).unwrap_err(), CheckoutError::AlreadyCheckedOut { by_handle: handle.handle_id });
+        // 别的文档不受影响
+        assert!(library.checkout(2).is_ok());
+        library.checkin(handle, String::from("draft v2")).unwrap();
+        assert!(library.checkout(1).is_ok());
+        assert_eq!(library.checkout(3).unwrap_err(), CheckoutError::NotFound(3));
+    }
+
+    #[test]
+    fn reads_see_the_old_content_during_checkout() {
+        let mut library = sample_library();
+        let handle = library.checkout(1).unwrap();
+        assert_eq!(library.read(1), Some("draft v1"));
+        library.checkin(handle, String::from("draft v2")).unwrap();
+        assert_eq!(library.read(1), Some("draft v2"));
+        assert_eq!(library.read(3), None);
+    }
+
+    #[test]
+    fn stale_handles_are_rejected_after_force_release() {
+        let mut library = sample_library();
+        let stale = library.checkout(2).unwrap();
+        let stale_id = stale.handle_id;
+        assert!(library.force_release(2));
+        assert!(!library.force_release(2));
+        let current = library.checkout(2).unwrap();
+        assert_eq!(
+            library.checkin(stale, String::from("lost edit")),
+            Err(CheckoutError::StaleHandle { doc_id: 2, handle_id: stale_id })
+        );
+        // 过期句柄的归还既不改内容，也不影响当前的借出者
+        assert_eq!(library.read(2), Some("notes"));
+        assert_eq!(library.checkout(2).unwrap_err(), CheckoutError::AlreadyCheckedOut { by_handle: current.handle_id });
+        assert_eq!(library.checkin(current, String::from("kept edit")), Ok(()));
+        assert_eq!(library.read(2), Some("kept edit"));
+    }
+
+    #[test]
+    fn checkin_after_delete_reports_not_found() {
+        let mut library = sample_library();
+        let orphan = library.checkout(2).unwrap();
+        assert!(library.remove_document(2));
+        assert!(!library.remove_document(2));
+        assert_eq!(library.checkin(orphan, String::from("orphan")), Err(CheckoutError::NotFound(2)));
+        assert!(!library.force_release(2));
+    }
+
+    #[test]
+    fn checkin_consumes_the_handle() {
+        let mut library = sample_library();
+        let handle = library.checkout(1).unwrap();
+        let (doc_id, handle_id) = (handle.doc_id, handle.handle_id);
+        library.checkin(handle, String::from("draft v2")).unwrap();
+        // handle 已经被移动进 checkin，再用它会编译失败：
+        // library.checkin(handle, String::from("again")); // error[E0382]: use of moved value: `handle`
+        // 即使伪造一个一模一样的句柄（相当于 Copy 出来的副本），也会被当作过期句柄拒绝
+        let forged = DocumentHandle { doc_id, handle_id };
+        assert_eq!(library.checkin(forged, String::from("again")), Err(CheckoutError::StaleHandle { doc_id, handle_id }));
+        assert_eq!(library.read(1), Some("draft v2"));
+        // 每次借出都会得到新的句柄编号
+        assert_ne!(library.checkout(1).unwrap().handle_id, handle_id);
+    }
+}
+
 /*
  * =====================================================================================
  * 练习挑战 (Challenge Section)
@@ -158,4 +360,15 @@ fn add_suffix(s: &mut String) -> &String{
  *    思考一下，为什么这个函数签名 `fn add_suffix(s: &mut String) -> &String` 是可行的？
  *    (提示：与生命周期有关，输入引用的生命周期会被推断为返回引用的生命周期。)
  *
+ * 3. 在运行时模拟借用规则:
+ *    编写一个 `Library`，按 id 保存文档，并提供：
+ *    - `checkout(&mut self, id: u32) -> Result<DocumentHandle, CheckoutError>`：借出文档，
+ *      同一时间只能借出一次，第二次借出返回 `AlreadyCheckedOut { by_handle }`。
+ *    - `checkin(&mut self, handle: DocumentHandle, new_content: String)`：归还并提交新内容，
+ *      过期或伪造的句柄要被拒绝。
+ *    - `read(&self, id: u32) -> Option<&str>`：任何时候都可以读。
+ *    - `force_release(&mut self, id: u32) -> bool`：管理员强制解除借出。
+ *    `DocumentHandle` 不能实现 `Copy`，这样 `checkin` 按值接收它之后，同一个句柄就不可能被归还两次。
+ *    思考一下：这和“同一时间只能有一个可变引用”的规则有什么相似之处？
+ *
  */
\ No newline at end of file
@@ -60,6 +60,8 @@
 // 代码示例 (Code Section)
 // =====================================================================================
 
+use std::io::{self, BufRead};
+
 // 1. 定义一个枚举
 enum Coin {
     Penny,
@@ -151,8 +153,78 @@ fn main() {
         println!("\t没有匹配，因为值为None")
     }
     println!("\n-----------------------------------\n");
+
+    // 练习3：
+    // 直接把本课中 Coin 的源码当作文本交给解析器
+    let coin_source = "
+        enum Coin {
+            Penny,
+            Nickel,
+            Dime,
+            Quarter(UsState), // Quarter 变体包含一个 UsState 类型的数据
+        }";
+    match parse_enum_source(coin_source) {
+        Ok(variants) => {
+            let skeleton = generate_match_skeleton("Coin", &variants);
+            println!("{}", skeleton);
+            // 与手写的、可以编译通过的版本逐字比较
+            println!("与手写版本一致: {}", skeleton == COIN_SKELETON);
+        }
+        Err(e) => println!("解析失败: {:?}", e),
+    }
+
+    let message_source = "
+        /// 概念讲解中的 Message 枚举
+        #[derive(Debug)]
+        enum Message {
+            Quit,
+            Move { x: i32, y: i32 },
+            Write(String),
+            ChangeColor(i32, i32, i32),
+        }";
+    match parse_enum_source(message_source) {
+        Ok(variants) => println!("{}", generate_match_skeleton("Message", &variants)),
+        Err(e) => println!("解析失败: {:?}", e),
+    }
+
+    // 泛型枚举不在支持范围内，会得到一个明确的错误
+    println!("{:?}", parse_enum_source("enum Option<T> { None, Some(T) }"));
+
+    // 练习4：
+    let mut monitor = AlertMonitor::new(30.0, 25.0).unwrap();
+    // 在 25 和 30 之间来回波动，只会报警一次、解除一次
+    println!("{:?}", monitor.observe_series(&[20.0, 31.0, 28.0, 31.0, 26.0, 24.0, 27.0])); // [(1, Raised), (5, Cleared)]
+    // 正好等于阈值时不会发生变化，NaN 会被跳过
+    println!("{:?}", monitor.observe_series(&[30.0, f64::NAN, 30.5, 25.0, 24.9])); // [(2, Raised), (4, Cleared)]
+    // 一开始就超过报警阈值
+    let mut hot = AlertMonitor::new(30.0, 25.0).unwrap();
+    println!("{:?} {:?}", hot.observe(35.0), hot.state); // Some(Raised) Active
+    println!("{:?}", AlertMonitor::new(25.0, 30.0).map(|m| m.state)); // Err("解除阈值 (30) 必须小于报警阈值 (25)")
+    let week = [(1, 22.0), (2, 31.5), (3, 29.0), (4, 24.0), (5, 33.0)];
+    println!("{:?}", temperature_alerts(&week, 30.0, 25.0)); // Ok([(2, Raised), (4, Cleared), (5, Raised)])
+
+    // 练习3：
+    // 把自己的枚举定义粘贴进来，生成对应的 match 骨架
+    println!("\n粘贴一个枚举的定义，以空行结束（直接回车跳过）：");
+    match read_pasted_enum(&mut io::stdin().lock()) {
+        Ok(src) if src.trim().is_empty() => {}
+        Ok(src) => match skeleton_from_source(&src) {
+            Ok(skeleton) => println!("{}", skeleton),
+            Err(e) => println!("解析失败: {:?}", e),
+        },
+        Err(e) => println!("读取输入失败: {}", e),
+    }
 }
 
+// 练习3 中生成的 Coin 骨架，手写一份作为对照。
+// 把 match 部分粘贴进一个接收 `value: Coin` 的函数里就可以直接编译。
+const COIN_SKELETON: &str = "match value {
+    Coin::Penny => todo!(),
+    Coin::Nickel => todo!(),
+    Coin::Dime => todo!(),
+    Coin::Quarter(quarter) => todo!(),
+}";
+
 fn add_fancy_hat() {}
 fn remove_fancy_hat() {}
 fn reroll() {}
@@ -170,6 +242,549 @@ fn get_duration(t1:TrafficLight) -> u8{
         TrafficLight::Green => 45
     }
 }
+// 练习3：
+// 一个变体携带的数据有三种形状：没有数据、元组（只关心有几个字段）、具名字段
+#[derive(Debug, Clone, PartialEq)]
+enum FieldSpec {
+    Unit,
+    Tuple(usize),
+    Named(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct VariantSpec {
+    name: String,
+    fields: FieldSpec,
+}
+
+#[derive(Debug, PartialEq)]
+enum EnumParseError {
+    MissingEnumKeyword,
+    MissingEnumName,
+    GenericsNotSupported(String),
+    UnbalancedBraces,
+    InvalidVariant(String),
+}
+
+// 把 "ChangeColor" 这样的驼峰名转换成 "change_color"，用作绑定变量名
+fn to_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+// Rust 的关键字（包括保留的），不能直接用作变量名
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate", "do", "dyn", "else",
+    "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "macro", "match", "mod",
+    "move", "mut", "override", "priv", "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true",
+    "try", "type", "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+// 单字段变体的绑定名：变体名的 snake_case。Move、Type、Box 这样的变体转换后正好是关键字，
+// 写成原始标识符 r#move 就能当变量名用；self、super、crate 连原始标识符都不允许，改用 value
+fn binding_name(variant_name: &str) -> String {
+    let name = to_snake_case(variant_name);
+    match name.as_str() {
+        "self" | "super" | "crate" => String::from("value"),
+        keyword if KEYWORDS.contains(&keyword) => format!("r#{}", keyword),
+        _ => name,
+    }
+}
+
+// 为每个变体生成一个 match 分支：
+// - 无数据的变体:  `Coin::Penny => todo!(),`
+// - 只有一个字段:   用变体名的 snake_case 作为绑定名，例如 `Coin::Quarter(quarter)`、`Message::Move(r#move)`
+// - 多个元组字段:   依次使用 a, b, c ... 作为绑定名
+// - 具名字段:       直接使用字段名，例如 `Message::Move { x, y }`
+fn generate_match_skeleton(enum_name: &str, variants: &[VariantSpec]) -> String {
+    let mut out = String::from("match value {\n");
+    for variant in variants {
+        let pattern = match &variant.fields {
+            FieldSpec::Unit => format!("{}::{}", enum_name, variant.name),
+            FieldSpec::Tuple(1) => format!("{}::{}({})", enum_name, variant.name, binding_name(&variant.name)),
+            FieldSpec::Tuple(n) => {
+                let bindings: Vec<String> = (0..*n)
+                    .map(|i| match i {
+                        0..=25 => ((b'a' + i as u8) as char).to_string(),
+                        _ => format!("f{}", i),
+                    })
+                    .collect();
+                format!("{}::{}({})", enum_name, variant.name, bindings.join(", "))
+            }
+            FieldSpec::Named(fields) => format!("{}::{} {{ {} }}", enum_name, variant.name, fields.join(", ")),
+        };
+        out.push_str(&format!("    {} => todo!(),\n", pattern));
+    }
+    out.push('}');
+    out
+}
+
+// 去掉注释（`//`、`///`、`/* */`）和属性（`#[...]`），只留下真正的代码
+fn strip_comments_and_attributes(src: &str) -> String {
+    let chars: Vec<char> = src.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match (chars[i], chars.get(i + 1)) {
+            ('/', Some('/')) => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            ('/', Some('*')) => {
+                i += 2;
+                while i + 1 < chars.len() && !(chars[i] == '*' && chars[i + 1] == '/') {
+                    i += 1;
+                }
+                i += 2;
+            }
+            ('#', Some('[')) => {
+                // 属性里可能还有嵌套的方括号，所以要数括号的层数
+                let mut depth = 0;
+                while i < chars.len() {
+                    match chars[i] {
+                        '[' => depth += 1,
+                        ']' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                i += 1;
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                    i += 1;
+                }
+            }
+            (c, _) => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+// 只在最外层的逗号处切分，括号、花括号和尖括号里面的逗号不算。
+// `fn() -> T` 里的 `>` 是箭头的一部分，不是尖括号，所以只有前面不是 `-`、
+// 并且确实有没关闭的 `<` 时才算关闭尖括号
+fn split_top_level(s: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut angle_depth = 0;
+    let mut previous = ' ';
+    for c in s.chars() {
+        match c {
+            '(' | '{' | '[' => depth += 1,
+            ')' | '}' | ']' => depth -= 1,
+            '<' => angle_depth += 1,
+            '>' if previous != '-' && angle_depth > 0 => angle_depth -= 1,
+            ',' if depth == 0 && angle_depth == 0 => {
+                parts.push(current.trim().to_string());
+                current.clear();
+                previous = c;
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+        previous = c;
+    }
+    parts.push(current.trim().to_string());
+    // 最后一个变体后面的逗号会留下一个空字符串，过滤掉
+    parts.into_iter().filter(|p| !p.is_empty()).collect()
+}
+
+fn parse_variant(text: &str) -> Result<VariantSpec, EnumParseError> {
+    let name: String = text.chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+    if name.is_empty() {
+        return Err(EnumParseError::InvalidVariant(text.to_string()));
+    }
+    let rest = text[name.len()..].trim();
+    let fields = if rest.is_empty() || rest.starts_with('=') {
+        // `Red = 1` 这种带判别值的写法也是无数据的变体
+        FieldSpec::Unit
+    } else if rest.starts_with('(') && rest.ends_with(')') {
+        FieldSpec::Tuple(split_top_level(&rest[1..rest.len() - 1]).len())
+    } else if rest.starts_with('{') && rest.ends_with('}') {
+        let mut names = Vec::new();
+        for field in split_top_level(&rest[1..rest.len() - 1]) {
+            match field.split_once(':') {
+                Some((field_name, _)) => names.push(field_name.trim().trim_start_matches("pub ").trim().to_string()),
+                None => return Err(EnumParseError::InvalidVariant(text.to_string())),
+            }
+        }
+        FieldSpec::Named(names)
+    } else {
+        return Err(EnumParseError::InvalidVariant(text.to_string()));
+    };
+    Ok(VariantSpec { name, fields })
+}
+
+fn parse_enum_source(src: &str) -> Result<Vec<VariantSpec>, EnumParseError> {
+    parse_enum_definition(src).map(|(_, variants)| variants)
+}
+
+// 和 parse_enum_source 一样，但同时返回枚举名
+fn parse_enum_definition(src: &str) -> Result<(String, Vec<VariantSpec>), EnumParseError> {
+    let code = strip_comments_and_attributes(src);
+
+    // 1. 找到 `enum` 关键字和后面的枚举名
+    let after_keyword = match code.split_once("enum ") {
+        Some((_, rest)) => rest.trim_start(),
+        None => return Err(EnumParseError::MissingEnumKeyword),
+    };
+    let name: String = after_keyword.chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+    if name.is_empty() {
+        return Err(EnumParseError::MissingEnumName);
+    }
+
+    // 2. 枚举名后面紧跟 `<` 说明是泛型枚举，明确拒绝
+    let after_name = after_keyword[name.len()..].trim_start();
+    if after_name.starts_with('<') {
+        return Err(EnumParseError::GenericsNotSupported(name));
+    }
+
+    // 3. 取出最外层花括号中的内容
+    if !after_name.starts_with('{') {
+        return Err(EnumParseError::UnbalancedBraces);
+    }
+    let mut depth = 0;
+    let mut body_end = None;
+    for (i, c) in after_name.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    body_end = Some(i);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let body = match body_end {
+        Some(end) => &after_name[1..end],
+        None => return Err(EnumParseError::UnbalancedBraces),
+    };
+
+    // 4. 逐个解析变体，任何一个出错就用 `?` 直接返回
+    let mut variants = Vec::new();
+    for variant_text in split_top_level(body) {
+        variants.push(parse_variant(&variant_text)?);
+    }
+    Ok((name, variants))
+}
+
+// 从粘贴进来的枚举源码直接生成 match 骨架，枚举名从源码里读
+fn skeleton_from_source(src: &str) -> Result<String, EnumParseError> {
+    let (name, variants) = parse_enum_definition(src)?;
+    Ok(generate_match_skeleton(&name, &variants))
+}
+
+// 一行一行地读粘贴进来的源码，读到空行或者输入结束为止
+fn read_pasted_enum(input: &mut impl BufRead) -> io::Result<String> {
+    let mut src = String::new();
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            return Ok(src);
+        }
+        src.push_str(&line);
+    }
+}
+
+
+// 练习4：
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AlertState {
+    Normal,
+    Active,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Transition {
+    Raised,
+    Cleared,
+}
+
+// 带“滞后”（hysteresis）的报警器：超过 rising_threshold 才报警，
+// 报警后要降到 clearing_threshold 以下才解除。两条线之间来回波动不会反复报警。
+#[derive(Debug)]
+struct AlertMonitor {
+    rising_threshold: f64,
+    clearing_threshold: f64,
+    state: AlertState,
+}
+
+impl AlertMonitor {
+    fn new(rising_threshold: f64, clearing_threshold: f64) -> Result<AlertMonitor, String> {
+        // 用 partial_cmp 而不是 a >= b：任何一个阈值是 NaN 时结果是 None，同样会报错
+        if clearing_threshold.partial_cmp(&rising_threshold) != Some(std::cmp::Ordering::Less) {
+            return Err(format!(
+                "解除阈值 ({}) 必须小于报警阈值 ({})",
+                clearing_threshold, rising_threshold
+            ));
+        }
+        Ok(AlertMonitor { rising_threshold, clearing_threshold, state: AlertState::Normal })
+    }
+
+    // 两个阈值都是“不含等于”的：value > rising 才报警，value < clearing 才解除，
+    // 正好等于阈值时保持原状态。NaN 会被忽略，不改变状态。
+    fn observe(&mut self, value: f64) -> Option<Transition> {
+        if value.is_nan() {
+            return None;
+        }
+        match self.state {
+            AlertState::Normal if value > self.rising_threshold => {
+                self.state = AlertState::Active;
+                Some(Transition::Raised)
+            }
+            AlertState::Active if value < self.clearing_threshold => {
+                self.state = AlertState::Normal;
+                Some(Transition::Cleared)
+            }
+            _ => None,
+        }
+    }
+
+    // 依次观察一串数值，返回发生状态变化的位置（下标从 0 开始）和变化
+    fn observe_series(&mut self, values: &[f64]) -> Vec<(usize, Transition)> {
+        values
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &value)| self.observe(value).map(|t| (i, t)))
+            .collect()
+    }
+}
+
+// 对按天记录的气温 (第几天, 温度) 做报警检查，返回发生变化的日期
+fn temperature_alerts(daily_temps: &[(u32, f64)], rising: f64, clearing: f64) -> Result<Vec<(u32, Transition)>, String> {
+    let mut monitor = AlertMonitor::new(rising, clearing)?;
+    Ok(daily_temps
+        .iter()
+        .filter_map(|&(day, temp)| monitor.observe(temp).map(|t| (day, t)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 练习3
+    fn variant(name: &str, fields: FieldSpec) -> VariantSpec {
+        VariantSpec { name: name.to_string(), fields }
+    }
+
+    #[test]
+    fn skeleton_for_each_field_shape() {
+        let variants = [
+            variant("Empty", FieldSpec::Unit),
+            variant("One", FieldSpec::Tuple(1)),
+            variant("Pair", FieldSpec::Tuple(2)),
+            variant("Point", FieldSpec::Named(vec!["x".to_string(), "y".to_string()])),
+        ];
+        assert_eq!(
+            generate_match_skeleton("Shape", &variants),
+            "match value {
+    Shape::Empty => todo!(),
+    Shape::One(one) => todo!(),
+    Shape::Pair(a, b) => todo!(),
+    Shape::Point { x, y } => todo!(),
+}"
+        );
+    }
+
+    #[test]
+    fn keyword_variant_names_get_valid_bindings() {
+        let variants = [
+            variant("Move", FieldSpec::Tuple(1)),
+            variant("Type", FieldSpec::Tuple(1)),
+            variant("Crate", FieldSpec::Tuple(1)),
+            variant("ChangeColor", FieldSpec::Tuple(1)),
+        ];
+        assert_eq!(
+            generate_match_skeleton("Command", &variants),
+            "match value {
+    Command::Move(r#move) => todo!(),
+    Command::Type(r#type) => todo!(),
+    Command::Crate(value) => todo!(),
+    Command::ChangeColor(change_color) => todo!(),
+}"
+        );
+    }
+
+    // 上面两个测试里期望的骨架原样放进函数里，能编译通过就说明生成的代码是合法的
+    #[allow(dead_code)]
+    enum Command {
+        Move(i32),
+        Type(String),
+        Crate(u8),
+        ChangeColor(u32),
+    }
+
+    #[allow(dead_code, unused_variables, unreachable_code)]
+    fn command_skeleton_compiles(value: Command) {
+        match value {
+            Command::Move(r#move) => todo!(),
+            Command::Type(r#type) => todo!(),
+            Command::Crate(value) => todo!(),
+            Command::ChangeColor(change_color) => todo!(),
+        }
+    }
+
+    #[allow(dead_code, unused_variables, unreachable_code)]
+    fn coin_skeleton_compiles(value: Coin) {
+        match value {
+            Coin::Penny => todo!(),
+            Coin::Nickel => todo!(),
+            Coin::Dime => todo!(),
+            Coin::Quarter(quarter) => todo!(),
+        }
+    }
+
+    #[test]
+    fn coin_parsed_from_its_source_matches_the_hand_written_skeleton() {
+        let coin_source = "
+            enum Coin {
+                Penny,
+                Nickel,
+                Dime,
+                Quarter(UsState), // Quarter 变体包含一个 UsState 类型的数据
+            }";
+        let variants = parse_enum_source(coin_source).unwrap();
+        assert_eq!(variants[3], variant("Quarter", FieldSpec::Tuple(1)));
+        assert_eq!(generate_match_skeleton("Coin", &variants), COIN_SKELETON);
+    }
+
+    #[test]
+    fn message_is_parsed_past_doc_comments_and_derives() {
+        let message_source = "
+            /// 概念讲解中的 Message 枚举
+            #[derive(Debug, Clone)]
+            enum Message {
+                /* 退出 */ Quit,
+                Move { x: i32, y: i32 },
+                Write(String),
+                ChangeColor(i32, i32, i32),
+            }";
+        assert_eq!(
+            parse_enum_source(message_source),
+            Ok(vec![
+                variant("Quit", FieldSpec::Unit),
+                variant("Move", FieldSpec::Named(vec!["x".to_string(), "y".to_string()])),
+                variant("Write", FieldSpec::Tuple(1)),
+                variant("ChangeColor", FieldSpec::Tuple(3)),
+            ])
+        );
+    }
+
+    #[test]
+    fn function_pointer_fields_are_not_angle_brackets() {
+        let source = "enum Handler { Call(fn(u8, u8) -> u8, Vec<u8>), Map { f: fn() -> Option<u8>, keys: HashMap<String, u8> } }";
+        assert_eq!(
+            parse_enum_source(source),
+            Ok(vec![
+                variant("Call", FieldSpec::Tuple(2)),
+                variant("Map", FieldSpec::Named(vec!["f".to_string(), "keys".to_string()])),
+            ])
+        );
+        assert_eq!(split_top_level("Box<dyn Fn() -> u8>, u8"), vec!["Box<dyn Fn() -> u8>", "u8"]);
+    }
+
+    #[test]
+    fn generic_enums_and_broken_source_are_rejected() {
+        assert_eq!(
+            parse_enum_source("enum Option<T> { None, Some(T) }"),
+            Err(EnumParseError::GenericsNotSupported("Option".to_string()))
+        );
+        assert_eq!(parse_enum_source("struct Point { x: i32 }"), Err(EnumParseError::MissingEnumKeyword));
+        assert_eq!(parse_enum_source("enum Light { Red, Green"), Err(EnumParseError::UnbalancedBraces));
+        assert_eq!(parse_enum_source("enum Light { Red, Green: u8 }"), Err(EnumParseError::InvalidVariant("Green: u8".to_string())));
+    }
+
+    #[test]
+    fn pasted_enum_is_read_up_to_the_blank_line() {
+        let pasted = "enum Light {\n    Red,\n    Green(u8),\n}\n\nthis line is not read\n";
+        let src = read_pasted_enum(&mut io::Cursor::new(pasted)).unwrap();
+        assert_eq!(src, "enum Light {\n    Red,\n    Green(u8),\n}\n");
+        assert_eq!(skeleton_from_source(&src), Ok("match value {\n    Light::Red => todo!(),\n    Light::Green(green) => todo!(),\n}".to_string()));
+        // 没有粘贴任何东西
+        assert_eq!(read_pasted_enum(&mut io::Cursor::new("")).unwrap(), "");
+    }
+
+    // 练习4
+    #[test]
+    fn oscillating_between_thresholds_raises_and_clears_once() {
+        let mut monitor = AlertMonitor::new(30.0, 25.0).unwrap();
+        // 在 25 和 30 之间来回波动，只会报警一次、解除一次
+        let transitions = monitor.observe_series(&[20.0, 31.0, 28.0, 31.0, 26.0, 29.9, 26.0, 24.0, 27.0]);
+        assert_eq!(transitions, vec![(1, Transition::Raised), (7, Transition::Cleared)]);
+        assert_eq!(monitor.state, AlertState::Normal);
+    }
+
+    #[test]
+    fn values_equal_to_a_threshold_do_not_change_the_state() {
+        // 两个阈值都不含等于：正好等于报警阈值不会报警，正好等于解除阈值不会解除
+        let mut monitor = AlertMonitor::new(30.0, 25.0).unwrap();
+        assert_eq!(monitor.observe(30.0), None);
+        assert_eq!(monitor.state, AlertState::Normal);
+        assert_eq!(monitor.observe(30.5), Some(Transition::Raised));
+        assert_eq!(monitor.observe(25.0), None);
+        assert_eq!(monitor.state, AlertState::Active);
+        assert_eq!(monitor.observe(24.9), Some(Transition::Cleared));
+    }
+
+    #[test]
+    fn starting_above_the_rising_threshold_raises_immediately() {
+        let mut hot = AlertMonitor::new(30.0, 25.0).unwrap();
+        assert_eq!(hot.observe(35.0), Some(Transition::Raised));
+        assert_eq!(hot.state, AlertState::Active);
+        // 已经在报警，继续升高不会重复报警
+        assert_eq!(hot.observe(40.0), None);
+    }
+
+    #[test]
+    fn nan_observations_are_skipped() {
+        let mut monitor = AlertMonitor::new(30.0, 25.0).unwrap();
+        assert_eq!(monitor.observe(f64::NAN), None);
+        assert_eq!(monitor.state, AlertState::Normal);
+        let transitions = monitor.observe_series(&[31.0, f64::NAN, 20.0]);
+        assert_eq!(transitions, vec![(0, Transition::Raised), (2, Transition::Cleared)]);
+    }
+
+    #[test]
+    fn constructor_requires_clearing_below_rising() {
+        assert_eq!(AlertMonitor::new(25.0, 30.0).unwrap_err(), "解除阈值 (30) 必须小于报警阈值 (25)");
+        assert!(AlertMonitor::new(25.0, 25.0).is_err());
+        assert!(AlertMonitor::new(f64::NAN, 25.0).is_err());
+        assert!(AlertMonitor::new(30.0, f64::NAN).is_err());
+        assert!(AlertMonitor::new(30.0, 25.0).is_ok());
+    }
+
+    #[test]
+    fn temperature_alerts_report_day_numbers() {
+        let week = [(1, 22.0), (2, 31.5), (3, 29.0), (4, 24.0), (5, 33.0)];
+        assert_eq!(
+            temperature_alerts(&week, 30.0, 25.0),
+            Ok(vec![(2, Transition::Raised), (4, Transition::Cleared), (5, Transition::Raised)])
+        );
+        assert!(temperature_alerts(&week, 25.0, 30.0).is_err());
+    }
+}
+
 /*
  * =====================================================================================
  * 练习挑战 (Challenge Section)
@@ -186,4 +801,21 @@ fn get_duration(t1:TrafficLight) -> u8{
  *    如果变量是 `None`，则什么也不做。
  *    尝试用 `Some` 和 `None` 两种情况来测试你的代码。
  *
+ * 3. 生成 match 骨架:
+ *    定义 `enum FieldSpec { Unit, Tuple(usize), Named(Vec<String>) }` 和
+ *    `struct VariantSpec { name: String, fields: FieldSpec }`。
+ *    - 编写 `generate_match_skeleton(enum_name: &str, variants: &[VariantSpec]) -> String`，
+ *      为每个变体生成一个 `=> todo!(),` 分支，例如 `Coin::Quarter(quarter) => todo!(),`。
+ *    - 编写 `parse_enum_source(src: &str) -> Result<Vec<VariantSpec>, EnumParseError>`，
+ *      从粘贴进来的枚举定义文本中提取变体。需要跳过 `#[derive]` 和文档注释，
+ *      处理末尾的逗号和具名字段的花括号；泛型枚举不需要支持，但要返回一个明确的错误。
+ *    用本课中的 `Coin` 和概念讲解中的 `Message` 来试一试。
+ *
+ * 4. 带滞后的报警器:
+ *    定义 `enum AlertState { Normal, Active }` 和 `enum Transition { Raised, Cleared }`，
+ *    以及 `struct AlertMonitor { rising_threshold, clearing_threshold, state }`。
+ *    `new` 要检查解除阈值小于报警阈值；`observe(value)` 在超过报警阈值时返回 `Some(Raised)`，
+ *    报警状态下低于解除阈值时返回 `Some(Cleared)`，其余情况返回 `None`，这样数值在两条线之间
+ *    来回波动时不会反复报警。再实现 `observe_series` 和按天检查气温的 `temperature_alerts`。
+ *
  */
\ No newline at end of file
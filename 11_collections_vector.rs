@@ -59,7 +59,9 @@
 // 代码示例 (Code Section)
 // =====================================================================================
 
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::hash::Hash;
 
 fn main() {
     // 1. 创建 Vector
@@ -139,60 +141,608 @@ fn main() {
         Some(median) => println!("  Median is: {}", median), // 输出：3
         None => println!("  No median found."),
     }
+    println!("  {:?}", calculate_median(&[4u8, 1, 3, 2]));       // Some(2.5)，偶数个取中间两个的平均值
+    println!("  {:?}", calculate_median(&[2.5, -1.0, 9.75]));    // Some(2.5)，奇数个取正中间的那个
+    println!("  {:?}", calculate_median(&[1.0, f64::NAN, 3.0])); // None，含有 NaN
+    println!("  {:?}", calculate_median::<f32>(&[]));            // None
     // 练习2：
     match calculate_mode(&list1) {
         Some(mode) => println!("  Mode is: {}", mode),   // 输出：5
         None => println!("  No mode found."),
     }
+    println!("  {:?}", calculate_modes(&[1, 1, 2, 2, 3])); // [1, 2]，两个值都出现了两次
+    println!("  {:?}", calculate_modes(&list1));           // [5]
+    println!("  {:?}", calculate_modes(&[9, 4, 7]));       // [4, 7, 9]，每个值都只出现一次
+    println!("  {:?}", calculate_modes(&[]));              // []
+
+    // 练习3：
+    let intervals = vec![(1, 3), (2, 6), (8, 10)];
+    println!("Merged {:?} => {:?}", intervals, merge_intervals(&intervals)); // 输出：[(1, 6), (8, 10)]
+    let disjoint = vec![(1, 2), (4, 5)];
+    println!("Merged {:?} => {:?}", disjoint, merge_intervals(&disjoint)); // 输出：[(1, 2), (4, 5)]
+
+    // 练习4：
+    let ten: Vec<i32> = (1..=10).collect();
+    let parts = split_into_n(&ten, 3);
+    println!("Split into 3: {:?}", parts); // 输出：[[1, 2, 3, 4], [5, 6, 7], [8, 9, 10]]
+    println!("Split into 0: {:?}", split_into_n(&ten, 0)); // 输出：[]
+
+    // 练习5：
+    println!("{}", is_arithmetic(&[2, 4, 6, 8])); // true，公差是 2
+    println!("{}", is_arithmetic(&[1, 2, 4]));    // false
+    println!("{}", is_arithmetic(&[7]));          // true，一个元素也算等差数列
+
+    // 练习6：
+    println!("{:?}", clamp_all(&[-5, 0, 3, 7, 12], 0, 10)); // [0, 0, 3, 7, 10]
+    // clamp_all(&[1, 2], 10, 0); // min > max，会 panic
+
+    // 练习7：
+    println!("{}", lerp(0.0, 10.0, 0.5));  // 5
+    println!("{}", lerp(0.0, 10.0, 1.5));  // 10，t 被限制在 0..=1
+    println!("{:?}", lerp_slice(&[0.0, 2.0], &[10.0, 4.0], 0.5)); // Ok([5.0, 3.0])
+    println!("{:?}", lerp_slice(&[0.0], &[1.0, 2.0], 0.5));       // Err("长度不一致: 1 和 2")
+
+    // 练习8：
+    let data = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+    println!("{:?}", std_deviation(&data)); // Some(2.0)，平均值 5，方差 (9+1+1+1+0+0+4+16)/8 = 4
+    let sd = std_deviation(&[1.0, 2.0, 3.0, 4.0]).unwrap();
+    println!("{}", (sd - 1.118033988749895).abs() < 1e-12); // true，sqrt(1.25)
+    println!("{:?}", std_deviation(&[])); // None
+
+    // 练习9：
+    let letters: Counter<char> = "mississippi".chars().collect();
+    println!("{} {} {}", letters.count(&'s'), letters.count(&'p'), letters.count(&'z')); // 4 2 0
+    println!("{:?}", letters.most_common(2)); // [('i', 4), ('s', 4)]，次数相同时先出现的 'i' 排在前面
+    let mut stock = Counter::new();
+    stock.add_n("apple", 3);
+    stock.add("pear");
+    let sold: Counter<&str> = ["apple", "apple", "apple", "apple", "pear"].into_iter().collect();
+    stock.subtract(&sold);
+    println!("{:?}", stock.most_common(10)); // []，减到 0 的条目被删掉，不会变成负数
+    let merged = &letters + &"sip".chars().collect();
+    println!("{:?}", merged.most_common(3)); // [('i', 5), ('s', 5), ('p', 3)]
+    println!("{:?}", Counter::<i32>::new().most_common(3)); // []
+    println!("{:?}", calculate_mode(&[3, 1, 1, 3])); // Some(3)，次数相同时返回先出现的那个
+
+    // 练习10：
+    println!("{:?}", median_of_sorted(&[1, 3], &[2]));       // Some(2.0)
+    println!("{:?}", median_of_sorted(&[1, 2], &[3, 4]));    // Some(2.5)
+    println!("{:?}", median_of_sorted(&[], &[]));            // None
+
+    // 练习11：
+    println!("{:?}", find_peaks(&[1, 3, 2, 4, 1])); // [1, 3]
+    println!("{:?}", find_peaks(&[1, 2, 3, 4]));    // [3]，单调递增时只有最后一个是峰值
+    println!("{:?}", find_peaks(&[5, 4, 3]));       // [0]
+    println!("{:?}", find_peaks(&[2, 2, 2]));       // []，必须严格大于邻居
+    println!("{:?}", find_peaks(&[]));              // []
 }
 
-fn calculate_median(numbers: &[i32]) -> Option<f64>{
-    if numbers.is_empty(){
+// 泛型版本：i32、u8、f32、f64 等能无损转换成 f64 的类型都可以用
+fn calculate_median<T>(numbers: &[T]) -> Option<f64>
+where
+    T: Copy + Into<f64> + PartialOrd,
+{
+    // NaN 和任何值比较都没有大小关系，排序的结果没有意义，所以含有 NaN 时直接返回 None
+    if numbers.is_empty() || numbers.iter().any(|&n| n.into().is_nan()) {
         return None;
     }
 
     let mut sorted_numbers = numbers.to_vec();
 
-    sorted_numbers.sort_unstable();
+    // 浮点数没有实现 Ord，不能用 sort_unstable()，只能用 partial_cmp 比较。
+    // 上面已经排除了 NaN，partial_cmp 不会返回 None
+    sorted_numbers.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
     let len = sorted_numbers.len();
     let mid_index = len / 2;
 
-    if len % 2 == 0 {
-        let mid1 = sorted_numbers[mid_index -1] as f64;
-        let mid2 = sorted_numbers[mid_index] as f64;
+    if len.is_multiple_of(2) {
+        let mid1: f64 = sorted_numbers[mid_index - 1].into();
+        let mid2: f64 = sorted_numbers[mid_index].into();
         Some((mid1 + mid2) / 2.0)
     }else{
-        Some(sorted_numbers[mid_index] as f64)
+        Some(sorted_numbers[mid_index].into())
     }
 
 }
-// 思路：用hashmap记录所有元素的出现次数，出现次数最多的元素即为众数
+// 思路：用hashmap记录所有元素的出现次数，出现次数最多的元素即为众数。
+// 计数交给 Counter（练习9），出现次数相同时返回最先出现的那个
 fn calculate_mode(numbers: &[i32]) -> Option<i32>{
-    if numbers.is_empty(){
+    let counts: Counter<i32> = numbers.iter().copied().collect();
+    // most_common(1) 取出次数最多的一个；numbers 为空时结果也为空，返回 None
+    counts.most_common(1).first().map(|&(&num, _)| num)
+}
+
+// calculate_mode 在次数相同时只返回一个，这个版本返回所有出现次数最多的值，从小到大排序
+fn calculate_modes(numbers: &[i32]) -> Vec<i32> {
+    let counts: Counter<i32> = numbers.iter().copied().collect();
+    // most_common 按次数从多到少排好了，第一个的次数就是最大次数；numbers 为空时 all 也为空
+    let all = counts.most_common(numbers.len());
+    let max = all.first().map_or(0, |&(_, count)| count);
+    let mut modes: Vec<i32> = all.iter().filter(|&&(_, count)| count == max).map(|&(&num, _)| num).collect();
+    modes.sort();
+    modes
+}
+
+// 练习3：
+// 思路：先按区间起点排序，然后从左到右扫描，
+// 如果当前区间和结果中最后一个区间重叠（或首尾相接），就把它们合并，否则作为新区间放入结果
+fn merge_intervals(intervals: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    let mut sorted = intervals.to_vec();
+    sorted.sort_by_key(|&(start, _)| start);
+
+    let mut merged: Vec<(i64, i64)> = Vec::new();
+    for (start, end) in sorted {
+        // last_mut() 返回 Option<&mut (i64, i64)>，可以直接修改结果中的最后一个区间
+        match merged.last_mut() {
+            // 重叠（例如 (1,3) 和 (2,6)）或首尾相接（例如 (1,3) 和 (3,5)）都合并
+            Some(last) if start <= last.1 => {
+                last.1 = last.1.max(end);
+            }
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+// 练习4：
+fn split_into_n<T: Clone>(slice: &[T], n: usize) -> Vec<Vec<T>> {
+    if n == 0 {
+        return Vec::new();
+    }
+    // 每块至少 base 个元素，前 extra 块各多分 1 个
+    let base = slice.len() / n;
+    let extra = slice.len() % n;
+
+    let mut chunks = Vec::with_capacity(n);
+    let mut start = 0;
+    for i in 0..n {
+        let size = if i < extra { base + 1 } else { base };
+        // to_vec() 会克隆切片中的元素，这就是需要 T: Clone 的原因
+        chunks.push(slice[start..start + size].to_vec());
+        start += size;
+    }
+    chunks
+}
+
+// 练习5：
+fn is_arithmetic(nums: &[i64]) -> bool {
+    // 少于两个元素时没有“差”可以比较，直接认为是等差数列
+    if nums.len() < 2 {
+        return true;
+    }
+    // windows(2) 依次给出相邻的两个元素，检查每一对的差是否都等于第一对的差。
+    // 两个 i64 的差可能超出 i64 的范围（例如 1 - i64::MIN），转成 i128 再减就不会溢出
+    let diff = |pair: &[i64]| pair[1] as i128 - pair[0] as i128;
+    let step = diff(&nums[..2]);
+    nums.windows(2).all(|pair| diff(pair) == step)
+}
+// 练习6：
+// 把每个值限制在 [min, max] 范围内，小于 min 的变成 min，大于 max 的变成 max。
+// 和标准库的 i32::clamp 一样，min > max 属于调用者的编程错误，直接 panic
+fn clamp_all(values: &[i32], min: i32, max: i32) -> Vec<i32> {
+    assert!(min <= max, "clamp_all: min ({}) 不能大于 max ({})", min, max);
+    values.iter().map(|&v| v.clamp(min, max)).collect()
+}
+// 练习7：
+// 线性插值：t = 0 时得到 a，t = 1 时得到 b，中间按比例变化
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    let t = t.clamp(0.0, 1.0);
+    a + (b - a) * t
+}
+
+// 对两个切片逐个元素做插值，长度不同时返回错误
+fn lerp_slice(a: &[f64], b: &[f64], t: f64) -> Result<Vec<f64>, String> {
+    if a.len() != b.len() {
+        return Err(format!("长度不一致: {} 和 {}", a.len(), b.len()));
+    }
+    Ok(a.iter().zip(b.iter()).map(|(&x, &y)| lerp(x, y, t)).collect())
+}
+// 练习8：
+// 总体标准差：先求平均值，再求每个数与平均值之差的平方的平均值（方差），最后开平方
+fn std_deviation(numbers: &[f64]) -> Option<f64> {
+    if numbers.is_empty() {
         return None;
     }
+    let n = numbers.len() as f64;
+    let mean = numbers.iter().sum::<f64>() / n;
+    let variance = numbers.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+    Some(variance.sqrt())
+}
+// 练习9：
+// 通用的计数器：用 HashMap 记录每个元素出现的次数，同时记下第一次出现的顺序，
+// 这样 most_common 在次数相同时也能给出固定的结果
+struct Counter<T: Eq + Hash> {
+    counts: HashMap<T, (usize, usize)>,
+    next_order: usize,
+}
 
-    let mut counts = HashMap::new();
+impl<T: Eq + Hash> Counter<T> {
+    fn new() -> Self {
+        Counter { counts: HashMap::new(), next_order: 0 }
+    }
 
-    for &num in numbers{
-        //entry(num)检查num是否为map中的键
-        //or_insert 如果不存在，插入0，并且返回该值的可变引用
-        *counts.entry(num).or_insert(0)+=1;
+    fn add(&mut self, item: T) {
+        self.add_n(item, 1);
     }
-    // 现在我们需要找到计数值最大的那个条目。
-    // `counts.iter()` 创建一个迭代器。
-    // `.max_by_key(|&(_, count)| count)` 找到一个条目，其 count (值) 是最大的。
-    // `max_by_key` 返回一个 Option，因为 HashMap 可能为空（尽管我们已经处理了空列表）。
-    // `map(|(&num, _)| num)` 如果找到了最大条目，就提取出它的键（num），并返回它。
-    let mode = counts
-        .into_iter()
-        .max_by_key(|&(_, count)| count)
-        .map(|(num, _)| num);
-    
-    mode
 
+    fn add_n(&mut self, item: T, n: usize) {
+        if n == 0 {
+            return;
+        }
+        let order = self.next_order;
+        let entry = self.counts.entry(item).or_insert((0, order));
+        if entry.1 == order {
+            self.next_order += 1;
+        }
+        entry.0 += n;
+    }
+
+    fn count(&self, item: &T) -> usize {
+        self.counts.get(item).map_or(0, |&(count, _)| count)
+    }
+
+    // 次数从多到少排列的前 n 个元素；次数相同的按第一次出现的先后排列
+    fn most_common(&self, n: usize) -> Vec<(&T, usize)> {
+        let mut entries: Vec<(&T, usize, usize)> =
+            self.counts.iter().map(|(item, &(count, order))| (item, count, order)).collect();
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then(a.2.cmp(&b.2)));
+        entries.into_iter().take(n).map(|(item, count, _)| (item, count)).collect()
+    }
+
+    // 减去另一个计数器里的次数，最少减到 0，减到 0 的元素直接删掉
+    fn subtract(&mut self, other: &Counter<T>) {
+        for (item, &(count, _)) in &other.counts {
+            if let Some(entry) = self.counts.get_mut(item) {
+                entry.0 = entry.0.saturating_sub(count);
+            }
+        }
+        self.counts.retain(|_, &mut (count, _)| count > 0);
+    }
+}
+
+impl<T: Eq + Hash> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Counter::new();
+        for item in iter {
+            counter.add(item);
+        }
+        counter
+    }
+}
+
+// 两个计数器相加得到一个新的计数器：左边的元素保持原来的顺序，右边新出现的元素排在后面。
+// 这里写完整路径而不 use std::ops::Add，否则 counter.add(item) 会和 Add::add 混淆
+impl<T: Eq + Hash + Clone> std::ops::Add for &Counter<T> {
+    type Output = Counter<T>;
+
+    fn add(self, other: &Counter<T>) -> Counter<T> {
+        let mut merged = Counter::new();
+        for counter in [self, other] {
+            let mut entries: Vec<(&T, &(usize, usize))> = counter.counts.iter().collect();
+            entries.sort_by_key(|&(_, &(_, order))| order);
+            for (item, &(count, _)) in entries {
+                merged.add_n(item.clone(), count);
+            }
+        }
+        merged
+    }
+}
+
+// 练习10：
+// 思路：像归并排序的“合并”那样同时从两个切片的开头往后走，每次取较小的那个，
+// 但只走到合并后的中间位置就停下，不需要真的建出合并后的 Vec。
+// 总长度为 n 时，中位数只和第 (n-1)/2 个和第 n/2 个元素有关（n 为奇数时两者相同）
+fn median_of_sorted(a: &[i32], b: &[i32]) -> Option<f64> {
+    let total = a.len() + b.len();
+    if total == 0 {
+        return None;
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut previous = 0;
+    let mut current = 0;
+    for _ in 0..=total / 2 {
+        previous = current;
+        // b 已经取完，或者 a 还有元素并且不比 b 的当前元素大，就取 a 的
+        if j >= b.len() || (i < a.len() && a[i] <= b[j]) {
+            current = a[i];
+            i += 1;
+        } else {
+            current = b[j];
+            j += 1;
+        }
+    }
+
+    if total.is_multiple_of(2) {
+        Some((previous as f64 + current as f64) / 2.0)
+    } else {
+        Some(current as f64)
+    }
+}
+
+// 练习11：
+// 思路：对每个位置，只和实际存在的邻居比较。两端的元素只有一个邻居；
+// 只有一个元素时没有邻居，也算作峰值
+fn find_peaks(nums: &[i32]) -> Vec<usize> {
+    let mut peaks = Vec::new();
+    for (i, &value) in nums.iter().enumerate() {
+        let above_left = i == 0 || value > nums[i - 1];
+        let above_right = i + 1 == nums.len() || value > nums[i + 1];
+        if above_left && above_right {
+            peaks.push(i);
+        }
+    }
+    peaks
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 练习3
+    #[test]
+    fn merge_intervals_joins_overlapping_ranges() {
+        assert_eq!(merge_intervals(&[(1, 3), (2, 6), (8, 10)]), vec![(1, 6), (8, 10)]);
+        // 输入没有排序、一个区间完全包含另一个时也能合并
+        assert_eq!(merge_intervals(&[(8, 10), (1, 9), (2, 3)]), vec![(1, 10)]);
+        // 首尾相接也算重叠
+        assert_eq!(merge_intervals(&[(3, 5), (1, 3)]), vec![(1, 5)]);
+    }
+
+    #[test]
+    fn merge_intervals_keeps_disjoint_ranges() {
+        assert_eq!(merge_intervals(&[(1, 2), (4, 5)]), vec![(1, 2), (4, 5)]);
+        assert_eq!(merge_intervals(&[(4, 5), (1, 2)]), vec![(1, 2), (4, 5)]);
+        assert_eq!(merge_intervals(&[]), vec![]);
+    }
+
+    // 练习5
+    #[test]
+    fn is_arithmetic_checks_every_difference() {
+        assert!(is_arithmetic(&[2, 4, 6, 8]));
+        assert!(is_arithmetic(&[9, 6, 3, 0, -3]));
+        assert!(!is_arithmetic(&[1, 2, 4]));
+        assert!(!is_arithmetic(&[1, 3, 5, 8]));
+    }
+
+    #[test]
+    fn short_slices_are_arithmetic() {
+        assert!(is_arithmetic(&[7]));
+        assert!(is_arithmetic(&[]));
+        assert!(is_arithmetic(&[5, -5]));
+    }
+
+    #[test]
+    fn is_arithmetic_does_not_overflow_on_extreme_values() {
+        // 1 - i64::MIN 放不进 i64
+        assert!(is_arithmetic(&[i64::MIN, 1]));
+        assert!(is_arithmetic(&[i64::MIN, -1, i64::MAX - 1]));
+        assert!(!is_arithmetic(&[i64::MIN, 0, i64::MAX]));
+        assert!(!is_arithmetic(&[i64::MAX, i64::MIN, i64::MAX]));
+    }
+
+    // 练习9
+    #[test]
+    fn most_common_breaks_ties_by_first_appearance() {
+        let mut counter = Counter::new();
+        for word in ["pear", "apple", "fig", "apple", "pear", "kiwi"] {
+            counter.add(word);
+        }
+        // pear 比 apple 先出现；fig 和 kiwi 也一样
+        assert_eq!(counter.most_common(4), vec![(&"pear", 2), (&"apple", 2), (&"fig", 1), (&"kiwi", 1)]);
+        assert_eq!(counter.most_common(1), vec![(&"pear", 2)]);
+        // 同一个顺序下再加次数，不会改变它第一次出现的位置
+        counter.add_n("kiwi", 5);
+        counter.add_n("fig", 0);
+        assert_eq!(counter.most_common(2), vec![(&"kiwi", 6), (&"pear", 2)]);
+    }
+
+    #[test]
+    fn subtract_saturates_at_zero_and_drops_empty_entries() {
+        let mut stock = Counter::new();
+        stock.add_n("apple", 3);
+        stock.add_n("pear", 2);
+        let sold: Counter<&str> = ["apple", "apple", "apple", "apple", "pear", "plum"].into_iter().collect();
+        stock.subtract(&sold);
+        assert_eq!(stock.count(&"apple"), 0);
+        assert_eq!(stock.count(&"plum"), 0);
+        assert_eq!(stock.most_common(10), vec![(&"pear", 1)]);
+    }
+
+    #[test]
+    fn counter_collects_chars_of_a_string() {
+        let letters: Counter<char> = "mississippi".chars().collect();
+        assert_eq!((letters.count(&'i'), letters.count(&'s'), letters.count(&'p'), letters.count(&'m')), (4, 4, 2, 1));
+        assert_eq!(letters.count(&'z'), 0);
+        assert_eq!(letters.most_common(2), vec![(&'i', 4), (&'s', 4)]);
+    }
+
+    #[test]
+    fn adding_counters_sums_counts_and_keeps_left_order_first() {
+        let left: Counter<char> = "mississippi".chars().collect();
+        let right: Counter<char> = "zips".chars().collect();
+        let merged = &left + &right;
+        assert_eq!(merged.most_common(5), vec![(&'i', 5), (&'s', 5), (&'p', 3), (&'m', 1), (&'z', 1)]);
+        // 两边都不变
+        assert_eq!(left.count(&'i'), 4);
+        assert_eq!(right.count(&'z'), 1);
+    }
+
+    #[test]
+    fn empty_counter_has_no_most_common() {
+        let empty = Counter::<i32>::new();
+        assert!(empty.most_common(3).is_empty());
+        assert_eq!(empty.count(&1), 0);
+        assert!((&empty + &Counter::new()).most_common(1).is_empty());
+    }
+
+    // 练习4
+    #[test]
+    fn split_into_n_gives_extra_elements_to_the_first_chunks() {
+        let ten: Vec<i32> = (1..=10).collect();
+        let parts = split_into_n(&ten, 3);
+        assert_eq!(parts, vec![vec![1, 2, 3, 4], vec![5, 6, 7], vec![8, 9, 10]]);
+        assert_eq!(split_into_n(&ten, 4).iter().map(Vec::len).collect::<Vec<_>>(), vec![3, 3, 2, 2]);
+        assert_eq!(split_into_n(&ten, 5), vec![vec![1, 2], vec![3, 4], vec![5, 6], vec![7, 8], vec![9, 10]]);
+    }
+
+    #[test]
+    fn split_into_zero_parts_is_empty() {
+        assert_eq!(split_into_n(&[1, 2, 3], 0), Vec::<Vec<i32>>::new());
+        assert_eq!(split_into_n::<i32>(&[], 0), Vec::<Vec<i32>>::new());
+    }
+
+    #[test]
+    fn split_into_more_parts_than_elements_pads_with_empty_chunks() {
+        assert_eq!(split_into_n(&["a", "b"], 4), vec![vec!["a"], vec!["b"], vec![], vec![]]);
+        assert_eq!(split_into_n::<i32>(&[], 2), vec![vec![], vec![]]);
+    }
+
+    // 练习6
+    #[test]
+    fn clamp_all_limits_values_below_within_and_above() {
+        assert_eq!(clamp_all(&[-5, 0, 3, 7, 12], 0, 10), vec![0, 0, 3, 7, 10]);
+        assert_eq!(clamp_all(&[i32::MIN, i32::MAX], -1, 1), vec![-1, 1]);
+        // min == max 时所有值都变成同一个数
+        assert_eq!(clamp_all(&[1, 5, 9], 4, 4), vec![4, 4, 4]);
+        assert_eq!(clamp_all(&[], 0, 10), Vec::<i32>::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "不能大于 max")]
+    fn clamp_all_panics_when_min_is_greater_than_max() {
+        clamp_all(&[1, 2], 10, 0);
+    }
+
+    // 练习7
+    #[test]
+    fn lerp_interpolates_and_clamps_t() {
+        assert_eq!(lerp(0.0, 10.0, 0.5), 5.0);
+        assert_eq!(lerp(-4.0, 4.0, 0.25), -2.0);
+        assert_eq!(lerp(0.0, 10.0, 0.0), 0.0);
+        assert_eq!(lerp(0.0, 10.0, 1.0), 10.0);
+        // t 被限制在 0..=1
+        assert_eq!(lerp(0.0, 10.0, 1.5), 10.0);
+        assert_eq!(lerp(0.0, 10.0, -1.0), 0.0);
+    }
+
+    #[test]
+    fn lerp_slice_works_element_by_element() {
+        assert_eq!(lerp_slice(&[0.0, 2.0], &[10.0, 4.0], 0.5), Ok(vec![5.0, 3.0]));
+        assert_eq!(lerp_slice(&[], &[], 0.5), Ok(vec![]));
+    }
+
+    #[test]
+    fn lerp_slice_rejects_different_lengths() {
+        assert_eq!(lerp_slice(&[0.0], &[1.0, 2.0], 0.5), Err(String::from("长度不一致: 1 和 2")));
+    }
+
+    // 练习8
+    #[test]
+    fn std_deviation_of_a_known_dataset() {
+        // 平均值 5，方差 (9+1+1+1+0+0+4+16)/8 = 4
+        assert_eq!(std_deviation(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]), Some(2.0));
+        // sqrt(1.25)
+        let sd = std_deviation(&[1.0, 2.0, 3.0, 4.0]).unwrap();
+        assert!((sd - 1.118033988749895).abs() < 1e-12);
+    }
+
+    #[test]
+    fn std_deviation_of_empty_and_single_inputs() {
+        assert_eq!(std_deviation(&[]), None);
+        assert_eq!(std_deviation(&[42.0]), Some(0.0));
+        assert_eq!(std_deviation(&[3.5, 3.5, 3.5]), Some(0.0));
+    }
+
+    // 练习10
+    #[test]
+    fn median_of_sorted_small_cases() {
+        assert_eq!(median_of_sorted(&[1, 3], &[2]), Some(2.0));
+        assert_eq!(median_of_sorted(&[1, 2], &[3, 4]), Some(2.5));
+        assert_eq!(median_of_sorted(&[], &[]), None);
+    }
+
+    #[test]
+    fn median_of_sorted_matches_concatenate_and_sort() {
+        let pairs: [(&[i32], &[i32]); 8] = [
+            (&[1, 3], &[2]),
+            (&[], &[5]),
+            (&[-4, 0, 9], &[]),
+            (&[1, 1, 1], &[1, 1]),
+            (&[1, 2, 3, 4], &[10, 20]),
+            (&[10, 20], &[1, 2, 3, 4]),
+            (&[-7, 2, 2, 8], &[-3, 2, 5, 6, 11]),
+            (&[i32::MIN, i32::MAX], &[0]),
+        ];
+        for (a, b) in pairs {
+            let combined = [a, b].concat();
+            assert_eq!(median_of_sorted(a, b), calculate_median(&combined), "{:?} {:?}", a, b);
+        }
+    }
+
+    // 练习11
+    #[test]
+    fn find_peaks_compares_with_both_neighbours() {
+        assert_eq!(find_peaks(&[1, 3, 2, 4, 1]), vec![1, 3]);
+        // 必须严格大于邻居
+        assert_eq!(find_peaks(&[2, 2, 2]), Vec::<usize>::new());
+        assert_eq!(find_peaks(&[1, 3, 3, 1]), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn find_peaks_on_monotonic_slices_returns_an_end() {
+        // 两端的元素只和一个邻居比较
+        assert_eq!(find_peaks(&[1, 2, 3, 4]), vec![3]);
+        assert_eq!(find_peaks(&[5, 4, 3]), vec![0]);
+    }
+
+    #[test]
+    fn find_peaks_on_tiny_slices() {
+        assert_eq!(find_peaks(&[]), Vec::<usize>::new());
+        // 只有一个元素时没有邻居，也算作峰值
+        assert_eq!(find_peaks(&[7]), vec![0]);
+    }
+
+    // 练习1
+    #[test]
+    fn median_of_odd_length_takes_the_middle_value() {
+        assert_eq!(calculate_median(&[5, 1, 2, 5, 3, 5, 2]), Some(3.0));
+        assert_eq!(calculate_median(&[2.5, -1.0, 9.75]), Some(2.5));
+        assert_eq!(calculate_median(&[7u8]), Some(7.0));
+    }
+
+    #[test]
+    fn median_of_even_length_averages_the_middle_two() {
+        assert_eq!(calculate_median(&[4u8, 1, 3, 2]), Some(2.5));
+        assert_eq!(calculate_median(&[-3, 3]), Some(0.0));
+        assert_eq!(calculate_median(&[0.5f32, 0.25]), Some(0.375));
+    }
+
+    #[test]
+    fn median_of_empty_or_nan_input_is_none() {
+        assert_eq!(calculate_median::<f32>(&[]), None);
+        assert_eq!(calculate_median(&[1.0, f64::NAN, 3.0]), None);
+    }
+
+    // 练习2
+    #[test]
+    fn calculate_modes_of_unimodal_input() {
+        assert_eq!(calculate_modes(&[5, 1, 2, 5, 3, 5, 2]), vec![5]);
+        assert_eq!(calculate_modes(&[4, 4]), vec![4]);
+    }
+
+    #[test]
+    fn calculate_modes_of_bimodal_input_is_sorted() {
+        assert_eq!(calculate_modes(&[1, 1, 2, 2, 3]), vec![1, 2]);
+        assert_eq!(calculate_modes(&[9, 3, 9, 3, -1]), vec![3, 9]);
+    }
+
+    #[test]
+    fn calculate_modes_of_all_unique_input_returns_every_value() {
+        assert_eq!(calculate_modes(&[9, 4, 7]), vec![4, 7, 9]);
+        assert_eq!(calculate_modes(&[]), Vec::<i32>::new());
+    }
+}
 /*
  * =====================================================================================
  * 练习挑战 (Challenge Section)
@@ -201,6 +751,10 @@ fn calculate_mode(numbers: &[i32]) -> Option<i32>{
  * 1. 计算中位数和众数:
  *    给定一个整数 `Vec`，编写两个函数：一个返回中位数（排序后中间位置的值），
  *    另一个返回众数（出现次数最多的值；提示：使用HashMap会更容易，但也可以尝试只用Vec解决）。
+ *    进阶：把中位数函数改成泛型 `calculate_median<T: Copy + Into<f64> + PartialOrd>(numbers: &[T]) -> Option<f64>`，
+ *    让 `i32`、`u8`、`f64` 都能用。浮点数要用 `sort_by` 和 `partial_cmp` 排序，并且想想遇到 NaN 该怎么办。
+ *    众数可能不止一个：再写一个 `calculate_modes(numbers: &[i32]) -> Vec<i32>`，返回所有出现次数最多的值，
+ *    从小到大排序。例如 `[1, 1, 2, 2, 3]` 返回 `[1, 2]`，空切片返回空的 `Vec`。
  *
  * 2. 字符串转换成 Pig Latin:
  *    编写一个函数，它接收一个字符串切片 `&str`，并将其中的每个单词转换成 Pig Latin 形式。
@@ -209,4 +763,49 @@ fn calculate_mode(numbers: &[i32]) -> Option<i32>{
  *    例如, "first" -> "irst-fay", "apple" -> "apple-hay"。
  *    函数应该返回一个新的 `String`。
  *
+ * 3. 合并重叠区间:
+ *    编写函数 `merge_intervals(intervals: &[(i64, i64)]) -> Vec<(i64, i64)>`，
+ *    先按起点排序，再把重叠或相邻的区间合并成最少的覆盖区间。
+ *    例如, `[(1,3),(2,6),(8,10)]` -> `[(1,6),(8,10)]`。
+ *
+ * 4. 平均切分:
+ *    编写泛型函数 `split_into_n<T: Clone>(slice: &[T], n: usize) -> Vec<Vec<T>>`，
+ *    把切片尽量平均地分成 `n` 块，多出来的元素依次分给前面的块。
+ *    例如 10 个元素分成 3 块，大小分别是 4、3、3。`n == 0` 时返回空的 `Vec`。
+ *
+ * 5. 等差数列:
+ *    编写函数 `is_arithmetic(nums: &[i64]) -> bool`，判断相邻元素的差是否都相同。
+ *    长度为 0 或 1 的切片返回 `true`。
+ *    例如 [2, 4, 6, 8] 返回 true，[1, 2, 4] 返回 false。
+ *    提示：`windows(2)` 可以依次取出相邻的两个元素。
+ *
+ * 6. 把所有值限制在范围内:
+ *    编写函数 `clamp_all(values: &[i32], min: i32, max: i32) -> Vec<i32>`，
+ *    返回一个新的 `Vec`，其中每个值都被限制在 [min, max] 之间。`min > max` 时应该 panic。
+ *    例如 `clamp_all(&[-5, 0, 3, 7, 12], 0, 10)` 返回 `[0, 0, 3, 7, 10]`。
+ *
+ * 7. 线性插值:
+ *    编写函数 `lerp(a: f64, b: f64, t: f64) -> f64`，返回 `a + (b - a) * t`，其中 `t` 先被限制在 0..=1。
+ *    再编写 `lerp_slice(a: &[f64], b: &[f64], t: f64) -> Result<Vec<f64>, String>`，
+ *    对两个长度相同的切片逐个元素插值；长度不同时返回 `Err`。
+ *
+ * 8. 标准差:
+ *    编写函数 `std_deviation(numbers: &[f64]) -> Option<f64>`，返回总体标准差（方差的平方根），
+ *    空切片返回 `None`。例如 [2, 4, 4, 4, 5, 5, 7, 9] 的标准差是 2。
+ *
+ * 9. 通用计数器:
+ *    定义 `Counter<T: Eq + Hash>`，提供 `add`/`add_n`、`count`、`most_common(n)`（次数从多到少，
+ *    次数相同的按第一次出现的顺序）、`subtract`（最少减到 0，减到 0 的元素删掉），
+ *    并实现 `FromIterator<T>` 和 `Add`（`&a + &b` 合并两个计数器）。
+ *    然后用它重写 `calculate_mode`。
+ *
+ * 10. 两个有序切片的中位数:
+ *    编写函数 `median_of_sorted(a: &[i32], b: &[i32]) -> Option<f64>`，`a` 和 `b` 都已经从小到大排好序，
+ *    返回把它们合在一起之后的中位数；两个都为空时返回 `None`。
+ *    不要真的合并出一个新的 `Vec`：像归并那样同时扫描两个切片，走到中间位置就停下。
+ *
+ * 11. 找出峰值:
+ *    编写函数 `find_peaks(nums: &[i32]) -> Vec<usize>`，返回所有严格大于左右邻居的元素的下标；
+ *    两端的元素只和它唯一的邻居比较。例如 `[1, 3, 2, 4, 1]` 返回 `[1, 3]`。
+ *
  */
\ No newline at end of file
@@ -116,7 +116,42 @@ fn main() {
     println!("{}",reverse_str("abc"));
 
     // 练习2：
-    println!("{}",check_str("acca"))
+    println!("{}",check_str("acca"));
+
+    // 练习3：
+    println!("{}", is_valid_isbn10("0-8044-2957-X")); // true，最后一位 X 代表 10；更多的例子见文件末尾的单元测试
+
+    // 练习4：
+    for n in [0, 21, 100, 1234, 3_000_017] {
+        println!("{} -> {}", n, number_to_words(n));
+    }
+    // 输出：
+    // 0 -> zero
+    // 21 -> twenty-one
+    // 100 -> one hundred
+    // 1234 -> one thousand two hundred thirty-four
+    // 3000017 -> three million seventeen
+
+    // 练习5：
+    println!("{}", longest_palindrome_substring("babad")); // bab（"aba" 一样长，但 "bab" 先出现）
+    println!("{}", longest_palindrome_substring("cbbd"));  // bb
+    println!("{}", longest_palindrome_substring("x"));     // x
+    println!("{:?}", longest_palindrome_substring(""));    // ""
+    println!("{}", longest_palindrome_substring("上海自来水来自海上")); // 上海自来水来自海上
+
+    // 练习6：
+    println!("{}", is_rotation("waterbottle", "erbottlewat")); // true
+    println!("{}", is_rotation("waterbottle", "bottlewater")); // true
+    println!("{}", is_rotation("waterbottle", "erbottlewta")); // false，字母相同但顺序不对
+    println!("{}", is_rotation("abc", "abcabc"));              // false，长度不同
+    println!("{}", is_rotation("你好世界", "世界你好"));         // true
+    println!("{}", is_rotation("", ""));                       // true
+
+    // 练习7：
+    let words = ["apple", "Apricot", "banana", "APPLET", "avocado"];
+    println!("{:?}", autocomplete(&words, "ap")); // ["apple", "APPLET", "Apricot"]
+    println!("{:?}", autocomplete(&words, "AP")); // 同上，大小写都不影响
+    println!("{:?}", autocomplete(&words, "kiwi")); // []
 }
 
 fn reverse_str(s:&str) -> String{
@@ -144,6 +179,298 @@ fn check_str(s: &str) -> bool{
     clearStr == backward
     
 }
+
+// 练习3：
+fn is_valid_isbn10(s: &str) -> bool {
+    // 1. 去掉所有连字符，剩下的必须正好是 10 个字符
+    let digits: Vec<char> = s.chars().filter(|&c| c != '-').collect();
+    if digits.len() != 10 {
+        return false;
+    }
+
+    // 2. 计算加权和：第 1 位乘以 10，第 2 位乘以 9，……，第 10 位乘以 1
+    let mut sum = 0;
+    for (i, &c) in digits.iter().enumerate() {
+        let value = match c {
+            // to_digit(10) 把 '0'..='9' 转成 0..=9
+            '0'..='9' => c.to_digit(10).unwrap(),
+            // 'X' 只允许出现在最后一位，代表 10
+            'X' | 'x' if i == 9 => 10,
+            _ => return false,
+        };
+        sum += value * (10 - i as u32);
+    }
+
+    // 3. 加权和能被 11 整除才是有效的 ISBN-10
+    sum % 11 == 0
+}
+
+// 练习4：
+// 把 0..=999 的数字转成英文单词
+fn below_thousand_to_words(n: u64) -> String {
+    let ones = [
+        "", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+        "ten", "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen",
+        "seventeen", "eighteen", "nineteen",
+    ];
+    let tens = [
+        "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+    ];
+
+    let mut parts: Vec<String> = Vec::new();
+    let hundreds = n / 100;
+    let rest = n % 100;
+    if hundreds > 0 {
+        parts.push(format!("{} hundred", ones[hundreds as usize]));
+    }
+    // 用 match 区分三种情况：0、小于 20（有专门的单词）、20 及以上（十位 + 连字符 + 个位）
+    match rest {
+        0 => {}
+        1..=19 => parts.push(ones[rest as usize].to_string()),
+        _ => {
+            let t = tens[(rest / 10) as usize];
+            match rest % 10 {
+                0 => parts.push(t.to_string()),
+                o => parts.push(format!("{}-{}", t, ones[o as usize])),
+            }
+        }
+    }
+    parts.join(" ")
+}
+
+fn number_to_words(n: u64) -> String {
+    if n == 0 {
+        return String::from("zero");
+    }
+    let scales = ["", "thousand", "million", "billion", "trillion", "quadrillion", "quintillion"];
+
+    // 每三位一组，从低位到高位拆分：1234 -> [234, 1]
+    let mut groups = Vec::new();
+    let mut rest = n;
+    while rest > 0 {
+        groups.push(rest % 1000);
+        rest /= 1000;
+    }
+
+    // 从最高的一组开始拼接，值为 0 的组直接跳过（例如 3,000,017 中间的 000）
+    let mut parts: Vec<String> = Vec::new();
+    for (i, &group) in groups.iter().enumerate().rev() {
+        if group == 0 {
+            continue;
+        }
+        let words = below_thousand_to_words(group);
+        if scales[i].is_empty() {
+            parts.push(words);
+        } else {
+            parts.push(format!("{} {}", words, scales[i]));
+        }
+    }
+    parts.join(" ")
+}
+// 练习5：
+// 中心扩展法：回文一定是从某个“中心”向两边对称展开的。
+// 长度为 n 的字符串有 2n-1 个中心（n 个字符本身，加上 n-1 个相邻字符之间的空隙）。
+// 按 char 而不是按字节处理，这样中文等多字节字符也不会被切坏。
+fn longest_palindrome_substring(s: &str) -> String {
+    let chars: Vec<char> = s.chars().collect();
+    if chars.is_empty() {
+        return String::new();
+    }
+
+    // 从 [left, right] 开始向两边扩展，返回能扩展到的最大回文的 (起点, 长度)
+    let expand = |mut left: usize, mut right: usize| -> (usize, usize) {
+        if chars[left] != chars[right] {
+            return (left, 0);
+        }
+        while left > 0 && right + 1 < chars.len() && chars[left - 1] == chars[right + 1] {
+            left -= 1;
+            right += 1;
+        }
+        (left, right - left + 1)
+    };
+
+    let mut best = (0, 1);
+    for center in 0..chars.len() {
+        let odd = expand(center, center);
+        // 只有更长时才替换，所以长度相同时保留最先出现的那个
+        if odd.1 > best.1 {
+            best = odd;
+        }
+        if center + 1 < chars.len() {
+            let even = expand(center, center + 1);
+            if even.1 > best.1 {
+                best = even;
+            }
+        }
+    }
+    chars[best.0..best.0 + best.1].iter().collect()
+}
+
+// 练习6：
+// 如果 b 是 a 旋转得到的，那么 b 一定出现在 a + a 里面，例如 "erbottlewat" 在 "waterbottlewaterbottle" 中。
+// 长度按字节比较就够了：UTF-8 的编码方式保证一个合法的字符串只会在字符边界上匹配另一个合法的字符串，
+// 不会出现从某个字符中间开始匹配的情况，所以多字节字符也不会出错
+fn is_rotation(a: &str, b: &str) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let doubled = format!("{}{}", a, a);
+    doubled.contains(b)
+}
+
+// 练习7：
+// 比较时把单词和前缀都转成小写，返回的仍然是原来的写法；
+// 排序也忽略大小写，只有小写后相同时才按原来的写法排，保证结果固定
+fn autocomplete(words: &[&str], prefix: &str) -> Vec<String> {
+    let prefix = prefix.to_lowercase();
+    let mut matches: Vec<String> = words
+        .iter()
+        .filter(|word| word.to_lowercase().starts_with(&prefix))
+        .map(|word| word.to_string())
+        .collect();
+    matches.sort_by(|a, b| a.to_lowercase().cmp(&b.to_lowercase()).then_with(|| a.cmp(b)));
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 练习3
+    #[test]
+    fn isbn10_accepts_valid_numbers_with_or_without_hyphens() {
+        assert!(is_valid_isbn10("0-306-40615-2"));
+        assert!(is_valid_isbn10("0306406152"));
+        assert!(is_valid_isbn10("3-598-21508-8"));
+    }
+
+    #[test]
+    fn isbn10_accepts_a_trailing_x_check_digit() {
+        assert!(is_valid_isbn10("0-8044-2957-X"));
+        assert!(is_valid_isbn10("080442957x"));
+        // X 只能出现在最后一位
+        assert!(!is_valid_isbn10("X-8044-2957-0"));
+        assert!(!is_valid_isbn10("0-8044-295X-7"));
+    }
+
+    #[test]
+    fn isbn10_rejects_bad_checksums_lengths_and_characters() {
+        assert!(!is_valid_isbn10("0-306-40615-3"));
+        assert!(!is_valid_isbn10("123456789"));
+        assert!(!is_valid_isbn10("03064061522"));
+        assert!(!is_valid_isbn10(""));
+        assert!(!is_valid_isbn10("0-306-4O615-2"));
+        assert!(!is_valid_isbn10("0 306 40615 2"));
+    }
+
+    // 练习4
+    #[test]
+    fn number_to_words_zero_and_small_numbers() {
+        assert_eq!(number_to_words(0), "zero");
+        assert_eq!(number_to_words(7), "seven");
+        assert_eq!(number_to_words(20), "twenty");
+        assert_eq!(number_to_words(21), "twenty-one");
+        assert_eq!(number_to_words(100), "one hundred");
+        assert_eq!(number_to_words(1234), "one thousand two hundred thirty-four");
+    }
+
+    #[test]
+    fn number_to_words_teens_use_their_own_words() {
+        assert_eq!(number_to_words(10), "ten");
+        assert_eq!(number_to_words(11), "eleven");
+        assert_eq!(number_to_words(13), "thirteen");
+        assert_eq!(number_to_words(19), "nineteen");
+        assert_eq!(number_to_words(115), "one hundred fifteen");
+        assert_eq!(number_to_words(17_000), "seventeen thousand");
+    }
+
+    #[test]
+    fn number_to_words_skips_empty_groups_up_to_u64_max() {
+        assert_eq!(number_to_words(3_000_017), "three million seventeen");
+        assert_eq!(number_to_words(1_000_000_000_000), "one trillion");
+        assert_eq!(
+            number_to_words(u64::MAX),
+            "eighteen quintillion four hundred forty-six quadrillion seven hundred forty-four trillion \
+             seventy-three billion seven hundred nine million five hundred fifty-one thousand six hundred fifteen"
+        );
+    }
+
+    // 练习5
+    #[test]
+    fn longest_palindrome_of_empty_and_single_char_input() {
+        assert_eq!(longest_palindrome_substring(""), "");
+        assert_eq!(longest_palindrome_substring("x"), "x");
+        // 没有更长的回文时，返回第一个字符
+        assert_eq!(longest_palindrome_substring("abc"), "a");
+    }
+
+    #[test]
+    fn longest_palindrome_prefers_the_first_of_equal_length() {
+        assert_eq!(longest_palindrome_substring("babad"), "bab");
+        assert_eq!(longest_palindrome_substring("cbbd"), "bb");
+        assert_eq!(longest_palindrome_substring("forgeeksskeegfor"), "geeksskeeg");
+    }
+
+    #[test]
+    fn longest_palindrome_handles_multibyte_text() {
+        assert_eq!(longest_palindrome_substring("上海自来水来自海上"), "上海自来水来自海上");
+        assert_eq!(longest_palindrome_substring("我爱妈妈"), "妈妈");
+        assert_eq!(longest_palindrome_substring("xé🦀éy"), "é🦀é");
+    }
+
+    // 练习6
+    #[test]
+    fn rotations_are_detected() {
+        assert!(is_rotation("waterbottle", "erbottlewat"));
+        assert!(is_rotation("waterbottle", "bottlewater"));
+        assert!(is_rotation("waterbottle", "waterbottle"));
+        assert!(is_rotation("", ""));
+    }
+
+    #[test]
+    fn non_rotations_are_rejected() {
+        // 字母相同但顺序不对
+        assert!(!is_rotation("waterbottle", "erbottlewta"));
+        // 长度不同，即使 b 出现在 a + a 里
+        assert!(!is_rotation("abc", "abcabc"));
+        assert!(!is_rotation("abc", "bc"));
+        assert!(!is_rotation("", "a"));
+    }
+
+    #[test]
+    fn rotations_of_multibyte_text() {
+        assert!(is_rotation("你好世界", "世界你好"));
+        assert!(is_rotation("🦀ab", "b🦀a"));
+        // 字节数相同、字符不同
+        assert!(!is_rotation("你好世界", "世界你们"));
+    }
+
+    // 练习7
+    #[test]
+    fn autocomplete_ignores_case_and_keeps_original_spelling() {
+        let words = ["apple", "Apricot", "banana", "APPLET", "avocado"];
+        assert_eq!(autocomplete(&words, "ap"), vec!["apple", "APPLET", "Apricot"]);
+        assert_eq!(autocomplete(&words, "AP"), vec!["apple", "APPLET", "Apricot"]);
+        assert_eq!(autocomplete(&words, "Banana"), vec!["banana"]);
+    }
+
+    #[test]
+    fn autocomplete_without_matches_or_prefix() {
+        let words = ["apple", "Apricot", "banana"];
+        assert!(autocomplete(&words, "kiwi").is_empty());
+        assert!(autocomplete(&[], "a").is_empty());
+        // 空前缀匹配所有单词
+        assert_eq!(autocomplete(&words, ""), vec!["apple", "Apricot", "banana"]);
+    }
+
+    #[test]
+    fn autocomplete_orders_case_variants_deterministically() {
+        // 小写后相同的单词按原来的写法排序，大写字母排在前面
+        assert_eq!(autocomplete(&["apple", "Apple", "APPLE"], "app"), vec!["APPLE", "Apple", "apple"]);
+        assert_eq!(autocomplete(&["Éclair", "école"], "é"), vec!["Éclair", "école"]);
+    }
+}
+
 /*
  * =====================================================================================
  * 练习挑战 (Challenge Section)
@@ -159,4 +486,31 @@ fn check_str(s: &str) -> bool{
  *    则返回 `true`，否则返回 `false`。
  *    例如, "A man, a plan, a canal: Panama" 应该返回 true。
  *
+ * 3. 校验 ISBN-10:
+ *    编写函数 `is_valid_isbn10(s: &str) -> bool`。先去掉连字符，确认剩下 10 个字符，
+ *    然后计算加权校验和（第 1 位乘 10，第 2 位乘 9，……，最后一位乘 1；最后一位可以是 'X'，代表 10），
+ *    校验和能被 11 整除则返回 `true`。
+ *    例如, "0-8044-2957-X" 是有效的。
+ *
+ * 4. 数字转英文单词:
+ *    编写函数 `number_to_words(n: u64) -> String`，把数字转换成英文，
+ *    例如 1234 -> "one thousand two hundred thirty-four"。
+ *    提示：每三位分成一组，先写一个把 0-999 转成单词的辅助函数，再为每组加上 thousand、million 等单位。
+ *
+ * 5. 最长回文子串:
+ *    编写函数 `longest_palindrome_substring(s: &str) -> String`，用“中心扩展法”找出最长的回文子串：
+ *    把每个字符、以及每两个相邻字符之间的位置都当作中心，向两边扩展到不再对称为止。
+ *    按 `char` 处理而不是按字节，空字符串返回空字符串；长度相同时返回最先出现的那个，
+ *    所以 "babad" 返回 "bab"。
+ *
+ * 6. 字符串旋转:
+ *    编写函数 `is_rotation(a: &str, b: &str) -> bool`，判断 `b` 是否由 `a` 旋转得到
+ *    （把开头的一段移到末尾）。提示：长度相同，并且 `b` 是 `a + a` 的子串。
+ *    例如 "erbottlewat" 是 "waterbottle" 的旋转。注意中文等多字节字符也要正确处理。
+ *
+ * 7. 自动补全:
+ *    编写函数 `autocomplete(words: &[&str], prefix: &str) -> Vec<String>`，返回所有以 `prefix` 开头的单词，
+ *    不区分大小写，结果按字母顺序排序。例如在 ["apple", "Apricot", "banana"] 里补全 "AP"，
+ *    得到 ["apple", "Apricot"]。
+ *
  */
\ No newline at end of file
@@ -51,8 +51,19 @@
 // 代码示例 (Code Section)
 // =====================================================================================
 
-use std::collections::HashMap;
-use std::io;//导入需要用户输入的包
+// main 只运行交互程序，有些练习的函数只在文件末尾的单元测试里调用，
+// 这些函数各自标了 #[allow(dead_code)]，其余代码没有用到时仍然会得到警告
+
+use std::cmp::Reverse;
+use std::convert::Infallible;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+use std::io::{self, BufRead, IsTerminal, Write};//导入需要用户输入的包
+use std::ops::ControlFlow;
+use std::time::{Duration, Instant};
+use company::Company;
+use messages::{render, Lang, Localize, MsgKey};
 fn main() {
     // 创建一个新的 HashMap，键是 String，值是 i32
     let mut scores = HashMap::new();
@@ -98,123 +109,6346 @@ fn main() {
     println!("{:?}", scores);
 
     // c) 基于旧值来更新值
+    // 计数的循环写在 count_words 里（练习40），每个单词交给 Tally::record，
+    // 它用 entry().or_insert 取出计数再加一（练习25）
     let text = "hello world wonderful world";
-    let mut word_counts = HashMap::new();
-
-    for word in text.split_whitespace() {
-        // `entry(word.to_string()).or_insert(0)` 返回一个 &mut i32
-        let count = word_counts.entry(word.to_string()).or_insert(0);
-        *count += 1; // 使用解引用操作符 `*` 来修改这个值
-    }
+    let word_counts = count_words(text);
     println!("\nWord counts: {:?}", word_counts);
 
     // 练习1：
     // 创建一个新的、可变的 HashMap。
     // Key 的类型是 String（部门名），Value 的类型是 Vec<String>（该部门的员工列表）
-    let mut departments: HashMap<String,Vec<String>> = HashMap::new();
-    println!("Welcome to Company System!");
-    println!("plz enter order like (Add xxx to xxx,List xxx,List All,Quit)");
+    // 当前使用的语言，可以用 "Lang <en|zh>" 命令切换（练习11）
+    let mut lang = Lang::Zh;
+    println!("{}", render(lang, MsgKey::WelcomeBanner));
+    println!("{}", render(lang, MsgKey::CommandHint));
+    // 上次退出时保存了数据的话，先读回来；没有这个文件就从空的 HashMap 开始（练习34）
+    // 数据交给 Company 保管，所有命令都通过它来读取和修改（练习49）
+    let mut company = Company::from(load_autosave(lang, AUTOSAVE_CSV));
+    // 修改数据的命令都记在这里，Undo 命令按相反的顺序撤销（练习47）
+    let mut journal = undo::Journal::new();
+    // 所有的键盘输入都从这里读，一行最多读多少个字节可以用环境变量 MAX_LINE_BYTES 设置（练习30）。
+    // 主循环、List All 的分页和 Import 的提问共用它，execute 把它一路传下去。
+    // 输入不是终端（比如用管道传进来的脚本）时，导入过程中可以用一行 CANCEL 取消导入
+    let mut stdin = LineInput::new(io::stdin().lock(), max_line_bytes_setting(std::env::var("MAX_LINE_BYTES").ok().as_deref()))
+        .scripted(!io::stdin().is_terminal());
     
     loop{
 
-        // 创建一个可变的空字符串，用来存放用户输入的内容
-        let mut input = String::new();
-        // 读取一行用户输入数据
-        // &mut input 表示我们把 input 的可变引用传给 read_line，这样它就能修改 input 的内容
-        // .expect() 是一个简单的错误处理方式，如果读取失败，程序会崩溃并显示后面的消息
-        io::stdin().read_line(&mut input).expect("读取用户输入失败");
-
-        // .trim() 会去掉输入字符串首尾的空白字符（比如换行符）
-        // .split_whitespace() 会用空白字符（空格、制表符等）把字符串分割成一个一个的单词
-        // .collect() 把这些单词收集到一个 Vec<&str> 类型的动态数组中
-        let words: Vec<&str> =  input.trim().split_whitespace().collect();
-
-        // 使用 match 语句来解析用户输入的命令
-        // 这是 Rust 中非常强大和常见的模式匹配功能
-        match words.as_slice(){
-            // 模式1：匹配 "Add <xxx> to <xxx>" 格式的命令
-            ["Add",name,"to",department] => {
-                println!("正在添加{}到{}部门...",name,department);
-
-                // 处理添加逻辑
-                // 1. .entry(department.to_string()): 检查 'department' 这个键是否存在。
-                //    .to_string() 是因为 department 是 &str 类型，而我们的 key 是 String 类型。
-                // 2. .or_insert(Vec::new()): 如果键不存在，就插入一个新的空 Vec 作为值。
-                // 3. 无论键是本来就存在还是刚刚插入的，.entry().or_insert() 都会返回一个指向 Vec 的可变引用。
-                // 4. .push(name.to_string()): 最后，调用 Vec 的 push 方法，把员工名字加进去。
-                departments.entry(department.to_string()).or_insert(Vec::new()).push(name.to_string());
-                println!("添加成功！")
-            }
-        
-            // 模式三：匹配 "List All" 命令
-            ["List","All"] => {
-                println!("公司所有部门及员工列表：");
-                // 为了保证每次输出的顺序一致，我们先收集所有的部门名并排序
-                let mut sorted_departments: Vec<_> = departments.keys().collect();
-                sorted_departments.sort();
-                // 遍历
-                for department in sorted_departments {
-                    // departments[department] 是获取部门对应员工列表的简写
-                    // 这里我们确定 key 肯定存在，所以可以直接用
-                    let mut employees = departments[department].clone();
-                    employees.sort();
-                    println!("\n ## {} ##",department);
-                    for employee in employees{
-                        println!("- {}",employee);
-                    }
+        // 读取一行用户输入数据。
+        // 读取失败（比如输入来自一个已经断开的管道）时用 report_error 说明原因，然后结束程序，而不是 panic
+        let outcome = match stdin.read_line() {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                println!("{}", render(lang, MsgKey::IoFailed { error: report_error(lang, &e) }));
+                break;
+            }
+        };
+        let input = match outcome {
+            LineOutcome::Line(line) => line,
+            // 太长的一行已经被整行丢弃，提示一下然后继续读下一行
+            LineOutcome::TooLong { consumed } => {
+                println!("{}", render(lang, MsgKey::LineTooLong { bytes: consumed, max: stdin.max_line_bytes }));
+                continue;
+            }
+            // 输入结束（比如通过管道输入的文件读完了），没有更多命令可以执行
+            LineOutcome::Eof => break,
+        };
+
+        // 先把输入解析成一个 Command（练习17），解析成功后再执行。
+        // 这样“读懂命令”和“执行命令”分开了，Run 命令执行脚本时也可以复用同样的两步
+        match parse_command(&input) {
+            Ok(command) => {
+                // execute 返回 ControlFlow::Break 表示用户要求退出
+                if execute(command, &mut company, &mut journal, &mut lang, &mut stdin).is_break() {
+                    break;
                 }
             }
-            
-            // 模式二：匹配 "List <xxx>" 格式的命令
-            ["List",department] => {
-                println!("{}部门的员工列表:",department);
-
-                // 查询方法.get()
-                match departments.get(*department){
-                    // Some(employees) 表示我们成功找到了部门，employees 是对员工列表 Vec 的引用
-                    Some(employees) => {
-                        // 创建一个克隆，因为我们不想直接修改原始数据，只是为了排序打印
-                        let mut sorted_employees = employees.clone();
-                        // 对员工字母排序
-                        sorted_employees.sort();
-                        // 遍历
-                        for employee in sorted_employees {
-                            println!("- {}",employee);
-                        }
-                    }
-                    None => {
-                        println!("未找到'{}'部门",department);
-                    }
+            Err(CommandError::UnknownLang(code)) => {
+                println!("{}", render(lang, MsgKey::UnknownLang { code }));
+            }
+            // 输错了命令名但能猜到想输入什么（练习29）
+            Err(CommandError::UnknownCommand { word, suggestion: Some(verb) }) => {
+                println!("{}", render(lang, MsgKey::DidYouMean { word, verb: verb.to_string() }));
+            }
+            // 如果用户输入的命令不匹配任何一种格式
+            Err(_) => {
+                println!("{}", render(lang, MsgKey::InvalidCommand));
+            }
+        }
+    }
+}
+
+// 练习3：
+#[allow(dead_code)]
+fn most_common_word_length(text: &str) -> Option<usize> {
+    // 键是单词长度（按字符数计算），值是这个长度出现的次数
+    let mut histogram: HashMap<usize, usize> = HashMap::new();
+    for word in text.split_whitespace() {
+        *histogram.entry(word.chars().count()).or_insert(0) += 1;
+    }
+    // 次数最多的长度胜出；次数相同时选择较短的长度，保证结果不受 HashMap 遍历顺序影响
+    histogram
+        .into_iter()
+        .max_by(|(len_a, count_a), (len_b, count_b)| count_a.cmp(count_b).then(len_b.cmp(len_a)))
+        .map(|(len, _)| len)
+}
+
+// 练习4：
+#[allow(dead_code)]
+fn avg_name_length(map: &HashMap<String, Vec<String>>) -> HashMap<String, f64> {
+    let mut averages = HashMap::new();
+    for (department, employees) in map {
+        // 空部门没有平均值可言（而且会除以 0），直接跳过
+        if employees.is_empty() {
+            continue;
+        }
+        let total: usize = employees.iter().map(|name| name.chars().count()).sum();
+        averages.insert(department.clone(), total as f64 / employees.len() as f64);
+    }
+    averages
+}
+
+// 练习5：
+// 告诉导入函数：哪一列是名字，哪一列是部门；ignore_unknown 为 false 时，出现其他列就报错
+#[derive(Debug, Clone)]
+struct ColumnMapping {
+    name_column: String,
+    department_column: String,
+    ignore_unknown: bool,
+}
+
+#[derive(Debug)]
+struct ImportResult {
+    // 去重之后的 (部门, 名字)，按文件中首次出现的顺序排列
+    records: Vec<(String, String)>,
+    rows_imported: usize,
+    rows_skipped: usize,
+    duplicates_merged: usize,
+}
+
+#[derive(Debug)]
+enum ImportError {
+    MissingHeader,
+    UnknownColumn(String),
+    UnmappedColumns(Vec<String>),
+    Csv(CsvError),
+}
+
+impl Localize for ImportError {
+    fn localized(&self, lang: Lang) -> String {
+        let key = match self {
+            ImportError::MissingHeader => MsgKey::ImportMissingHeader,
+            ImportError::UnknownColumn(column) => MsgKey::ImportUnknownColumn { column: column.clone() },
+            ImportError::UnmappedColumns(columns) => MsgKey::ImportUnmappedColumns { columns: columns.join(", ") },
+            ImportError::Csv(error) => return error.localized(lang),
+        };
+        render(lang, key)
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.localized(Lang::Zh))
+    }
+}
+
+impl From<CsvError> for ImportError {
+    fn from(e: CsvError) -> Self {
+        ImportError::Csv(e)
+    }
+}
+
+// 用 parse_csv_rows（练习43）读出所有行，去掉空行。带引号的字段里可以有逗号
+fn csv_records(csv_text: &str) -> Result<Vec<Vec<String>>, ImportError> {
+    let mut rows = parse_csv_rows(csv_text)?;
+    rows.retain(|row| row.iter().any(|field| !field.trim().is_empty()));
+    Ok(rows)
+}
+
+// 第一行是表头，列名去掉首尾空白
+fn header_columns(rows: &[Vec<String>]) -> Result<Vec<String>, ImportError> {
+    match rows.first() {
+        Some(header) => Ok(header.iter().map(|c| c.trim().to_string()).collect()),
+        None => Err(ImportError::MissingHeader),
+    }
+}
+
+// 只取出表头，返回所有列名（导入前展示给用户看）
+fn csv_header(csv_text: &str) -> Result<Vec<String>, ImportError> {
+    header_columns(&csv_records(csv_text)?)
+}
+
+// 名字和部门都去掉首尾空白，这样 " Sally " 和 "Sally" 会被当作同一个人
+fn sanitize_field(field: &str) -> String {
+    field.trim().to_string()
+}
+
+fn import_with_mapping(csv_text: &str, mapping: &ColumnMapping) -> Result<ImportResult, ImportError> {
+    let rows = csv_records(csv_text)?;
+    let header = header_columns(&rows)?;
+
+    // 1. 在表头中找到名字列和部门列的位置
+    let position = |column: &str| {
+        header
+            .iter()
+            .position(|c| c == column)
+            .ok_or(ImportError::UnknownColumn(column.to_string()))
+    };
+    let name_index = position(&mapping.name_column)?;
+    let department_index = position(&mapping.department_column)?;
+
+    if !mapping.ignore_unknown {
+        let unmapped: Vec<String> = header
+            .iter()
+            .filter(|c| **c != mapping.name_column && **c != mapping.department_column)
+            .cloned()
+            .collect();
+        if !unmapped.is_empty() {
+            return Err(ImportError::UnmappedColumns(unmapped));
+        }
+    }
+
+    // 2. 逐行读取数据（跳过表头和空行），名字或部门为空的行计入 rows_skipped
+    let mut result = ImportResult {
+        records: Vec::new(),
+        rows_imported: 0,
+        rows_skipped: 0,
+        duplicates_merged: 0,
+    };
+    for fields in rows.iter().skip(1) {
+        let field = |index: usize| fields.get(index).map_or(String::new(), |field| sanitize_field(field));
+        let name = field(name_index);
+        let department = field(department_index);
+        if name.is_empty() || department.is_empty() {
+            result.rows_skipped += 1;
+            continue;
+        }
+        // 3. 同一个部门里重复出现的同一个名字只保留一次
+        //    每一行数据只会计入 rows_imported、rows_skipped、duplicates_merged 三者之一
+        let record = (department, name);
+        if result.records.contains(&record) {
+            result.duplicates_merged += 1;
+        } else {
+            result.records.push(record);
+            result.rows_imported += 1;
+        }
+    }
+    Ok(result)
+}
+
+// 把导入的记录合并进现有的部门表，返回新创建的部门数量；已经存在的员工不会重复添加
+fn merge_import(departments: &mut HashMap<String, Vec<String>>, records: &[(String, String)]) -> usize {
+    let mut new_departments = 0;
+    for (department, name) in records {
+        if !departments.contains_key(department) {
+            new_departments += 1;
+        }
+        let employees = departments.entry(department.clone()).or_default();
+        if !employees.contains(name) {
+            employees.push(name.clone());
+        }
+    }
+    new_departments
+}
+
+// 向用户提一个问题，并从 input 读取一行回答。input 可以换成 io::Cursor，不需要真的键盘输入。
+// 读取失败时把错误交给调用者，由它决定怎么报告
+fn prompt(input: &mut LineInput<impl BufRead>, question: &str) -> io::Result<String> {
+    println!("{}", question);
+    match input.read_line()? {
+        LineOutcome::Line(answer) => Ok(answer.trim().to_string()),
+        // 超过上限的回答不可能是列名或序号，和没有回答一样处理
+        LineOutcome::TooLong { .. } | LineOutcome::Eof => Ok(String::new()),
+    }
+}
+
+// 用户既可以输入列的序号，也可以直接输入列名
+fn resolve_column(answer: &str, header: &[String]) -> String {
+    match answer.parse::<usize>() {
+        Ok(index) if index >= 1 && index <= header.len() => header[index - 1].clone(),
+        _ => answer.to_string(),
+    }
+}
+
+// Import 命令的交互部分：所有逻辑都在上面的纯函数里，这里只负责提问和打印
+fn import_interactively(
+    lang: Lang,
+    path: &str,
+    company: &mut Company,
+    input: &mut LineInput<impl BufRead>,
+) {
+    let csv_text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            println!("{}", render(lang, MsgKey::ReadFileFailed { path: path.to_string(), error: report_error(lang, &e) }));
+            return;
+        }
+    };
+    let header = match csv_header(&csv_text) {
+        Ok(header) => header,
+        Err(e) => {
+            println!("{}", render(lang, MsgKey::ImportFailed { error: e.localized(lang) }));
+            return;
+        }
+    };
+    println!("{}", render(lang, MsgKey::ImportColumnsHeader));
+    for (index, column) in header.iter().enumerate1() {
+        println!("{}", render(lang, MsgKey::ImportColumn { index, column: column.clone() }));
+    }
+    let answers = (|| {
+        let name = prompt(input, &render(lang, MsgKey::AskNameColumn))?;
+        let department = prompt(input, &render(lang, MsgKey::AskDeptColumn))?;
+        Ok::<_, io::Error>((name, department))
+    })();
+    let (name_answer, department_answer) = match answers {
+        Ok(answers) => answers,
+        Err(e) => {
+            println!("{}", render(lang, MsgKey::IoFailed { error: report_error(lang, &e) }));
+            return;
+        }
+    };
+    let mapping = ColumnMapping {
+        name_column: resolve_column(&name_answer, &header),
+        department_column: resolve_column(&department_answer, &header),
+        ignore_unknown: true,
+    };
+    let result = match import_with_mapping(&csv_text, &mapping) {
+        Ok(result) => result,
+        Err(e) => {
+            println!("{}", render(lang, MsgKey::ImportFailed { error: e.localized(lang) }));
+            return;
+        }
+    };
+
+    // 大文件分块合并，每处理完一块打印一次进度（练习7）。
+    // 输入来自脚本时，两块之间读到一行 CANCEL 就取消导入；终端上不去等待输入。
+    // 是不是脚本由 input 自己记录，测试里换成 io::Cursor 时也能控制
+    let scripted = input.scripted;
+    let outcome = company.import_chunked(&result.records, IMPORT_CHUNK_SIZE, |progress| {
+        println!(
+            "{}",
+            render(lang, MsgKey::ImportProgress {
+                done: progress.done,
+                total: progress.total,
+                elapsed: format!("{:?}", progress.elapsed),
+            })
+        );
+        if scripted && cancel_requested(&mut input.reader) {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+    match outcome {
+        Ok((ProcessOutcome::Cancelled { completed }, _)) => {
+            println!("{}", render(lang, MsgKey::ImportCancelled { done: completed, total: result.records.len() }));
+        }
+        Ok((ProcessOutcome::Completed { .. }, new_departments)) => println!(
+            "{}",
+            render(lang, MsgKey::ImportDone {
+                imported: result.rows_imported,
+                skipped: result.rows_skipped,
+                merged: result.duplicates_merged,
+                new_depts: new_departments,
+            })
+        ),
+        Err(e) => println!("{}", render(lang, MsgKey::ImportInterrupted { error: report_error(lang, &e) })),
+    }
+}
+
+// 练习6：
+#[allow(dead_code)]
+fn largest_department(map: &HashMap<String, Vec<String>>) -> Option<(String, usize)> {
+    // 人数多的优先；人数相同时，名字在字母表中靠前的优先。
+    // max_by 遇到“相等”时会返回最后一个，所以比较名字时要反过来（b 和 a 比较）
+    map.iter()
+        .max_by(|(name_a, list_a), (name_b, list_b)| {
+            list_a.len().cmp(&list_b.len()).then(name_b.cmp(name_a))
+        })
+        .map(|(name, list)| (name.clone(), list.len()))
+}
+
+// 练习7：
+// Import 命令合并数据时每块的大小
+const IMPORT_CHUNK_SIZE: usize = 500;
+
+// 每处理完一块，就把当前进度报告给回调
+#[derive(Debug, Clone, Copy)]
+struct Progress {
+    done: usize,
+    total: usize,
+    elapsed: Duration,
+}
+
+#[derive(Debug, PartialEq)]
+enum ProcessOutcome {
+    Completed { processed: usize },
+    Cancelled { completed: usize },
+}
+
+// 处理失败的原因：块大小为 0，或者某一块的处理函数返回了错误（附带已经完成的数量）
+#[derive(Debug, PartialEq)]
+enum ChunkError<E> {
+    ZeroChunkSize,
+    Work { completed: usize, error: E },
+}
+
+impl<E> fmt::Display for ChunkError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChunkError::ZeroChunkSize => write!(f, "分块大小不能为 0"),
+            ChunkError::Work { completed, .. } => write!(f, "处理完 {} 个元素之后出错", completed),
+        }
+    }
+}
+
+// 具体的错误放在 source() 里，report_error 会把它作为 "caused by" 打印出来
+impl<E: std::error::Error + 'static> std::error::Error for ChunkError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ChunkError::ZeroChunkSize => None,
+            ChunkError::Work { error, .. } => Some(error),
+        }
+    }
+}
+
+// 把 items 按 chunk_size 分块交给 work 处理。
+// 每块处理完之后调用 on_progress，它返回 ControlFlow::Break 时，在当前块结束后停止，
+// 已经处理的块不会回滚，ProcessOutcome::Cancelled 记录了完成了多少个元素。
+fn process_chunked<T, E, F, P>(
+    items: &[T],
+    chunk_size: usize,
+    mut work: F,
+    mut on_progress: P,
+) -> Result<ProcessOutcome, ChunkError<E>>
+where
+    F: FnMut(&[T]) -> Result<(), E>,
+    P: FnMut(Progress) -> ControlFlow<()>,
+{
+    if chunk_size == 0 {
+        return Err(ChunkError::ZeroChunkSize);
+    }
+    let start = Instant::now();
+    let mut done = 0;
+    // chunks() 会自动处理最后一块不足 chunk_size 的情况
+    for chunk in items.chunks(chunk_size) {
+        if let Err(error) = work(chunk) {
+            return Err(ChunkError::Work { completed: done, error });
+        }
+        done += chunk.len();
+        let progress = Progress { done, total: items.len(), elapsed: start.elapsed() };
+        if on_progress(progress).is_break() && done < items.len() {
+            return Ok(ProcessOutcome::Cancelled { completed: done });
+        }
+    }
+    Ok(ProcessOutcome::Completed { processed: done })
+}
+
+// Import 用的分块合并：先合并进一份副本，全部完成后才替换原来的数据，
+// 这样中途取消时 departments 完全不变。返回处理结果和新建的部门数（取消时为 0）
+fn merge_import_chunked(
+    departments: &mut HashMap<String, Vec<String>>,
+    records: &[(String, String)],
+    chunk_size: usize,
+    on_progress: impl FnMut(Progress) -> ControlFlow<()>,
+) -> Result<(ProcessOutcome, usize), ChunkError<Infallible>> {
+    let mut staged = departments.clone();
+    let mut new_departments = 0;
+    let outcome = process_chunked(
+        records,
+        chunk_size,
+        |chunk| {
+            new_departments += merge_import(&mut staged, chunk);
+            Ok(())
+        },
+        on_progress,
+    )?;
+    match outcome {
+        ProcessOutcome::Completed { .. } => {
+            *departments = staged;
+            Ok((outcome, new_departments))
+        }
+        ProcessOutcome::Cancelled { .. } => Ok((outcome, 0)),
+    }
+}
+
+// 输入里紧接着的一行是 CANCEL 时把它读掉并返回 true；是别的内容时什么也不读，留给命令循环
+fn cancel_requested(input: &mut impl BufRead) -> bool {
+    let Ok(buffer) = input.fill_buf() else {
+        return false;
+    };
+    let line_len = buffer.iter().position(|&b| b == b'\n').map_or(buffer.len(), |i| i + 1);
+    if buffer[..line_len].trim_ascii() == b"CANCEL" {
+        input.consume(line_len);
+        true
+    } else {
+        false
+    }
+}
+
+// 练习8：
+fn all_employees_sorted(map: &HashMap<String, Vec<String>>) -> Vec<String> {
+    // flatten() 把“部门 -> 员工列表”摊平成一个员工迭代器
+    let mut all: Vec<String> = map.values().flatten().cloned().collect();
+    all.sort();
+    // dedup_consecutive() 只会去掉相邻的重复元素，所以必须先排序（练习12）
+    all.into_iter().dedup_consecutive().collect()
+}
+
+// 练习9：
+// 键不区分大小写的“一对多”映射：查找时把键折叠成小写，显示时使用第一次插入时的原始写法
+struct CiMultiMap<V> {
+    // 折叠后的键 -> (用于显示的原始键, 值列表)
+    entries: HashMap<String, (String, Vec<V>)>,
+}
+
+impl<V> CiMultiMap<V> {
+    fn new() -> CiMultiMap<V> {
+        CiMultiMap { entries: HashMap::new() }
+    }
+
+    fn fold(key: &str) -> String {
+        key.to_lowercase()
+    }
+
+    #[allow(dead_code)]
+    fn insert(&mut self, key: &str, value: V) {
+        self.insert_key(key).push(value);
+    }
+
+    // 只登记键、不加值，返回这个键的值列表。
+    // or_insert_with 只有在键不存在时才会调用闭包，所以显示用的写法永远是第一次插入的那个
+    fn insert_key(&mut self, key: &str) -> &mut Vec<V> {
+        &mut self.entries.entry(Self::fold(key)).or_insert_with(|| (key.to_string(), Vec::new())).1
+    }
+
+    fn get(&self, key: &str) -> Option<&[V]> {
+        self.entries.get(&Self::fold(key)).map(|(_, values)| values.as_slice())
+    }
+
+    // 按折叠后的键排序，保证每次遍历的顺序都一样
+    #[allow(dead_code)]
+    fn keys_display(&self) -> impl Iterator<Item = &str> {
+        let mut keys: Vec<(&String, &String)> =
+            self.entries.iter().map(|(folded, (display, _))| (folded, display)).collect();
+        keys.sort();
+        keys.into_iter().map(|(_, display)| display.as_str())
+    }
+
+    // 删除该键下所有满足条件的值，返回删除的数量；值被删光时连键一起删除
+    #[allow(dead_code)]
+    fn remove_value(&mut self, key: &str, pred: impl Fn(&V) -> bool) -> usize {
+        let folded = Self::fold(key);
+        let removed = match self.entries.get_mut(&folded) {
+            Some((_, values)) => {
+                let before = values.len();
+                values.retain(|v| !pred(v));
+                before - values.len()
+            }
+            None => return 0,
+        };
+        if self.entries.get(&folded).is_some_and(|(_, values)| values.is_empty()) {
+            self.entries.remove(&folded);
+        }
+        removed
+    }
+
+    #[allow(dead_code)]
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[allow(dead_code)]
+    fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    #[allow(dead_code)]
+    fn total_values(&self) -> usize {
+        self.entries.values().map(|(_, values)| values.len()).sum()
+    }
+}
+
+// 适配器：为部门表建立一个不区分大小写的只读视图，值是对员工名字的引用，不需要克隆。
+// 按部门名排序后再插入，这样 "Sales" 和 "sales" 同时存在时，显示用的写法也是确定的。
+// 没有员工的部门也要登记，否则 List 会说一个存在的部门找不到
+fn departments_ci(map: &HashMap<String, Vec<String>>) -> CiMultiMap<&String> {
+    let mut names: Vec<&String> = map.keys().collect();
+    names.sort();
+    let mut view = CiMultiMap::new();
+    for name in names {
+        view.insert_key(name).extend(&map[name]);
+    }
+    view
+}
+
+// 单词 -> 出现的行号（从 1 开始），查找时不区分大小写
+#[allow(dead_code)]
+fn word_line_index(text: &str) -> CiMultiMap<usize> {
+    let mut index = CiMultiMap::new();
+    for (i, line) in text.lines().enumerate() {
+        for word in line.split_whitespace() {
+            index.insert(word, i + 1);
+        }
+    }
+    index
+}
+
+// 练习10：
+#[allow(dead_code)]
+fn shannon_entropy(s: &str) -> f64 {
+    // 1. 统计每个字符出现的次数
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in s.chars() {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+    // 空字符串或只有一种字符时，没有任何“不确定性”
+    if counts.len() <= 1 {
+        return 0.0;
+    }
+
+    // 2. H = -Σ p * log2(p)，其中 p 是每个字符出现的概率
+    let total = s.chars().count() as f64;
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+// 练习11：
+// 部门管理程序里所有给用户看的文字都集中放在这个模块里，按语言查表再填入参数
+mod messages {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Lang {
+        En,
+        Zh,
+    }
+
+    impl Lang {
+        // 解析 "Lang <en|zh>" 命令里的语言代码，不区分大小写
+        pub fn parse(code: &str) -> Option<Lang> {
+            match code.to_lowercase().as_str() {
+                "en" => Some(Lang::En),
+                "zh" => Some(Lang::Zh),
+                _ => None,
+            }
+        }
+    }
+
+    // 每一条消息是一个变体，需要填进模板的参数直接放在变体里
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum MsgKey {
+        WelcomeBanner,
+        CommandHint,
+        Adding { name: String, dept: String },
+        AddedOk { name: String, dept: String },
+        ListAllHeader,
+        DeptHeader { dept: String },
+        EmployeeLine { name: String },
+        ListDeptHeader { dept: String },
+        DeptNotFound { dept: String },
+        RosterHeader,
+        LangSwitched,
+        UnknownLang { code: String },
+        ReadFileFailed { path: String, error: String },
+        ImportFailed { error: String },
+        ImportColumnsHeader,
+        ImportColumn { index: usize, column: String },
+        AskNameColumn,
+        AskDeptColumn,
+        ImportProgress { done: usize, total: usize, elapsed: String },
+        ImportDone { imported: usize, skipped: usize, merged: usize, new_depts: usize },
+        ImportInterrupted { error: String },
+        ExportDone { path: String },
+        ExportFailed { error: String },
+        ScriptRejected { count: usize },
+        ScriptLineError { line: usize, error: String },
+        ScriptDone { executed: usize, skipped: usize },
+        QueryHeader { count: usize },
+        QueryResult { dept: String, name: String },
+        StatsReport { departments: usize, employees: usize, average: String, size: String },
+        DidYouMean { word: String, verb: String },
+        LineTooLong { bytes: usize, max: usize },
+        IoFailed { error: String },
+        HintCheckPath,
+        HintCheckPermissions,
+        HintCheckEncoding,
+        RemovedOk { name: String, dept: String },
+        DeptRemoved { dept: String },
+        EmployeeNotInDept { name: String, dept: String },
+        TransferredOk { name: String, from: String, to: String },
+        TransferSameDept { name: String, dept: String },
+        SaveDone { path: String, departments: usize },
+        SaveFailed { error: String },
+        LoadDone { path: String, departments: usize, employees: usize },
+        LoadFailed { error: String },
+        LoadNoChecksum { path: String },
+        CsvLoaded { path: String, employees: usize },
+        CsvBadLine { path: String, line: usize },
+        CsvLoadFailed { path: String, error: String },
+        CsvSaved { path: String },
+        CsvSaveFailed { path: String, error: String },
+        CountLine { dept: String, count: usize },
+        RenamedOk { old: String, new: String },
+        RenameMerged { old: String, new: String, merged: usize },
+        RenameSameName { dept: String },
+        RenameHasSubDepts { dept: String },
+        FoundIn { name: String, dept: String },
+        FindNotFound { name: String },
+        FindCloseMatch { name: String, dept: String },
+        CountTotal { departments: usize, total: usize },
+        Undone { action: String },
+        NothingToUndo,
+        ImportMissingHeader,
+        ImportUnknownColumn { column: String },
+        ImportUnmappedColumns { columns: String },
+        CommandEmpty,
+        CommandUnknown { word: String },
+        CommandUnknownSuggest { word: String, verb: String },
+        CommandBadArguments { usage: String },
+        CommandNestedRun,
+        ScaledEmpty,
+        ScaledNegative,
+        ScaledInvalidNumber { text: String },
+        ScaledUnknownSuffix { suffix: char },
+        ScaledNotWhole { text: String },
+        ScaledOverflow,
+        ChecksumMismatch { expected: u32, actual: u32 },
+        ChecksumTruncated,
+        RosterMalformed { line: usize, reason: String },
+        RosterMissingColon,
+        RosterEmptyDept,
+        RosterDuplicateDept,
+        RosterEmptyEmployee,
+        RosterUnrepresentable { name: String },
+        JsonSyntax { offset: usize, reason: String },
+        JsonExpected { expected: char },
+        JsonExpectedCommaOr { close: char },
+        JsonDuplicateDept,
+        JsonExpectedString,
+        JsonUnterminatedString,
+        JsonControlChar,
+        JsonBadHexEscape,
+        JsonMissingLowSurrogate,
+        JsonInvalidCodepoint,
+        JsonUnknownEscape,
+        JsonTrailingContent,
+        HelpAdd,
+        HelpRemove,
+        HelpTransfer,
+        HelpMove,
+        HelpRename,
+        HelpList,
+        HelpRoster,
+        HelpImport,
+        HelpExport,
+        HelpExportJson,
+        HelpImportJson,
+        HelpUndo,
+        HelpSave,
+        HelpLoad,
+        HelpLang,
+        HelpRun,
+        HelpQuery,
+        HelpStats,
+        HelpFind,
+        HelpCount,
+        HelpHelp,
+        HelpQuit,
+        HelpOverviewHeader,
+        HelpUsage { usage: String },
+        HelpExamples,
+        ImportCancelled { done: usize, total: usize },
+        CsvUnclosedQuote { row: usize },
+        CsvTextAfterQuote { row: usize },
+        LoadRecovered { path: String },
+        LoadRecoverHint { path: String },
+        InvalidCommand,
+        QuitFarewell,
+    }
+
+    impl MsgKey {
+        // 在目录里查找模板用的名字。这里的 match 没有通配分支，
+        // 新增一个变体而忘了给它起名字时，编译器会直接报错
+        pub fn id(&self) -> &'static str {
+            match self {
+                MsgKey::WelcomeBanner => "welcome_banner",
+                MsgKey::CommandHint => "command_hint",
+                MsgKey::Adding { .. } => "adding",
+                MsgKey::AddedOk { .. } => "added_ok",
+                MsgKey::ListAllHeader => "list_all_header",
+                MsgKey::DeptHeader { .. } => "dept_header",
+                MsgKey::EmployeeLine { .. } => "employee_line",
+                MsgKey::ListDeptHeader { .. } => "list_dept_header",
+                MsgKey::DeptNotFound { .. } => "dept_not_found",
+                MsgKey::RosterHeader => "roster_header",
+                MsgKey::LangSwitched => "lang_switched",
+                MsgKey::UnknownLang { .. } => "unknown_lang",
+                MsgKey::ReadFileFailed { .. } => "read_file_failed",
+                MsgKey::ImportFailed { .. } => "import_failed",
+                MsgKey::ImportColumnsHeader => "import_columns_header",
+                MsgKey::ImportColumn { .. } => "import_column",
+                MsgKey::AskNameColumn => "ask_name_column",
+                MsgKey::AskDeptColumn => "ask_dept_column",
+                MsgKey::ImportProgress { .. } => "import_progress",
+                MsgKey::ImportDone { .. } => "import_done",
+                MsgKey::ImportInterrupted { .. } => "import_interrupted",
+                MsgKey::ExportDone { .. } => "export_done",
+                MsgKey::ExportFailed { .. } => "export_failed",
+                MsgKey::ScriptRejected { .. } => "script_rejected",
+                MsgKey::ScriptLineError { .. } => "script_line_error",
+                MsgKey::ScriptDone { .. } => "script_done",
+                MsgKey::QueryHeader { .. } => "query_header",
+                MsgKey::QueryResult { .. } => "query_result",
+                MsgKey::StatsReport { .. } => "stats_report",
+                MsgKey::DidYouMean { .. } => "did_you_mean",
+                MsgKey::LineTooLong { .. } => "line_too_long",
+                MsgKey::IoFailed { .. } => "io_failed",
+                MsgKey::HintCheckPath => "hint_check_path",
+                MsgKey::HintCheckPermissions => "hint_check_permissions",
+                MsgKey::HintCheckEncoding => "hint_check_encoding",
+                MsgKey::RemovedOk { .. } => "removed_ok",
+                MsgKey::DeptRemoved { .. } => "dept_removed",
+                MsgKey::EmployeeNotInDept { .. } => "employee_not_in_dept",
+                MsgKey::TransferredOk { .. } => "transferred_ok",
+                MsgKey::TransferSameDept { .. } => "transfer_same_dept",
+                MsgKey::SaveDone { .. } => "save_done",
+                MsgKey::SaveFailed { .. } => "save_failed",
+                MsgKey::LoadDone { .. } => "load_done",
+                MsgKey::LoadFailed { .. } => "load_failed",
+                MsgKey::LoadNoChecksum { .. } => "load_no_checksum",
+                MsgKey::CsvLoaded { .. } => "csv_loaded",
+                MsgKey::CsvBadLine { .. } => "csv_bad_line",
+                MsgKey::CsvLoadFailed { .. } => "csv_load_failed",
+                MsgKey::CsvSaved { .. } => "csv_saved",
+                MsgKey::CsvSaveFailed { .. } => "csv_save_failed",
+                MsgKey::CountLine { .. } => "count_line",
+                MsgKey::RenamedOk { .. } => "renamed_ok",
+                MsgKey::RenameMerged { .. } => "rename_merged",
+                MsgKey::RenameSameName { .. } => "rename_same_name",
+                MsgKey::RenameHasSubDepts { .. } => "rename_has_sub_depts",
+                MsgKey::FoundIn { .. } => "found_in",
+                MsgKey::FindNotFound { .. } => "find_not_found",
+                MsgKey::FindCloseMatch { .. } => "find_close_match",
+                MsgKey::CountTotal { .. } => "count_total",
+                MsgKey::Undone { .. } => "undone",
+                MsgKey::NothingToUndo => "nothing_to_undo",
+                MsgKey::ImportMissingHeader => "import_missing_header",
+                MsgKey::ImportUnknownColumn { .. } => "import_unknown_column",
+                MsgKey::ImportUnmappedColumns { .. } => "import_unmapped_columns",
+                MsgKey::CommandEmpty => "command_empty",
+                MsgKey::CommandUnknown { .. } => "command_unknown",
+                MsgKey::CommandUnknownSuggest { .. } => "command_unknown_suggest",
+                MsgKey::CommandBadArguments { .. } => "command_bad_arguments",
+                MsgKey::CommandNestedRun => "command_nested_run",
+                MsgKey::ScaledEmpty => "scaled_empty",
+                MsgKey::ScaledNegative => "scaled_negative",
+                MsgKey::ScaledInvalidNumber { .. } => "scaled_invalid_number",
+                MsgKey::ScaledUnknownSuffix { .. } => "scaled_unknown_suffix",
+                MsgKey::ScaledNotWhole { .. } => "scaled_not_whole",
+                MsgKey::ScaledOverflow => "scaled_overflow",
+                MsgKey::ChecksumMismatch { .. } => "checksum_mismatch",
+                MsgKey::ChecksumTruncated => "checksum_truncated",
+                MsgKey::RosterMalformed { .. } => "roster_malformed",
+                MsgKey::RosterMissingColon => "roster_missing_colon",
+                MsgKey::RosterEmptyDept => "roster_empty_dept",
+                MsgKey::RosterDuplicateDept => "roster_duplicate_dept",
+                MsgKey::RosterEmptyEmployee => "roster_empty_employee",
+                MsgKey::RosterUnrepresentable { .. } => "roster_unrepresentable",
+                MsgKey::JsonSyntax { .. } => "json_syntax",
+                MsgKey::JsonExpected { .. } => "json_expected",
+                MsgKey::JsonExpectedCommaOr { .. } => "json_expected_comma_or",
+                MsgKey::JsonDuplicateDept => "json_duplicate_dept",
+                MsgKey::JsonExpectedString => "json_expected_string",
+                MsgKey::JsonUnterminatedString => "json_unterminated_string",
+                MsgKey::JsonControlChar => "json_control_char",
+                MsgKey::JsonBadHexEscape => "json_bad_hex_escape",
+                MsgKey::JsonMissingLowSurrogate => "json_missing_low_surrogate",
+                MsgKey::JsonInvalidCodepoint => "json_invalid_codepoint",
+                MsgKey::JsonUnknownEscape => "json_unknown_escape",
+                MsgKey::JsonTrailingContent => "json_trailing_content",
+                MsgKey::HelpAdd => "help_add",
+                MsgKey::HelpRemove => "help_remove",
+                MsgKey::HelpTransfer => "help_transfer",
+                MsgKey::HelpMove => "help_move",
+                MsgKey::HelpRename => "help_rename",
+                MsgKey::HelpList => "help_list",
+                MsgKey::HelpRoster => "help_roster",
+                MsgKey::HelpImport => "help_import",
+                MsgKey::HelpExport => "help_export",
+                MsgKey::HelpExportJson => "help_export_json",
+                MsgKey::HelpImportJson => "help_import_json",
+                MsgKey::HelpUndo => "help_undo",
+                MsgKey::HelpSave => "help_save",
+                MsgKey::HelpLoad => "help_load",
+                MsgKey::HelpLang => "help_lang",
+                MsgKey::HelpRun => "help_run",
+                MsgKey::HelpQuery => "help_query",
+                MsgKey::HelpStats => "help_stats",
+                MsgKey::HelpFind => "help_find",
+                MsgKey::HelpCount => "help_count",
+                MsgKey::HelpHelp => "help_help",
+                MsgKey::HelpQuit => "help_quit",
+                MsgKey::HelpOverviewHeader => "help_overview_header",
+                MsgKey::HelpUsage { .. } => "help_usage",
+                MsgKey::HelpExamples => "help_examples",
+                MsgKey::ImportCancelled { .. } => "import_cancelled",
+                MsgKey::CsvUnclosedQuote { .. } => "csv_unclosed_quote",
+                MsgKey::CsvTextAfterQuote { .. } => "csv_text_after_quote",
+                MsgKey::LoadRecovered { .. } => "load_recovered",
+                MsgKey::LoadRecoverHint { .. } => "load_recover_hint",
+                MsgKey::InvalidCommand => "invalid_command",
+                MsgKey::QuitFarewell => "quit_farewell",
+            }
+        }
+
+        // 模板里的 {占位符} 和对应的值
+        fn params(&self) -> Vec<(&'static str, String)> {
+            match self {
+                MsgKey::Adding { name, dept }
+                | MsgKey::AddedOk { name, dept }
+                | MsgKey::RemovedOk { name, dept }
+                | MsgKey::EmployeeNotInDept { name, dept }
+                | MsgKey::TransferSameDept { name, dept }
+                | MsgKey::FoundIn { name, dept }
+                | MsgKey::FindCloseMatch { name, dept }
+                | MsgKey::QueryResult { dept, name } => {
+                    vec![("name", name.clone()), ("dept", dept.clone())]
+                }
+                MsgKey::DeptHeader { dept }
+                | MsgKey::ListDeptHeader { dept }
+                | MsgKey::DeptNotFound { dept }
+                | MsgKey::DeptRemoved { dept }
+                | MsgKey::RenameSameName { dept }
+                | MsgKey::RenameHasSubDepts { dept } => vec![("dept", dept.clone())],
+                MsgKey::EmployeeLine { name } | MsgKey::FindNotFound { name } => vec![("name", name.clone())],
+                MsgKey::UnknownLang { code } => vec![("code", code.clone())],
+                MsgKey::ReadFileFailed { path, error } => {
+                    vec![("path", path.clone()), ("error", error.clone())]
+                }
+                MsgKey::ImportFailed { error }
+                | MsgKey::ImportInterrupted { error }
+                | MsgKey::ExportFailed { error }
+                | MsgKey::IoFailed { error } => {
+                    vec![("error", error.clone())]
+                }
+                MsgKey::ImportColumn { index, column } => {
+                    vec![("index", index.to_string()), ("column", column.clone())]
+                }
+                MsgKey::ImportProgress { done, total, elapsed } => vec![
+                    ("done", done.to_string()),
+                    ("total", total.to_string()),
+                    ("elapsed", elapsed.clone()),
+                ],
+                MsgKey::ImportDone { imported, skipped, merged, new_depts } => vec![
+                    ("imported", imported.to_string()),
+                    ("skipped", skipped.to_string()),
+                    ("merged", merged.to_string()),
+                    ("new_depts", new_depts.to_string()),
+                ],
+                MsgKey::ExportDone { path } => vec![("path", path.clone())],
+                MsgKey::ScriptRejected { count } => vec![("count", count.to_string())],
+                MsgKey::ScriptLineError { line, error } => {
+                    vec![("line", line.to_string()), ("error", error.clone())]
                 }
+                MsgKey::ScriptDone { executed, skipped } => {
+                    vec![("executed", executed.to_string()), ("skipped", skipped.to_string())]
+                }
+                MsgKey::QueryHeader { count } => vec![("count", count.to_string())],
+                MsgKey::StatsReport { departments, employees, average, size } => vec![
+                    ("departments", departments.to_string()),
+                    ("employees", employees.to_string()),
+                    ("average", average.clone()),
+                    ("size", size.clone()),
+                ],
+                MsgKey::DidYouMean { word, verb } => vec![("word", word.clone()), ("verb", verb.clone())],
+                MsgKey::LineTooLong { bytes, max } => vec![("bytes", bytes.to_string()), ("max", max.to_string())],
+                MsgKey::TransferredOk { name, from, to } => {
+                    vec![("name", name.clone()), ("from", from.clone()), ("to", to.clone())]
+                }
+                MsgKey::SaveDone { path, departments } => {
+                    vec![("path", path.clone()), ("departments", departments.to_string())]
+                }
+                MsgKey::SaveFailed { error } | MsgKey::LoadFailed { error } => vec![("error", error.clone())],
+                MsgKey::LoadDone { path, departments, employees } => vec![
+                    ("path", path.clone()),
+                    ("departments", departments.to_string()),
+                    ("employees", employees.to_string()),
+                ],
+                MsgKey::LoadNoChecksum { path } => vec![("path", path.clone())],
+                MsgKey::CsvLoaded { path, employees } => {
+                    vec![("path", path.clone()), ("employees", employees.to_string())]
+                }
+                MsgKey::CsvBadLine { path, line } => vec![("path", path.clone()), ("line", line.to_string())],
+                MsgKey::CsvLoadFailed { path, error } | MsgKey::CsvSaveFailed { path, error } => {
+                    vec![("path", path.clone()), ("error", error.clone())]
+                }
+                MsgKey::CsvSaved { path } => vec![("path", path.clone())],
+                MsgKey::CountLine { dept, count } => vec![("dept", dept.clone()), ("count", count.to_string())],
+                MsgKey::RenamedOk { old, new } => vec![("old", old.clone()), ("new", new.clone())],
+                MsgKey::RenameMerged { old, new, merged } => {
+                    vec![("old", old.clone()), ("new", new.clone()), ("merged", merged.to_string())]
+                }
+                MsgKey::CountTotal { departments, total } => {
+                    vec![("departments", departments.to_string()), ("total", total.to_string())]
+                }
+                MsgKey::Undone { action } => vec![("action", action.clone())],
+                MsgKey::ImportUnknownColumn { column } => vec![("column", column.clone())],
+                MsgKey::ImportUnmappedColumns { columns } => vec![("columns", columns.clone())],
+                MsgKey::CommandUnknown { word } => vec![("word", word.clone())],
+                MsgKey::CommandUnknownSuggest { word, verb } => vec![("word", word.clone()), ("verb", verb.clone())],
+                MsgKey::CommandBadArguments { usage } => vec![("usage", usage.clone())],
+                MsgKey::ScaledInvalidNumber { text } => vec![("text", text.clone())],
+                MsgKey::ScaledUnknownSuffix { suffix } => vec![("suffix", suffix.to_string())],
+                MsgKey::ScaledNotWhole { text } => vec![("text", text.clone())],
+                MsgKey::ChecksumMismatch { expected, actual } => vec![("expected", format!("{:08X}", expected)), ("actual", format!("{:08X}", actual))],
+                MsgKey::RosterMalformed { line, reason } => vec![("line", line.to_string()), ("reason", reason.clone())],
+                MsgKey::RosterUnrepresentable { name } => vec![("name", name.clone())],
+                MsgKey::JsonSyntax { offset, reason } => vec![("offset", offset.to_string()), ("reason", reason.clone())],
+                MsgKey::JsonExpected { expected } => vec![("expected", expected.to_string())],
+                MsgKey::JsonExpectedCommaOr { close } => vec![("close", close.to_string())],
+                MsgKey::HelpUsage { usage } => vec![("usage", usage.clone())],
+                MsgKey::ImportCancelled { done, total } => vec![("done", done.to_string()), ("total", total.to_string())],
+                MsgKey::CsvUnclosedQuote { row } | MsgKey::CsvTextAfterQuote { row } => vec![("row", row.to_string())],
+                MsgKey::LoadRecovered { path } => vec![("path", path.clone())],
+                MsgKey::LoadRecoverHint { path } => vec![("path", path.clone())],
+                _ => Vec::new(),
+            }
+        }
+
+        // 每个变体各一个样例，用来检查目录是否完整
+        #[cfg(test)]
+        pub fn samples() -> Vec<MsgKey> {
+            let s = String::new;
+            vec![
+                MsgKey::WelcomeBanner,
+                MsgKey::CommandHint,
+                MsgKey::Adding { name: s(), dept: s() },
+                MsgKey::AddedOk { name: s(), dept: s() },
+                MsgKey::ListAllHeader,
+                MsgKey::DeptHeader { dept: s() },
+                MsgKey::EmployeeLine { name: s() },
+                MsgKey::ListDeptHeader { dept: s() },
+                MsgKey::DeptNotFound { dept: s() },
+                MsgKey::RosterHeader,
+                MsgKey::LangSwitched,
+                MsgKey::UnknownLang { code: s() },
+                MsgKey::ReadFileFailed { path: s(), error: s() },
+                MsgKey::ImportFailed { error: s() },
+                MsgKey::ImportColumnsHeader,
+                MsgKey::ImportColumn { index: 0, column: s() },
+                MsgKey::AskNameColumn,
+                MsgKey::AskDeptColumn,
+                MsgKey::ImportProgress { done: 0, total: 0, elapsed: s() },
+                MsgKey::ImportDone { imported: 0, skipped: 0, merged: 0, new_depts: 0 },
+                MsgKey::ImportInterrupted { error: s() },
+                MsgKey::ExportDone { path: s() },
+                MsgKey::ExportFailed { error: s() },
+                MsgKey::ScriptRejected { count: 0 },
+                MsgKey::ScriptLineError { line: 0, error: s() },
+                MsgKey::ScriptDone { executed: 0, skipped: 0 },
+                MsgKey::QueryHeader { count: 0 },
+                MsgKey::QueryResult { dept: s(), name: s() },
+                MsgKey::StatsReport { departments: 0, employees: 0, average: s(), size: s() },
+                MsgKey::DidYouMean { word: s(), verb: s() },
+                MsgKey::LineTooLong { bytes: 0, max: 0 },
+                MsgKey::IoFailed { error: s() },
+                MsgKey::HintCheckPath,
+                MsgKey::HintCheckPermissions,
+                MsgKey::HintCheckEncoding,
+                MsgKey::RemovedOk { name: s(), dept: s() },
+                MsgKey::DeptRemoved { dept: s() },
+                MsgKey::EmployeeNotInDept { name: s(), dept: s() },
+                MsgKey::TransferredOk { name: s(), from: s(), to: s() },
+                MsgKey::TransferSameDept { name: s(), dept: s() },
+                MsgKey::SaveDone { path: s(), departments: 0 },
+                MsgKey::SaveFailed { error: s() },
+                MsgKey::LoadDone { path: s(), departments: 0, employees: 0 },
+                MsgKey::LoadFailed { error: s() },
+                MsgKey::LoadNoChecksum { path: s() },
+                MsgKey::CsvLoaded { path: s(), employees: 0 },
+                MsgKey::CsvBadLine { path: s(), line: 0 },
+                MsgKey::CsvLoadFailed { path: s(), error: s() },
+                MsgKey::CsvSaved { path: s() },
+                MsgKey::CsvSaveFailed { path: s(), error: s() },
+                MsgKey::CountLine { dept: s(), count: 0 },
+                MsgKey::RenamedOk { old: s(), new: s() },
+                MsgKey::RenameMerged { old: s(), new: s(), merged: 0 },
+                MsgKey::RenameSameName { dept: s() },
+                MsgKey::RenameHasSubDepts { dept: s() },
+                MsgKey::FoundIn { name: s(), dept: s() },
+                MsgKey::FindNotFound { name: s() },
+                MsgKey::FindCloseMatch { name: s(), dept: s() },
+                MsgKey::CountTotal { departments: 0, total: 0 },
+                MsgKey::Undone { action: s() },
+                MsgKey::NothingToUndo,
+                MsgKey::ImportMissingHeader,
+                MsgKey::ImportUnknownColumn { column: s() },
+                MsgKey::ImportUnmappedColumns { columns: s() },
+                MsgKey::CommandEmpty,
+                MsgKey::CommandUnknown { word: s() },
+                MsgKey::CommandUnknownSuggest { word: s(), verb: s() },
+                MsgKey::CommandBadArguments { usage: s() },
+                MsgKey::CommandNestedRun,
+                MsgKey::ScaledEmpty,
+                MsgKey::ScaledNegative,
+                MsgKey::ScaledInvalidNumber { text: s() },
+                MsgKey::ScaledUnknownSuffix { suffix: ' ' },
+                MsgKey::ScaledNotWhole { text: s() },
+                MsgKey::ScaledOverflow,
+                MsgKey::ChecksumMismatch { expected: 0, actual: 0 },
+                MsgKey::ChecksumTruncated,
+                MsgKey::RosterMalformed { line: 0, reason: s() },
+                MsgKey::RosterMissingColon,
+                MsgKey::RosterEmptyDept,
+                MsgKey::RosterDuplicateDept,
+                MsgKey::RosterEmptyEmployee,
+                MsgKey::RosterUnrepresentable { name: s() },
+                MsgKey::JsonSyntax { offset: 0, reason: s() },
+                MsgKey::JsonExpected { expected: ' ' },
+                MsgKey::JsonExpectedCommaOr { close: ' ' },
+                MsgKey::JsonDuplicateDept,
+                MsgKey::JsonExpectedString,
+                MsgKey::JsonUnterminatedString,
+                MsgKey::JsonControlChar,
+                MsgKey::JsonBadHexEscape,
+                MsgKey::JsonMissingLowSurrogate,
+                MsgKey::JsonInvalidCodepoint,
+                MsgKey::JsonUnknownEscape,
+                MsgKey::JsonTrailingContent,
+                MsgKey::HelpAdd,
+                MsgKey::HelpRemove,
+                MsgKey::HelpTransfer,
+                MsgKey::HelpMove,
+                MsgKey::HelpRename,
+                MsgKey::HelpList,
+                MsgKey::HelpRoster,
+                MsgKey::HelpImport,
+                MsgKey::HelpExport,
+                MsgKey::HelpExportJson,
+                MsgKey::HelpImportJson,
+                MsgKey::HelpUndo,
+                MsgKey::HelpSave,
+                MsgKey::HelpLoad,
+                MsgKey::HelpLang,
+                MsgKey::HelpRun,
+                MsgKey::HelpQuery,
+                MsgKey::HelpStats,
+                MsgKey::HelpFind,
+                MsgKey::HelpCount,
+                MsgKey::HelpHelp,
+                MsgKey::HelpQuit,
+                MsgKey::HelpOverviewHeader,
+                MsgKey::HelpUsage { usage: s() },
+                MsgKey::HelpExamples,
+                MsgKey::ImportCancelled { done: 0, total: 0 },
+                MsgKey::CsvUnclosedQuote { row: 0 },
+                MsgKey::CsvTextAfterQuote { row: 0 },
+                MsgKey::LoadRecovered { path: s() },
+                MsgKey::LoadRecoverHint { path: s() },
+                MsgKey::InvalidCommand,
+                MsgKey::QuitFarewell,
+            ]
+        }
+    }
+
+    // 一种语言的消息目录：消息名 -> 模板
+    pub struct Catalog {
+        pub entries: &'static [(&'static str, &'static str)],
+    }
+
+    impl Catalog {
+        pub fn for_lang(lang: Lang) -> Catalog {
+            match lang {
+                Lang::En => Catalog { entries: EN },
+                Lang::Zh => Catalog { entries: ZH },
             }
+        }
 
-            // 模式四：匹配 "Quit" 命令
-            ["Quit"] => {
-                println!("Thanks,Bye!");
-                break;
+        pub fn get(&self, id: &str) -> Option<&'static str> {
+            self.entries.iter().find(|(key, _)| *key == id).map(|(_, template)| *template)
+        }
+
+        // 返回这个目录里缺少的消息名，空列表表示翻译完整
+        #[cfg(test)]
+        pub fn missing(&self) -> Vec<&'static str> {
+            MsgKey::samples()
+                .iter()
+                .map(|key| key.id())
+                .filter(|id| self.get(id).is_none())
+                .collect()
+        }
+    }
+
+    // 缺少翻译时退回英文，并在前面加上这个标记，方便发现漏翻的消息
+    pub const FALLBACK_MARKER: &str = "[en] ";
+
+    const EN: &[(&str, &str)] = &[
+        ("welcome_banner", "Welcome to Company System!"),
+        ("command_hint", "Commands: Add <name> to <dept>, List <dept>, List All, Roster, Import <file>, Lang <en|zh>, Export html <file>, Run <file> [--lenient], Query dept=<a,b> contains=<text> limit=<n>, Stats, Help [command], Remove <name> from <dept>, Transfer <name> from <dept> to <dept>, Save <file>, Load <file> [--recover] (replaces current data), Move <name> from <dept> to <dept>, Count [dept], Rename <dept> to <dept>, Find <name>, Export <file.csv>, ExportJson <file>, ImportJson <file>, Undo, Quit"),
+        ("adding", "Adding {name} to {dept}..."),
+        ("added_ok", "Added {name} to {dept}."),
+        ("list_all_header", "All departments and employees:"),
+        ("dept_header", "\n ## {dept} ##"),
+        ("employee_line", "- {name}"),
+        ("list_dept_header", "Employees in {dept}:"),
+        ("dept_not_found", "Department '{dept}' not found"),
+        ("roster_header", "Company roster:"),
+        ("lang_switched", "Language set to English."),
+        ("unknown_lang", "Unknown language '{code}', use 'en' or 'zh'"),
+        ("read_file_failed", "Cannot read file '{path}': {error}"),
+        ("import_failed", "Import failed: {error}"),
+        ("import_columns_header", "Columns in the file:"),
+        ("import_column", "  {index}. {column}"),
+        ("ask_name_column", "Which column holds the employee name? (number or name)"),
+        ("ask_dept_column", "Which column holds the department? (number or name)"),
+        ("import_progress", "  Progress: {done}/{total} ({elapsed})"),
+        ("import_done", "Import finished: {imported} imported, {skipped} skipped, {merged} duplicates merged, {new_depts} new departments"),
+        ("import_interrupted", "Import interrupted: {error}"),
+        ("export_done", "Report written to {path}"),
+        ("export_failed", "Export failed: {error}"),
+        ("script_rejected", "Script has {count} error(s); nothing was executed:"),
+        ("script_line_error", "  line {line}: {error}"),
+        ("script_done", "Script finished: {executed} command(s) executed, {skipped} line(s) skipped"),
+        ("query_header", "{count} result(s):"),
+        ("query_result", "- {name} ({dept})"),
+        ("stats_report", "{departments} departments, {employees} employees, average name length {average}, about {size} in memory"),
+        ("did_you_mean", "Unknown command '{word}'. Did you mean '{verb}'? Type Help for all commands."),
+        ("line_too_long", "Line too long ({bytes} bytes, limit {max}), ignored."),
+        ("io_failed", "Input/output error: {error}"),
+        ("hint_check_path", "Hint: check that the path is correct"),
+        ("hint_check_permissions", "Hint: check the file's read/write permissions"),
+        ("hint_check_encoding", "Hint: the file must be UTF-8 text"),
+        ("removed_ok", "Removed {name} from {dept}."),
+        ("dept_removed", "Department '{dept}' is now empty and has been removed."),
+        ("employee_not_in_dept", "{name} is not in {dept}."),
+        ("transferred_ok", "Moved {name} from {from} to {to}."),
+        ("transfer_same_dept", "{name} is already in {dept}; nothing to do."),
+        ("save_done", "Saved {departments} departments to {path}."),
+        ("save_failed", "Save failed: {error}"),
+        ("load_done", "Loaded {departments} departments and {employees} employees from {path}; previous data was replaced."),
+        ("load_failed", "Load failed: {error}"),
+        ("load_no_checksum", "Warning: {path} has no checksum line; it may be hand-written or truncated."),
+        ("csv_loaded", "Loaded {employees} employees from {path}."),
+        ("csv_bad_line", "Warning: skipping malformed line {line} in {path}."),
+        ("csv_load_failed", "Could not read {path}, starting empty: {error}"),
+        ("csv_saved", "Data saved to {path}."),
+        ("csv_save_failed", "Could not save to {path}: {error}"),
+        ("count_line", "{dept}: {count}"),
+        ("renamed_ok", "Renamed {old} to {new}."),
+        ("rename_merged", "Merged {old} into {new}: {merged} employee(s) added."),
+        ("rename_same_name", "{dept} already has that name; nothing to do."),
+        ("rename_has_sub_depts", "Cannot rename {dept}: it has sub-departments."),
+        ("found_in", "{name} is in {dept}"),
+        ("find_not_found", "{name} not found"),
+        ("find_close_match", "  Did you mean {name} in {dept}?"),
+        ("count_total", "Total: {total} employees in {departments} departments"),
+        ("undone", "Undid: {action}"),
+        ("nothing_to_undo", "Nothing to undo."),
+        ("import_missing_header", "The file is empty; no header row found."),
+        ("import_unknown_column", "The header has no column '{column}'."),
+        ("import_unmapped_columns", "These columns are not mapped: {columns}"),
+        ("command_empty", "Empty command"),
+        ("command_unknown", "Unknown command '{word}'"),
+        ("command_unknown_suggest", "Unknown command '{word}'; did you mean '{verb}'?"),
+        ("command_bad_arguments", "Wrong arguments, usage: {usage}"),
+        ("command_nested_run", "Run cannot be used inside a script"),
+        ("scaled_empty", "The amount cannot be empty"),
+        ("scaled_negative", "The amount cannot be negative"),
+        ("scaled_invalid_number", "'{text}' is not a valid number"),
+        ("scaled_unknown_suffix", "Unknown unit '{suffix}'; use k, m or b"),
+        ("scaled_not_whole", "'{text}' is not a whole number after scaling"),
+        ("scaled_overflow", "The amount is too large"),
+        ("checksum_mismatch", "Checksum mismatch: the file records {expected}, but the contents give {actual}"),
+        ("checksum_truncated", "The checksum line at the end of the file is missing; the file may be truncated"),
+        ("roster_malformed", "Line {line} is malformed: {reason}"),
+        ("roster_missing_colon", "missing ':'"),
+        ("roster_empty_dept", "empty department name"),
+        ("roster_duplicate_dept", "department appears more than once"),
+        ("roster_empty_employee", "empty employee name"),
+        ("roster_unrepresentable", "'{name}' contains ':', ',' or a line break and cannot be saved"),
+        ("json_syntax", "Invalid JSON (byte {offset}): {reason}"),
+        ("json_expected", "expected '{expected}' here"),
+        ("json_expected_comma_or", "expected ',' or '{close}' here"),
+        ("json_duplicate_dept", "duplicate department name"),
+        ("json_expected_string", "expected a string here"),
+        ("json_unterminated_string", "unterminated string"),
+        ("json_control_char", "control characters must be escaped inside strings"),
+        ("json_bad_hex_escape", "\\u must be followed by 4 hex digits"),
+        ("json_missing_low_surrogate", "high surrogate without a low surrogate"),
+        ("json_invalid_codepoint", "not a valid Unicode character"),
+        ("json_unknown_escape", "unknown escape sequence"),
+        ("json_trailing_content", "unexpected content after the object"),
+        ("help_add", "Add an employee to a department; the department can be a path like Sales/East"),
+        ("help_remove", "Remove an employee from a department; the department is deleted once it is empty"),
+        ("help_transfer", "Move an employee to another department; nothing changes if any step fails"),
+        ("help_move", "Same as Transfer: move an employee to another department, changing nothing if they are not in the source"),
+        ("help_rename", "Rename a department; if the new name exists, the two departments' employees are merged (duplicates dropped)"),
+        ("help_list", "List the employees of a department and its sub-departments; List All lists every department as a tree"),
+        ("help_roster", "List every employee in the company alphabetically"),
+        ("help_import", "Import employees from a CSV file"),
+        ("help_export", "Export all employees as a CSV file (department,employee), or as an HTML page with html"),
+        ("help_export_json", "Export all departments as a JSON object, e.g. {\"Sales\": [\"Amir\"]}"),
+        ("help_import_json", "Read a file in the ExportJson format and merge it into the current data"),
+        ("help_undo", "Undo the last Add, Remove, Move/Transfer or Rename; can be repeated"),
+        ("help_save", "Save all departments and employees to a file with a checksum at the end"),
+        ("help_load", "Read a file written by Save, replacing all current departments and employees; with --recover, a damaged file is read as far as possible"),
+        ("help_lang", "Switch the interface language"),
+        ("help_run", "Run the commands in a script file; with --lenient, lines with errors are skipped"),
+        ("help_query", "Query employees by department, name and sort order"),
+        ("help_stats", "Print the number of departments and employees and the estimated memory use"),
+        ("help_find", "List every department an employee is in; suggests close names when there is no exact match"),
+        ("help_count", "List the head count of each department, largest first, or of a single department"),
+        ("help_help", "List all commands, or show the detailed usage of one command"),
+        ("help_quit", "Quit the program"),
+        ("help_overview_header", "Available commands (type Help <command> for details):"),
+        ("help_usage", "Usage: {usage}"),
+        ("help_examples", "Examples:"),
+        ("import_cancelled", "Import cancelled after {done} of {total} records; nothing was changed."),
+        ("csv_unclosed_quote", "Row {row}: a quote is never closed"),
+        ("csv_text_after_quote", "Row {row}: unexpected text after a closing quote"),
+        ("load_recovered", "Warning: {path} failed its checksum or was truncated; loaded what could still be read, some data may be missing."),
+        ("load_recover_hint", "Use 'Load {path} --recover' to load whatever can still be read."),
+        ("invalid_command", "Invalid command. Valid forms: 'Add <name> to <department>', 'List <department>', 'List All', 'Roster', 'Import <file>', 'Lang <en|zh>', 'Export html <file>', 'Run <file> [--lenient]', 'Query ...', 'Stats', 'Help [command]', 'Remove <name> from <department>', 'Transfer <name> from <department> to <department>', 'Save <file>', 'Load <file> [--recover]', 'Move <name> from <department> to <department>', 'Count [department]', 'Rename <department> to <department>', 'Find <name>', 'Export <file>', 'ExportJson <file>', 'ImportJson <file>', 'Undo', 'Quit'"),
+        ("quit_farewell", "Thanks, bye!"),
+    ];
+
+    const ZH: &[(&str, &str)] = &[
+        ("welcome_banner", "欢迎使用公司管理系统！"),
+        ("command_hint", "可用命令：Add <名字> to <部门>、List <部门>、List All、Roster、Import <文件>、Lang <en|zh>、Export html <文件>、Run <文件> [--lenient]、Query dept=<部门,部门> contains=<文字> limit=<数量>、Stats、Help [命令]、Remove <名字> from <部门>、Transfer <名字> from <部门> to <部门>、Save <文件>、Load <文件> [--recover]（替换当前数据）、Move <名字> from <部门> to <部门>、Count [部门]、Rename <部门> to <部门>、Find <名字>、Export <文件.csv>、ExportJson <文件>、ImportJson <文件>、Undo、Quit"),
+        ("adding", "正在添加{name}到{dept}部门..."),
+        ("added_ok", "添加成功！{name} 已加入 {dept}"),
+        ("list_all_header", "公司所有部门及员工列表："),
+        ("dept_header", "\n ## {dept} ##"),
+        ("employee_line", "- {name}"),
+        ("list_dept_header", "{dept}部门的员工列表:"),
+        ("dept_not_found", "未找到'{dept}'部门"),
+        ("roster_header", "全公司员工名单："),
+        ("lang_switched", "已切换为中文。"),
+        ("unknown_lang", "未知语言'{code}'，请使用 'en' 或 'zh'"),
+        ("read_file_failed", "无法读取文件'{path}': {error}"),
+        ("import_failed", "导入失败: {error}"),
+        ("import_columns_header", "文件中的列:"),
+        ("import_column", "  {index}. {column}"),
+        ("ask_name_column", "哪一列是员工名字？(序号或列名)"),
+        ("ask_dept_column", "哪一列是部门？(序号或列名)"),
+        ("import_progress", "  进度: {done}/{total} ({elapsed})"),
+        ("import_done", "导入完成：导入 {imported} 行，跳过 {skipped} 行，合并重复 {merged} 条，新建部门 {new_depts} 个"),
+        ("import_interrupted", "导入中断: {error}"),
+        ("export_done", "报表已写入 {path}"),
+        ("export_failed", "导出失败: {error}"),
+        ("script_rejected", "脚本有 {count} 处错误，没有执行任何命令："),
+        ("script_line_error", "  第 {line} 行: {error}"),
+        ("script_done", "脚本执行完毕：执行了 {executed} 条命令，跳过 {skipped} 行"),
+        ("query_header", "共 {count} 条结果："),
+        ("query_result", "- {name}（{dept}）"),
+        ("stats_report", "共 {departments} 个部门、{employees} 名员工，平均名字长度 {average}，内存占用约 {size}"),
+        ("did_you_mean", "未知命令 '{word}'，你是不是想输入 '{verb}'？输入 Help 查看所有命令。"),
+        ("line_too_long", "这一行太长了（{bytes} 字节，上限 {max}），已忽略。"),
+        ("io_failed", "输入输出出错: {error}"),
+        ("hint_check_path", "提示: 请检查文件路径是否正确"),
+        ("hint_check_permissions", "提示: 请检查文件的读写权限"),
+        ("hint_check_encoding", "提示: 文件必须是 UTF-8 编码的文本"),
+        ("removed_ok", "已将 {name} 从 {dept} 中移除"),
+        ("dept_removed", "'{dept}' 部门已经没有员工，该部门已被删除"),
+        ("employee_not_in_dept", "{dept} 部门中没有 {name}"),
+        ("transferred_ok", "已将 {name} 从 {from} 调到 {to}"),
+        ("transfer_same_dept", "{name} 已经在 {dept} 部门了，无需调动"),
+        ("save_done", "已将 {departments} 个部门保存到 {path}"),
+        ("save_failed", "保存失败: {error}"),
+        ("load_done", "已从 {path} 读取 {departments} 个部门、{employees} 名员工，原来的数据已被替换"),
+        ("load_failed", "读取失败: {error}"),
+        ("load_no_checksum", "警告: {path} 没有校验行，可能是手工编写的或者被截断了"),
+        ("csv_loaded", "已从 {path} 读取 {employees} 名员工"),
+        ("csv_bad_line", "警告: {path} 第 {line} 行格式不对，已跳过"),
+        ("csv_load_failed", "无法读取 {path}，从空数据开始: {error}"),
+        ("csv_saved", "数据已保存到 {path}"),
+        ("csv_save_failed", "无法保存到 {path}: {error}"),
+        ("count_line", "{dept}：{count} 人"),
+        ("renamed_ok", "已将 {old} 部门改名为 {new}"),
+        ("rename_merged", "已将 {old} 部门合并到 {new}，新加入 {merged} 名员工"),
+        ("rename_same_name", "{dept} 已经是这个名字了，无需修改"),
+        ("rename_has_sub_depts", "无法修改 {dept} 的名字：它还有下级部门"),
+        ("found_in", "{name} 在 {dept} 部门"),
+        ("find_not_found", "没有找到 {name}"),
+        ("find_close_match", "  是不是要找 {dept} 部门的 {name}？"),
+        ("count_total", "合计：{departments} 个部门，{total} 名员工"),
+        ("undone", "已撤销: {action}"),
+        ("nothing_to_undo", "没有可以撤销的操作"),
+        ("import_missing_header", "文件是空的，找不到表头"),
+        ("import_unknown_column", "表头中没有'{column}'这一列"),
+        ("import_unmapped_columns", "以下列没有被映射: {columns}"),
+        ("command_empty", "空命令"),
+        ("command_unknown", "未知命令'{word}'"),
+        ("command_unknown_suggest", "未知命令'{word}'，你是不是想输入 '{verb}'？"),
+        ("command_bad_arguments", "参数不对，用法: {usage}"),
+        ("command_nested_run", "脚本中不能再使用 Run"),
+        ("scaled_empty", "数量不能为空"),
+        ("scaled_negative", "数量不能是负数"),
+        ("scaled_invalid_number", "'{text}' 不是一个有效的数字"),
+        ("scaled_unknown_suffix", "未知的单位 '{suffix}'，可以使用 k、m、b"),
+        ("scaled_not_whole", "'{text}' 换算后不是整数"),
+        ("scaled_overflow", "数量太大了"),
+        ("checksum_mismatch", "校验和不一致：文件记录的是 {expected}，实际计算出 {actual}"),
+        ("checksum_truncated", "文件末尾缺少校验行，可能被截断了"),
+        ("roster_malformed", "第 {line} 行格式错误: {reason}"),
+        ("roster_missing_colon", "缺少 ':'"),
+        ("roster_empty_dept", "部门名为空"),
+        ("roster_duplicate_dept", "部门重复出现"),
+        ("roster_empty_employee", "存在空的员工名"),
+        ("roster_unrepresentable", "'{name}' 中含有 ':'、',' 或换行符，无法保存"),
+        ("json_syntax", "JSON 格式错误（第 {offset} 字节）: {reason}"),
+        ("json_expected", "这里应该是 '{expected}'"),
+        ("json_expected_comma_or", "这里应该是 ',' 或 '{close}'"),
+        ("json_duplicate_dept", "部门名重复"),
+        ("json_expected_string", "这里应该是字符串"),
+        ("json_unterminated_string", "字符串没有结束"),
+        ("json_control_char", "字符串里不能直接出现控制字符"),
+        ("json_bad_hex_escape", "\\u 后面应该是 4 个十六进制数字"),
+        ("json_missing_low_surrogate", "高代理后面缺少低代理"),
+        ("json_invalid_codepoint", "不是合法的 Unicode 字符"),
+        ("json_unknown_escape", "未知的转义字符"),
+        ("json_trailing_content", "对象结束后还有多余的内容"),
+        ("help_add", "把员工添加到部门，部门可以是 Sales/East 这样的多级路径"),
+        ("help_remove", "把员工从部门中移除，部门变空时一起删掉"),
+        ("help_transfer", "把员工调到另一个部门，任何一步出错都不会修改数据"),
+        ("help_move", "和 Transfer 相同：把员工移到另一个部门，员工不在来源部门时什么也不改"),
+        ("help_rename", "修改部门名；新名字已经存在时把两个部门的员工合并（去掉重复的名字）"),
+        ("help_list", "列出一个部门及其下级部门的员工；List All 按层级列出所有部门"),
+        ("help_roster", "按字母顺序列出公司的所有员工"),
+        ("help_import", "从 CSV 文件导入员工"),
+        ("help_export", "把所有员工导出成 CSV 文件（department,employee），或者加上 html 导出成 HTML 页面"),
+        ("help_export_json", "把所有部门导出成 JSON 对象，例如 {\"Sales\": [\"Amir\"]}"),
+        ("help_import_json", "读取 ExportJson 格式的文件，合并进现有数据"),
+        ("help_undo", "撤销最近一次 Add、Remove、Move/Transfer 或 Rename，可以连续撤销多次"),
+        ("help_save", "把所有部门和员工保存到文件，文件末尾带校验和"),
+        ("help_load", "从 Save 保存的文件读取数据，会替换掉当前的全部部门和员工；加上 --recover 时尽量读取损坏的文件"),
+        ("help_lang", "切换界面语言"),
+        ("help_run", "执行脚本文件里的命令；加上 --lenient 时跳过出错的行"),
+        ("help_query", "按部门、名字和排序方式查询员工"),
+        ("help_stats", "打印部门数、员工数和估计的内存占用"),
+        ("help_find", "列出这个员工所在的所有部门；没有完全相同的名字时给出相近的名字"),
+        ("help_count", "按人数从多到少列出每个部门的人数，或者只看一个部门"),
+        ("help_help", "列出所有命令，或者查看某个命令的详细用法"),
+        ("help_quit", "退出程序"),
+        ("help_overview_header", "可用命令（输入 Help <命令> 查看详细用法）："),
+        ("help_usage", "用法: {usage}"),
+        ("help_examples", "例如:"),
+        ("import_cancelled", "导入已取消（已处理 {done}/{total} 条记录），数据没有任何改动"),
+        ("csv_unclosed_quote", "第 {row} 行的引号没有闭合"),
+        ("csv_text_after_quote", "第 {row} 行的引号后面还有多余的字符"),
+        ("load_recovered", "警告: {path} 的校验和不对或者文件被截断了，已经读取了还能读出的内容，部分数据可能丢失"),
+        ("load_recover_hint", "可以用 'Load {path} --recover' 读取还能读出的内容"),
+        ("invalid_command", "无效命令。有效格式: 'Add <name> to <department>', 'List <department>', 'List All', 'Roster', 'Import <file>', 'Lang <en|zh>', 'Export html <file>', 'Run <file> [--lenient]', 'Query ...', 'Stats', 'Help [command]', 'Remove <name> from <department>', 'Transfer <name> from <department> to <department>', 'Save <file>', 'Load <file> [--recover]', 'Move <name> from <department> to <department>', 'Count [department]', 'Rename <department> to <department>', 'Find <name>', 'Export <file>', 'ExportJson <file>', 'ImportJson <file>', 'Undo', 'Quit'"),
+        ("quit_farewell", "谢谢使用，再见！"),
+    ];
+
+    // 把模板里的每个 {占位符} 替换成对应的值。只从左到右扫描模板一遍，
+    // 填进去的值不会再被当作模板，所以名字里带 "{dept}" 也会原样输出；不认识的占位符保持原样
+    fn fill(template: &str, params: &[(&'static str, String)]) -> String {
+        let mut text = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(open) = rest.find('{') {
+            text.push_str(&rest[..open]);
+            let after = &rest[open + 1..];
+            let value = after.find('}').and_then(|close| {
+                let name = &after[..close];
+                params.iter().find(|(key, _)| *key == name).map(|(_, value)| (value, close))
+            });
+            match value {
+                Some((value, close)) => {
+                    text.push_str(value);
+                    rest = &after[close + 1..];
+                }
+                None => {
+                    text.push('{');
+                    rest = after;
+                }
+            }
+        }
+        text.push_str(rest);
+        text
+    }
+
+    // 先查给定的目录，查不到再退回英文目录（带上标记）
+    pub fn render_from(catalog: &Catalog, key: &MsgKey) -> String {
+        let params = key.params();
+        match catalog.get(key.id()) {
+            Some(template) => fill(template, &params),
+            None => {
+                let english = Catalog::for_lang(Lang::En)
+                    .get(key.id())
+                    .unwrap_or("<missing message>");
+                format!("{}{}", FALLBACK_MARKER, fill(english, &params))
+            }
+        }
+    }
+
+    pub fn render(lang: Lang, key: MsgKey) -> String {
+        render_from(&Catalog::for_lang(lang), &key)
+    }
+
+    // 错误类型实现它，按当前语言从目录里取出说明文字。
+    // 这些类型的 Display 都用中文（程序启动时的默认语言），交互程序里按 Lang 命令选择的语言显示
+    pub trait Localize {
+        fn localized(&self, lang: Lang) -> String;
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::collections::HashSet;
+
+        // 没有通配分支：给 MsgKey 新增变体时这里会编译失败，提醒同时把它加进 samples()
+        fn assert_listed(key: &MsgKey) {
+            match key {
+                MsgKey::WelcomeBanner
+                | MsgKey::CommandHint
+                | MsgKey::Adding { .. }
+                | MsgKey::AddedOk { .. }
+                | MsgKey::ListAllHeader
+                | MsgKey::DeptHeader { .. }
+                | MsgKey::EmployeeLine { .. }
+                | MsgKey::ListDeptHeader { .. }
+                | MsgKey::DeptNotFound { .. }
+                | MsgKey::RosterHeader
+                | MsgKey::LangSwitched
+                | MsgKey::UnknownLang { .. }
+                | MsgKey::ReadFileFailed { .. }
+                | MsgKey::ImportFailed { .. }
+                | MsgKey::ImportColumnsHeader
+                | MsgKey::ImportColumn { .. }
+                | MsgKey::AskNameColumn
+                | MsgKey::AskDeptColumn
+                | MsgKey::ImportProgress { .. }
+                | MsgKey::ImportDone { .. }
+                | MsgKey::ImportInterrupted { .. }
+                | MsgKey::ExportDone { .. }
+                | MsgKey::ExportFailed { .. }
+                | MsgKey::ScriptRejected { .. }
+                | MsgKey::ScriptLineError { .. }
+                | MsgKey::ScriptDone { .. }
+                | MsgKey::QueryHeader { .. }
+                | MsgKey::QueryResult { .. }
+                | MsgKey::StatsReport { .. }
+                | MsgKey::DidYouMean { .. }
+                | MsgKey::LineTooLong { .. }
+                | MsgKey::IoFailed { .. }
+                | MsgKey::HintCheckPath
+                | MsgKey::HintCheckPermissions
+                | MsgKey::HintCheckEncoding
+                | MsgKey::RemovedOk { .. }
+                | MsgKey::DeptRemoved { .. }
+                | MsgKey::EmployeeNotInDept { .. }
+                | MsgKey::TransferredOk { .. }
+                | MsgKey::TransferSameDept { .. }
+                | MsgKey::SaveDone { .. }
+                | MsgKey::SaveFailed { .. }
+                | MsgKey::LoadDone { .. }
+                | MsgKey::LoadFailed { .. }
+                | MsgKey::LoadNoChecksum { .. }
+                | MsgKey::CsvLoaded { .. }
+                | MsgKey::CsvBadLine { .. }
+                | MsgKey::CsvLoadFailed { .. }
+                | MsgKey::CsvSaved { .. }
+                | MsgKey::CsvSaveFailed { .. }
+                | MsgKey::CountLine { .. }
+                | MsgKey::RenamedOk { .. }
+                | MsgKey::RenameMerged { .. }
+                | MsgKey::RenameSameName { .. }
+                | MsgKey::RenameHasSubDepts { .. }
+                | MsgKey::FoundIn { .. }
+                | MsgKey::FindNotFound { .. }
+                | MsgKey::FindCloseMatch { .. }
+                | MsgKey::CountTotal { .. }
+                | MsgKey::Undone { .. }
+                | MsgKey::NothingToUndo
+                | MsgKey::ImportMissingHeader
+                | MsgKey::ImportUnknownColumn { .. }
+                | MsgKey::ImportUnmappedColumns { .. }
+                | MsgKey::CommandEmpty
+                | MsgKey::CommandUnknown { .. }
+                | MsgKey::CommandUnknownSuggest { .. }
+                | MsgKey::CommandBadArguments { .. }
+                | MsgKey::CommandNestedRun
+                | MsgKey::ScaledEmpty
+                | MsgKey::ScaledNegative
+                | MsgKey::ScaledInvalidNumber { .. }
+                | MsgKey::ScaledUnknownSuffix { .. }
+                | MsgKey::ScaledNotWhole { .. }
+                | MsgKey::ScaledOverflow
+                | MsgKey::ChecksumMismatch { .. }
+                | MsgKey::ChecksumTruncated
+                | MsgKey::RosterMalformed { .. }
+                | MsgKey::RosterMissingColon
+                | MsgKey::RosterEmptyDept
+                | MsgKey::RosterDuplicateDept
+                | MsgKey::RosterEmptyEmployee
+                | MsgKey::RosterUnrepresentable { .. }
+                | MsgKey::JsonSyntax { .. }
+                | MsgKey::JsonExpected { .. }
+                | MsgKey::JsonExpectedCommaOr { .. }
+                | MsgKey::JsonDuplicateDept
+                | MsgKey::JsonExpectedString
+                | MsgKey::JsonUnterminatedString
+                | MsgKey::JsonControlChar
+                | MsgKey::JsonBadHexEscape
+                | MsgKey::JsonMissingLowSurrogate
+                | MsgKey::JsonInvalidCodepoint
+                | MsgKey::JsonUnknownEscape
+                | MsgKey::JsonTrailingContent
+                | MsgKey::HelpAdd
+                | MsgKey::HelpRemove
+                | MsgKey::HelpTransfer
+                | MsgKey::HelpMove
+                | MsgKey::HelpRename
+                | MsgKey::HelpList
+                | MsgKey::HelpRoster
+                | MsgKey::HelpImport
+                | MsgKey::HelpExport
+                | MsgKey::HelpExportJson
+                | MsgKey::HelpImportJson
+                | MsgKey::HelpUndo
+                | MsgKey::HelpSave
+                | MsgKey::HelpLoad
+                | MsgKey::HelpLang
+                | MsgKey::HelpRun
+                | MsgKey::HelpQuery
+                | MsgKey::HelpStats
+                | MsgKey::HelpFind
+                | MsgKey::HelpCount
+                | MsgKey::HelpHelp
+                | MsgKey::HelpQuit
+                | MsgKey::HelpOverviewHeader
+                | MsgKey::HelpUsage { .. }
+                | MsgKey::HelpExamples
+                | MsgKey::ImportCancelled { .. }
+                | MsgKey::CsvUnclosedQuote { .. }
+                | MsgKey::CsvTextAfterQuote { .. }
+                | MsgKey::LoadRecovered { .. }
+                | MsgKey::LoadRecoverHint { .. }
+                | MsgKey::InvalidCommand
+                | MsgKey::QuitFarewell => {}
             }
-            // 默认模式：如果用户输入的命令不匹配以上任何一种格式
-            _ => {
-                println!("无效命令。有效格式: 'Add <name> to <department>', 'List <department>', 'List All', 'Quit'");
+        }
+
+        #[test]
+        fn samples_have_one_of_each_variant() {
+            let samples = MsgKey::samples();
+            let variants: HashSet<_> = samples.iter().map(std::mem::discriminant).collect();
+            assert_eq!(variants.len(), samples.len());
+            samples.iter().for_each(assert_listed);
+        }
+
+        #[test]
+        fn both_catalogs_cover_exactly_the_samples() {
+            let sample_ids: HashSet<&str> = MsgKey::samples().iter().map(MsgKey::id).collect();
+            for lang in [Lang::En, Lang::Zh] {
+                let catalog = Catalog::for_lang(lang);
+                assert_eq!(catalog.missing(), Vec::<&str>::new(), "{:?}", lang);
+                let ids: HashSet<&str> = catalog.entries.iter().map(|(id, _)| *id).collect();
+                assert_eq!(ids.len(), catalog.entries.len(), "{:?} 目录里有重复的消息名", lang);
+                assert_eq!(ids, sample_ids, "{:?}", lang);
             }
         }
+
+        #[test]
+        fn substitutes_parameters_in_both_languages() {
+            let key = MsgKey::AddedOk { name: "Sally".to_string(), dept: "Engineering".to_string() };
+            assert_eq!(render(Lang::En, key.clone()), "Added Sally to Engineering.");
+            assert_eq!(render(Lang::Zh, key), "添加成功！Sally 已加入 Engineering");
+        }
+
+        #[test]
+        fn filled_values_are_not_expanded_again() {
+            let key = MsgKey::AddedOk { name: "{dept}".to_string(), dept: "Sales".to_string() };
+            assert_eq!(render(Lang::En, key), "Added {dept} to Sales.");
+            let params = [("name", "{other}".to_string())];
+            assert_eq!(fill("{name} {other} { {", &params), "{other} {other} { {");
+        }
+
+        #[test]
+        fn missing_translation_falls_back_to_english_with_marker() {
+            let partial = Catalog { entries: &[("welcome_banner", "欢迎！")] };
+            assert_eq!(render_from(&partial, &MsgKey::WelcomeBanner), "欢迎！");
+            assert_eq!(render_from(&partial, &MsgKey::QuitFarewell), format!("{}Thanks, bye!", FALLBACK_MARKER));
+        }
     }
 }
 
-/*
- * =====================================================================================
- * 练习挑战 (Challenge Section)
- * =====================================================================================
- *
- * 1. 公司部门管理:
- *    使用 `HashMap` 和 `Vec`，创建一个文本界面来允许用户向公司的某个部门添加员工。
- *    例如，用户可以输入 "Add Sally to Engineering" 或 "Add Amir to Sales"。
- *    然后，用户应该能够输入一个部门名称，程序会打印出该部门所有员工的列表，
- *    并按字母顺序排序。
- *
- * 2. (来自 `11_collections_vector.rs` 的挑战) 使用 HashMap 计算众数:
- *    现在你已经学习了 `HashMap`，请重新完成之前 `Vec` 那一课的挑战：
- *    给定一个整数 `Vec`，编写一个函数返回众数（出现次数最多的值）。
- *    使用 `HashMap` 来记录每个数字出现的次数，会使这个问题变得简单很多。
+// 练习12：
+// 两个小的迭代器适配器：跳过相邻的重复元素，以及从 1 开始编号
+struct DedupConsecutive<I: Iterator> {
+    iter: I,
+    // 上一个交出去的元素，用来和下一个比较
+    last: Option<I::Item>,
+}
+
+impl<I> Iterator for DedupConsecutive<I>
+where
+    I: Iterator,
+    I::Item: PartialEq + Clone,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        // 一直取，直到遇到和上一个不同的元素；每一段相同元素只保留第一个
+        for item in self.iter.by_ref() {
+            if self.last.as_ref() != Some(&item) {
+                self.last = Some(item.clone());
+                return Some(item);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // 最多和原迭代器一样多；只要原迭代器还有元素，至少还能交出一个
+        let (lower, upper) = self.iter.size_hint();
+        (lower.min(1), upper)
+    }
+}
+
+struct Enumerate1<I> {
+    iter: I,
+    count: usize,
+}
+
+impl<I: Iterator> Iterator for Enumerate1<I> {
+    type Item = (usize, I::Item);
+
+    fn next(&mut self) -> Option<(usize, I::Item)> {
+        let item = self.iter.next()?;
+        self.count += 1;
+        Some((self.count, item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // 编号不会改变元素个数，直接沿用
+        self.iter.size_hint()
+    }
+}
+
+// 扩展 trait：为所有迭代器加上 .dedup_consecutive() 和 .enumerate1() 方法
+trait IteratorExt: Iterator + Sized {
+    fn dedup_consecutive(self) -> DedupConsecutive<Self>
+    where
+        Self::Item: PartialEq + Clone,
+    {
+        DedupConsecutive { iter: self, last: None }
+    }
+
+    fn enumerate1(self) -> Enumerate1<Self> {
+        Enumerate1 { iter: self, count: 0 }
+    }
+}
+
+impl<I: Iterator> IteratorExt for I {}
+
+// 练习13：
+// 一个很小的内置词典，足够演示 "cold" -> "warm"
+#[allow(dead_code)]
+const LADDER_WORDS: &[&str] = &[
+    "bold", "bolt", "card", "care", "cart", "coal", "cold", "cord", "core", "corn",
+    "lynx", "ward", "warm", "warn", "word", "wore", "worm",
+];
+
+// 两个单词长度相同，并且恰好只有一个位置的字符不同
+#[allow(dead_code)]
+fn differs_by_one(a: &str, b: &str) -> bool {
+    if a.chars().count() != b.chars().count() {
+        return false;
+    }
+    a.chars().zip(b.chars()).filter(|(x, y)| x != y).count() == 1
+}
+
+// 用广度优先搜索（BFS）找最短的单词阶梯：BFS 按步数一层一层向外扩展，
+// 所以第一次到达终点时走的一定是最短路径。
+// 约定：终点必须在词典里，起点可以不在；起点和终点相同时返回只有一个单词的阶梯。
+// 注意：邻居只通过把某一位换成 'a'..='z' 来生成，所以只支持小写 ASCII 单词。
+#[allow(dead_code)]
+fn word_ladder(start: &str, end: &str, dictionary: &HashSet<String>) -> Option<Vec<String>> {
+    if start.chars().count() != end.chars().count() || !dictionary.contains(end) {
+        return None;
+    }
+    if start == end {
+        return Some(vec![start.to_string()]);
+    }
+
+    // parents 记录每个单词是从哪个单词走过来的，找到终点后顺着它倒推出整条路径
+    let mut parents: HashMap<String, String> = HashMap::new();
+    let mut visited: HashSet<String> = HashSet::new();
+    let mut queue: VecDeque<String> = VecDeque::new();
+    visited.insert(start.to_string());
+    queue.push_back(start.to_string());
+
+    while let Some(word) = queue.pop_front() {
+        let chars: Vec<char> = word.chars().collect();
+        for i in 0..chars.len() {
+            // 不去扫描整个词典，而是把第 i 位换成每个字母，再到 HashSet 里查一下
+            for letter in 'a'..='z' {
+                if letter == chars[i] {
+                    continue;
+                }
+                let mut candidate = chars.clone();
+                candidate[i] = letter;
+                let next: String = candidate.into_iter().collect();
+                if !dictionary.contains(&next) || visited.contains(&next) {
+                    continue;
+                }
+                visited.insert(next.clone());
+                parents.insert(next.clone(), word.clone());
+
+                if next == end {
+                    let mut chain = vec![next];
+                    while let Some(parent) = parents.get(chain.last().unwrap()) {
+                        chain.push(parent.clone());
+                    }
+                    chain.reverse();
+                    return Some(chain);
+                }
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+// 练习14：
+// 出现次数多的字符排在前面，次数相同时按字符本身的顺序排，保证结果稳定
+#[allow(dead_code)]
+fn alphabet_by_frequency(text: &str) -> Vec<char> {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in text.chars() {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+    let mut alphabet: Vec<(char, usize)> = counts.into_iter().collect();
+    // 先按次数从大到小（b 和 a 反过来比较），再按字符从小到大
+    alphabet.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    alphabet.into_iter().map(|(c, _)| c).collect()
+}
+
+// 练习15：
+// 一个很小的 HTML 生成器：所有来自用户的数据都必须经过 escape，
+// 这样员工名字里就算有 "<script>" 也只会被当成普通文字显示
+mod html {
+    // 内嵌的一点样式，导出的文件不依赖任何外部资源
+    const STYLE: &str = "body { font-family: sans-serif; margin: 2em; }\n\
+table { border-collapse: collapse; }\n\
+th, td { border: 1px solid #999; padding: 4px 8px; text-align: left; }\n\
+th { background: #eee; }";
+
+    // 转义 HTML 中有特殊含义的五个字符
+    pub fn escape(s: &str) -> String {
+        let mut escaped = String::with_capacity(s.len());
+        for c in s.chars() {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&#39;"),
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+
+    pub struct HtmlDoc {
+        title: String,
+        // 已经转义好的 body 片段，render 时按顺序拼起来
+        body: Vec<String>,
+    }
+
+    impl HtmlDoc {
+        pub fn new(title: &str) -> Self {
+            HtmlDoc { title: title.to_string(), body: Vec::new() }
+        }
+
+        // level 会被限制在 1 到 6 之间（HTML 只有 h1 到 h6）
+        pub fn heading(&mut self, level: u8, text: &str) {
+            let level = level.clamp(1, 6);
+            self.body.push(format!("<h{0}>{1}</h{0}>", level, escape(text)));
+        }
+
+        pub fn paragraph(&mut self, text: &str) {
+            self.body.push(format!("<p>{}</p>", escape(text)));
+        }
+
+        pub fn table(&mut self, headers: &[&str], rows: &[Vec<String>]) {
+            let mut table = String::from("<table>\n<tr>");
+            for header in headers {
+                table.push_str(&format!("<th>{}</th>", escape(header)));
+            }
+            table.push_str("</tr>\n");
+            for row in rows {
+                table.push_str("<tr>");
+                for cell in row {
+                    table.push_str(&format!("<td>{}</td>", escape(cell)));
+                }
+                table.push_str("</tr>\n");
+            }
+            table.push_str("</table>");
+            self.body.push(table);
+        }
+
+        // 生成一个完整的、可以单独打开的 HTML 文档
+        pub fn render(&self) -> String {
+            format!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body>\n{}\n</body>\n</html>\n",
+                escape(&self.title),
+                STYLE,
+                self.body.join("\n")
+            )
+        }
+    }
+}
+
+// 交互命令遇到的系统错误都通过它变成一段文字（和第 16 课的 report_error 是同一个思路）：
+// 先是错误本身，再沿着 source() 逐层列出 "caused by: ..."，能识别的错误再按当前语言附上一句提示。
+// 系统给出的错误说明没有翻译，原样显示
+fn report_error(lang: Lang, e: &(dyn std::error::Error + 'static)) -> String {
+    let mut out = e.to_string();
+    let mut hint = error_hint(e);
+    let mut current = e.source();
+    while let Some(cause) = current {
+        out.push_str(&format!("\n  caused by: {}", cause));
+        hint = hint.or_else(|| error_hint(cause));
+        current = cause.source();
+    }
+    if let Some(hint) = hint {
+        out.push('\n');
+        out.push_str(&render(lang, hint));
+    }
+    out
+}
+
+// 用 downcast_ref 判断错误的具体类型，常见的几种 io 错误给出提示
+fn error_hint(e: &(dyn std::error::Error + 'static)) -> Option<MsgKey> {
+    match e.downcast_ref::<io::Error>()?.kind() {
+        io::ErrorKind::NotFound => Some(MsgKey::HintCheckPath),
+        io::ErrorKind::PermissionDenied => Some(MsgKey::HintCheckPermissions),
+        // read_to_string 读到不是 UTF-8 的内容时返回 InvalidData
+        io::ErrorKind::InvalidData => Some(MsgKey::HintCheckEncoding),
+        _ => None,
+    }
+}
+
+// 先写到同目录下的临时文件，写完再改名（和第 16 课的 atomic_write 做法相同）。
+// 临时文件名带上进程号和递增计数器，例如 `.report.html.tmp.1234.0`，同时进行的写入不会撞名。
+// 任何一步出错都会删除临时文件，目标文件要么是旧内容，要么是完整的新内容。
+// Export html、ExportJson、Save 和退出时的自动保存都用它
+fn atomic_write(path: &str, contents: &[u8]) -> io::Result<()> {
+    static COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+    let target = std::path::Path::new(path);
+    let n = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    let file_name = target.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    let temp_path = target.with_file_name(format!(".{}.tmp.{}.{}", file_name, std::process::id(), n));
+
+    let result = (|| {
+        let mut file = std::fs::File::create(&temp_path)?;
+        file.write_all(contents)?;
+        // sync_all 确保数据真正落盘之后才进行重命名
+        file.sync_all()?;
+        std::fs::rename(&temp_path, target)
+    })();
+    if result.is_err() {
+        // 清理失败时留下的临时文件，这里的错误可以忽略
+        let _ = std::fs::remove_file(&temp_path);
+        return result;
+    }
+
+    // 尽力同步父目录，让“重命名”本身也落盘；有的平台不支持打开目录，所以忽略错误。
+    // 相对路径 "a.csv" 的 parent 是空字符串，这时同步当前目录
+    let parent = match target.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => std::path::Path::new("."),
+    };
+    if let Ok(dir) = std::fs::File::open(parent) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+// 把部门报表导出成 HTML 表格，中途出错也不会留下写了一半的报表
+fn export_html(departments: &HashMap<String, Vec<String>>, path: &str) -> io::Result<()> {
+    let mut names: Vec<&String> = departments.keys().collect();
+    names.sort();
+    let rows: Vec<Vec<String>> = names
+        .into_iter()
+        .map(|department| {
+            let mut employees = departments[department].clone();
+            employees.sort();
+            vec![department.clone(), employees.len().to_string(), employees.join(", ")]
+        })
+        .collect();
+
+    let mut doc = html::HtmlDoc::new("Company Report");
+    doc.heading(1, "Company Report");
+    doc.paragraph(&format!("{} departments", rows.len()));
+    doc.table(&["Department", "Count", "Employees"], &rows);
+
+    atomic_write(path, doc.render().as_bytes())
+}
+
+// 练习16：
+// 每个部门人数占全公司总人数的百分比，按部门名排序
+#[allow(dead_code)]
+fn department_percentages(map: &HashMap<String, Vec<String>>) -> Vec<(String, f64)> {
+    let total: usize = map.values().map(|employees| employees.len()).sum();
+    // 没有任何员工时不能做除法，直接返回空列表
+    if total == 0 {
+        return Vec::new();
+    }
+    let mut shares: Vec<(String, f64)> = map
+        .iter()
+        .map(|(department, employees)| {
+            (department.clone(), employees.len() as f64 * 100.0 / total as f64)
+        })
+        .collect();
+    shares.sort_by(|a, b| a.0.cmp(&b.0));
+    shares
+}
+
+// 练习17：
+// 解析好的命令。先把一行文字变成 Command，再交给 execute 执行
+#[derive(Debug, Clone, PartialEq)]
+enum Command {
+    Add { name: String, department: String },
+    Remove { name: String, department: String },
+    Transfer { name: String, from: String, to: String },
+    // 练习37
+    Rename { old: String, new: String },
+    ListAll,
+    List(String),
+    Roster,
+    Import(String),
+    ExportHtml(String),
+    // 练习43
+    ExportCsv(String),
+    // 练习45
+    ExportJson(String),
+    ImportJson(String),
+    Save(String),
+    Load { path: String, recover: bool },
+    Lang(Lang),
+    Run { path: String, lenient: bool },
+    Query(QuerySpec),
+    Stats,
+    // 练习36：None 表示所有部门
+    Count(Option<String>),
+    // 练习38
+    Find(String),
+    // 练习47
+    Undo,
+    // 练习29：None 表示列出所有命令
+    Help(Option<&'static CommandSpec>),
+    Quit,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum CommandError {
+    Empty,
+    // suggestion 是拼写最接近的命令名（练习29）
+    UnknownCommand { word: String, suggestion: Option<&'static str> },
+    // 命令名认识，但参数的个数或格式不对
+    BadArguments { spec: &'static CommandSpec },
+    UnknownLang(String),
+    // 脚本里不能再 Run 别的脚本，避免脚本互相调用没完没了
+    NestedRun,
+}
+
+impl Localize for CommandError {
+    fn localized(&self, lang: Lang) -> String {
+        let key = match self {
+            CommandError::Empty => MsgKey::CommandEmpty,
+            CommandError::UnknownCommand { word, suggestion: None } => MsgKey::CommandUnknown { word: word.clone() },
+            CommandError::UnknownCommand { word, suggestion: Some(verb) } => {
+                MsgKey::CommandUnknownSuggest { word: word.clone(), verb: verb.to_string() }
+            }
+            CommandError::BadArguments { spec } => MsgKey::CommandBadArguments { usage: spec.usage() },
+            CommandError::UnknownLang(code) => MsgKey::UnknownLang { code: code.clone() },
+            CommandError::NestedRun => MsgKey::CommandNestedRun,
+        };
+        render(lang, key)
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.localized(Lang::Zh))
+    }
+}
+
+fn parse_command(line: &str) -> Result<Command, CommandError> {
+    // .trim() 会去掉输入字符串首尾的空白字符（比如换行符）
+    // .split_whitespace() 会用空白字符（空格、制表符等）把字符串分割成一个一个的单词
+    // .collect() 把这些单词收集到一个 Vec<&str> 类型的动态数组中
+    let words: Vec<&str> = line.trim().split_whitespace().collect();
+
+    // 第一个单词是命令名，先在命令表 COMMANDS 里找到它（练习29），找不到时附上最接近的命令名
+    let (verb, args) = match words.split_first() {
+        Some((verb, args)) => (*verb, args),
+        None => return Err(CommandError::Empty),
+    };
+    let spec = find_spec(verb).ok_or_else(|| unknown_command(verb))?;
+    let bad_arguments = CommandError::BadArguments { spec };
+
+    // 使用 match 语句按命令名和参数的形状来解析
+    // 这是 Rust 中非常强大和常见的模式匹配功能
+    match (spec.verb, args) {
+        // 匹配 "Add <xxx> to <xxx>" 格式的命令
+        // 部门可以是多级路径，先规范化（练习21）
+        ("Add", [name, "to", department]) => match normalize_dept_path(department) {
+            Some(department) => Ok(Command::Add { name: name.to_string(), department }),
+            None => Err(bad_arguments),
+        },
+        // "Remove <xxx> from <xxx>"（练习31）
+        ("Remove", [name, "from", department]) => match normalize_dept_path(department) {
+            Some(department) => Ok(Command::Remove { name: name.to_string(), department }),
+            None => Err(bad_arguments),
+        },
+        // "Transfer <xxx> from <xxx> to <xxx>"（练习32），"Move" 是同一个命令的另一种写法（练习35）
+        ("Transfer" | "Move", [name, "from", from, "to", to]) => match (normalize_dept_path(from), normalize_dept_path(to)) {
+            (Some(from), Some(to)) => Ok(Command::Transfer { name: name.to_string(), from, to }),
+            _ => Err(bad_arguments),
+        },
+        // "Rename <xxx> to <xxx>"（练习37）
+        ("Rename", [old, "to", new]) => match (normalize_dept_path(old), normalize_dept_path(new)) {
+            (Some(old), Some(new)) => Ok(Command::Rename { old, new }),
+            _ => Err(bad_arguments),
+        },
+        // "List All" 必须写在 "List <xxx>" 前面，否则会被当成名叫 All 的部门
+        ("List", ["All"]) => Ok(Command::ListAll),
+        ("List", [department]) => match normalize_dept_path(department) {
+            Some(department) => Ok(Command::List(department)),
+            None => Err(bad_arguments),
+        },
+        ("Roster", []) => Ok(Command::Roster),
+        ("Import", [path]) => Ok(Command::Import(path.to_string())),
+        ("Export", ["html", path]) => Ok(Command::ExportHtml(path.to_string())),
+        ("Export", [path]) => Ok(Command::ExportCsv(path.to_string())),
+        ("ExportJson", [path]) => Ok(Command::ExportJson(path.to_string())),
+        ("ImportJson", [path]) => Ok(Command::ImportJson(path.to_string())),
+        // 保存和读取整个公司的数据（练习33）
+        ("Save", [path]) => Ok(Command::Save(path.to_string())),
+        ("Load", [path]) => Ok(Command::Load { path: path.to_string(), recover: false }),
+        ("Load", [path, "--recover"]) => Ok(Command::Load { path: path.to_string(), recover: true }),
+        ("Lang", [code]) => match Lang::parse(code) {
+            Some(lang) => Ok(Command::Lang(lang)),
+            None => Err(CommandError::UnknownLang(code.to_string())),
+        },
+        ("Run", [path]) => Ok(Command::Run { path: path.to_string(), lenient: false }),
+        ("Run", [path, "--lenient"]) => Ok(Command::Run { path: path.to_string(), lenient: true }),
+        // 练习18：Query 后面跟着任意多个 key=value 参数
+        ("Query", args) => match parse_query_args(args) {
+            Some(spec) => Ok(Command::Query(spec)),
+            None => Err(bad_arguments),
+        },
+        ("Stats", []) => Ok(Command::Stats),
+        ("Count", []) => Ok(Command::Count(None)),
+        ("Find", [name]) => Ok(Command::Find(name.to_string())),
+        ("Undo", []) => Ok(Command::Undo),
+        ("Count", [department]) => Ok(Command::Count(Some(department.to_string()))),
+        // "Help Xyz" 里的命令名不存在时，和输错命令一样给出建议
+        ("Help", []) => Ok(Command::Help(None)),
+        ("Help", [topic]) => find_spec(topic).map(|spec| Command::Help(Some(spec))).ok_or_else(|| unknown_command(topic)),
+        ("Quit", []) => Ok(Command::Quit),
+        // 命令名认识，但参数的个数或格式不对
+        _ => Err(bad_arguments),
+    }
+}
+
+// 每一项都带着它在脚本里的行号
+type NumberedLines<T> = Vec<(usize, T)>;
+
+// 逐行解析脚本，跳过空行和以 # 开头的注释；行号从 1 开始，和原文件一致
+fn parse_lines(text: &str) -> (NumberedLines<Command>, NumberedLines<CommandError>) {
+    let mut commands = Vec::new();
+    let mut errors = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        match parse_command(trimmed) {
+            Ok(Command::Run { .. }) => errors.push((line_number, CommandError::NestedRun)),
+            Ok(command) => commands.push((line_number, command)),
+            Err(e) => errors.push((line_number, e)),
+        }
+    }
+    (commands, errors)
+}
+
+// 只有所有行都解析成功才返回命令列表；否则把所有错误（而不只是第一个）一起返回
+fn parse_script(text: &str) -> Result<Vec<Command>, Vec<(usize, CommandError)>> {
+    let (commands, errors) = parse_lines(text);
+    if errors.is_empty() {
+        Ok(commands.into_iter().map(|(_, command)| command).collect())
+    } else {
+        Err(errors)
+    }
+}
+
+// 执行一条命令。返回 ControlFlow::Break(()) 表示要退出程序
+fn execute(
+    command: Command,
+    company: &mut Company,
+    journal: &mut undo::Journal,
+    lang: &mut Lang,
+    input: &mut LineInput<impl BufRead>,
+) -> ControlFlow<()> {
+    match command {
+        Command::Add { name, department } => {
+            println!("{}", render(*lang, MsgKey::Adding { name: name.clone(), dept: department.clone() }));
+
+            // 记进撤销历史（练习47），真正的修改在闭包里完成，添加的逻辑在 Company::add_employee 里（练习49）
+            let operation = undo::Operation::Add { name: name.clone(), department: department.clone() };
+            journal.apply(operation, company, |company| company.add_employee(&name, &department));
+            println!("{}", render(*lang, MsgKey::AddedOk { name, dept: department }));
+        }
+
+        Command::Remove { name, department } => {
+            let operation = undo::Operation::Remove { name: name.clone(), department: department.clone() };
+            match journal.apply(operation, company, |company| company.remove_employee(&name, &department)) {
+                RemoveOutcome::Removed { department_deleted } => {
+                    println!("{}", render(*lang, MsgKey::RemovedOk { name, dept: department.clone() }));
+                    if department_deleted {
+                        println!("{}", render(*lang, MsgKey::DeptRemoved { dept: department }));
+                    }
+                }
+                RemoveOutcome::EmployeeNotFound => {
+                    println!("{}", render(*lang, MsgKey::EmployeeNotInDept { name, dept: department }));
+                }
+                RemoveOutcome::DepartmentNotFound => {
+                    println!("{}", render(*lang, MsgKey::DeptNotFound { dept: department }));
+                }
+            }
+        }
+
+        Command::Transfer { name, from, to } => {
+            let operation = undo::Operation::Move { name: name.clone(), from: from.clone(), to: to.clone() };
+            let result = journal.apply(operation, company, |company| company.move_employee(&name, &from, &to));
+            let message = match result {
+                Ok(()) => MsgKey::TransferredOk { name, from, to },
+                Err(TransferError::SameDepartment) => MsgKey::TransferSameDept { name, dept: to },
+                Err(TransferError::SourceNotFound) => MsgKey::DeptNotFound { dept: from },
+                Err(TransferError::EmployeeNotFound) => MsgKey::EmployeeNotInDept { name, dept: from },
+                Err(TransferError::AlreadyInDestination) => MsgKey::TransferSameDept { name, dept: to },
+            };
+            println!("{}", render(*lang, message));
+        }
+
+        Command::ListAll => {
+            println!("{}", render(*lang, MsgKey::ListAllHeader));
+            // render_org_tree 把所有部门按层级排好，子部门会缩进显示（练习21、练习48）。
+            // 超过一页时用分页器一页一页地显示（练习27）
+            let lines: Vec<String> = render_org_tree(company.departments()).lines().map(String::from).collect();
+            if let Err(e) = paginate(&lines, PAGE_SIZE, &mut io::stdout(), input) {
+                println!("{}", render(*lang, MsgKey::IoFailed { error: report_error(*lang, &e) }));
+            }
+        }
+
+        Command::List(department) => {
+            println!("{}", render(*lang, MsgKey::ListDeptHeader { dept: department.clone() }));
+
+            // 查找和排序都在 Company 的方法里（练习49），这里只负责打印。
+            // 排序后同名员工相邻，只打印一次（练习12）
+            match company.list_department(&department) {
+                Some(employees) => {
+                    for employee in employees.into_iter().dedup_consecutive() {
+                        println!("{}", render(*lang, MsgKey::EmployeeLine { name: employee }));
+                    }
+                }
+                None => {
+                    println!("{}", render(*lang, MsgKey::DeptNotFound { dept: department.clone() }));
+                }
+            }
+            // 再按子路径分组列出所有下级部门（练习21）
+            let all = company.list_all();
+            for sub_department in descendant_departments(company.departments(), &department) {
+                println!("{}", render(*lang, MsgKey::DeptHeader { dept: sub_department.clone() }));
+                for employee in all[sub_department].iter().dedup_consecutive() {
+                    println!("{}", render(*lang, MsgKey::EmployeeLine { name: employee.clone() }));
+                }
+            }
+        }
+
+        // 列出全公司所有员工（去重并排序）
+        Command::Roster => {
+            println!("{}", render(*lang, MsgKey::RosterHeader));
+            for employee in all_employees_sorted(company.departments()) {
+                println!("{}", render(*lang, MsgKey::EmployeeLine { name: employee }));
+            }
+        }
+
+        // 从 CSV 文件批量导入员工
+        // 导入和读取不能撤销，之前的撤销历史也不再适用（练习47）
+        Command::Import(path) => {
+            import_interactively(*lang, &path, company, input);
+            journal.clear();
+        }
+
+        // 把部门报表导出成 HTML（练习15）
+        Command::ExportHtml(path) => match export_html(company.departments(), &path) {
+            Ok(()) => println!("{}", render(*lang, MsgKey::ExportDone { path })),
+            Err(e) => println!("{}", render(*lang, MsgKey::ExportFailed { error: report_error(*lang, &e) })),
+        },
+
+        // 每个员工一行的 CSV，带逗号或引号的名字按 RFC 4180 处理（练习43）
+        Command::ExportCsv(path) => match export_csv(company.departments(), &path) {
+            Ok(_) => println!("{}", render(*lang, MsgKey::ExportDone { path })),
+            Err(e) => println!("{}", render(*lang, MsgKey::ExportFailed { error: report_error(*lang, &e) })),
+        },
+
+        // JSON 格式的导出和导入，导入的数据合并进现有数据（练习45）
+        Command::ExportJson(path) => match atomic_write(&path, json::to_json(company.departments()).as_bytes()) {
+            Ok(()) => println!("{}", render(*lang, MsgKey::ExportDone { path })),
+            Err(e) => println!("{}", render(*lang, MsgKey::ExportFailed { error: report_error(*lang, &e) })),
+        },
+        Command::ImportJson(path) => {
+            let text = match std::fs::read_to_string(&path) {
+                Ok(text) => text,
+                Err(e) => {
+                    println!("{}", render(*lang, MsgKey::ReadFileFailed { path, error: report_error(*lang, &e) }));
+                    return ControlFlow::Continue(());
+                }
+            };
+            match json::from_json(&text) {
+                Ok(incoming) => {
+                    let (imported, skipped, merged, new_depts) = company.merge(incoming);
+                    journal.clear();
+                    println!("{}", render(*lang, MsgKey::ImportDone { imported, skipped, merged, new_depts }));
+                }
+                Err(e) => println!("{}", render(*lang, MsgKey::ImportFailed { error: e.localized(*lang) })),
+            }
+        }
+
+        Command::Save(path) => match save_roster(company.departments(), &path) {
+            Ok(()) => println!("{}", render(*lang, MsgKey::SaveDone { path, departments: company.departments().len() })),
+            Err(e) => println!("{}", render(*lang, MsgKey::SaveFailed { error: e.localized(*lang) })),
+        },
+
+        // 读取成功时替换掉当前的全部数据；失败时当前数据保持不变
+        Command::Load { path, recover } => match load_for_command(&path, recover) {
+            Ok((loaded, warning)) => {
+                if let Some(warning) = warning {
+                    println!("{}", render(*lang, warning));
+                }
+                *company = Company::from(loaded);
+                journal.clear();
+                let departments = company.departments();
+                let employees = departments.values().map(|list| list.len()).sum();
+                println!("{}", render(*lang, MsgKey::LoadDone { path, departments: departments.len(), employees }));
+            }
+            Err(e) => {
+                println!("{}", render(*lang, MsgKey::LoadFailed { error: e.localized(*lang) }));
+                // 校验失败的文件也许还能读出大部分内容，告诉用户怎么恢复（练习19）
+                if let RosterError::Checksum(_) = e {
+                    println!("{}", render(*lang, MsgKey::LoadRecoverHint { path }));
+                }
+            }
+        },
+
+        // 切换输出语言（练习11）
+        Command::Lang(new_lang) => {
+            *lang = new_lang;
+            println!("{}", render(*lang, MsgKey::LangSwitched));
+        }
+
+        // 按条件查询员工（练习18）
+        Command::Query(spec) => {
+            let results = run_query(company.departments(), &spec);
+            println!("{}", render(*lang, MsgKey::QueryHeader { count: results.len() }));
+            for employee in results {
+                println!(
+                    "{}",
+                    render(*lang, MsgKey::QueryResult {
+                        dept: employee.department.to_string(),
+                        name: employee.name.to_string(),
+                    })
+                );
+            }
+        }
+
+        // 打印数据规模和估计的内存占用（练习22）
+        Command::Stats => {
+            let (department_count, employee_count, average, bytes) = company_stats(company.departments());
+            println!(
+                "{}",
+                render(*lang, MsgKey::StatsReport {
+                    departments: department_count,
+                    employees: employee_count,
+                    average: format!("{:.1}", average),
+                    size: format_bytes(bytes),
+                })
+            );
+        }
+
+        // 部门改名，新名字已经存在时合并（练习37）
+        Command::Rename { old, new } => {
+            let operation = undo::Operation::Rename { old: old.clone(), new: new.clone() };
+            let result = journal.apply(operation, company, |company| company.rename_department(&old, &new));
+            let message = match result {
+                Ok(None) => MsgKey::RenamedOk { old, new },
+                Ok(Some(merged)) => MsgKey::RenameMerged { old, new, merged },
+                Err(RenameError::SameName) => MsgKey::RenameSameName { dept: old },
+                Err(RenameError::NotFound) => MsgKey::DeptNotFound { dept: old },
+                Err(RenameError::HasSubDepartments) => MsgKey::RenameHasSubDepts { dept: old },
+            };
+            println!("{}", render(*lang, message));
+        }
+
+        // 在所有部门里找一个员工，找不到时列出相近的名字（练习38）
+        Command::Find(name) => {
+            let departments = company.departments();
+            let found = find_employee(departments, &name);
+            if found.is_empty() {
+                println!("{}", render(*lang, MsgKey::FindNotFound { name: name.clone() }));
+                for (employee, dept) in close_employee_matches(departments, &name) {
+                    let message = MsgKey::FindCloseMatch { name: employee.to_string(), dept: dept.to_string() };
+                    println!("{}", render(*lang, message));
+                }
+            } else {
+                for dept in found {
+                    println!("{}", render(*lang, MsgKey::FoundIn { name: name.clone(), dept: dept.to_string() }));
+                }
+            }
+        }
+
+        // 每个部门的人数（练习36）
+        Command::Count(None) => {
+            let counts = department_counts(company.departments());
+            let total = counts.iter().map(|(_, count)| count).sum();
+            let department_count = counts.len();
+            for (dept, count) in counts {
+                println!("{}", render(*lang, MsgKey::CountLine { dept, count }));
+            }
+            println!("{}", render(*lang, MsgKey::CountTotal { departments: department_count, total }));
+        }
+        // 和 List 一样不区分大小写（练习9）；精确匹配优先，"Sales" 和 "sales" 同时存在时按输入的写法查
+        Command::Count(Some(department)) => {
+            let departments = company.departments();
+            let count = departments
+                .get(&department)
+                .map(Vec::len)
+                .or_else(|| departments_ci(departments).get(&department).map(<[_]>::len));
+            match count {
+                Some(count) => println!("{}", render(*lang, MsgKey::CountLine { dept: department, count })),
+                None => println!("{}", render(*lang, MsgKey::DeptNotFound { dept: department })),
+            }
+        }
+
+        // 撤销最近一次 Add、Remove、Move 或 Rename（练习47）
+        Command::Undo => match journal.undo(company) {
+            Some(operation) => println!("{}", render(*lang, MsgKey::Undone { action: operation.to_string() })),
+            None => println!("{}", render(*lang, MsgKey::NothingToUndo)),
+        },
+
+        Command::Run { path, lenient } => return run_script(&path, lenient, company, journal, lang, input),
+
+        // 帮助文字直接来自命令表 COMMANDS（练习29）
+        Command::Help(None) => print!("{}", help_overview(*lang)),
+        Command::Help(Some(spec)) => print!("{}", help_for(*lang, spec)),
+
+        Command::Quit => {
+            // 退出前把数据写到 departments.csv，下次启动时自动读回来（练习34）
+            match atomic_write(AUTOSAVE_CSV, departments_to_csv(company.departments()).as_bytes()) {
+                Ok(()) => println!("{}", render(*lang, MsgKey::CsvSaved { path: AUTOSAVE_CSV.to_string() })),
+                Err(e) => println!("{}", render(*lang, MsgKey::CsvSaveFailed { path: AUTOSAVE_CSV.to_string(), error: report_error(*lang, &e) })),
+            }
+            println!("{}", render(*lang, MsgKey::QuitFarewell));
+            return ControlFlow::Break(());
+        }
+    }
+    ControlFlow::Continue(())
+}
+
+// Run 命令：先解析整个脚本，再执行。
+// 默认模式下只要有一行解析失败，就报告所有错误并且一条命令都不执行；
+// --lenient 模式下跳过错误的行，执行其余的命令，最后再报告跳过了哪些行。
+// 脚本中的 Quit 会结束整个程序。
+fn run_script(
+    path: &str,
+    lenient: bool,
+    company: &mut Company,
+    journal: &mut undo::Journal,
+    lang: &mut Lang,
+    input: &mut LineInput<impl BufRead>,
+) -> ControlFlow<()> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            println!("{}", render(*lang, MsgKey::ReadFileFailed { path: path.to_string(), error: report_error(*lang, &e) }));
+            return ControlFlow::Continue(());
+        }
+    };
+
+    let (commands, errors) = if lenient {
+        let (commands, errors) = parse_lines(&text);
+        (commands.into_iter().map(|(_, command)| command).collect(), errors)
+    } else {
+        match parse_script(&text) {
+            Ok(commands) => (commands, Vec::new()),
+            Err(errors) => {
+                println!("{}", render(*lang, MsgKey::ScriptRejected { count: errors.len() }));
+                for (line, error) in errors {
+                    println!("{}", render(*lang, MsgKey::ScriptLineError { line, error: error.localized(*lang) }));
+                }
+                return ControlFlow::Continue(());
+            }
+        }
+    };
+
+    let mut executed = 0;
+    for command in commands {
+        executed += 1;
+        if execute(command, company, journal, lang, input).is_break() {
+            return ControlFlow::Break(());
+        }
+    }
+    println!("{}", render(*lang, MsgKey::ScriptDone { executed, skipped: errors.len() }));
+    for (line, error) in errors {
+        println!("{}", render(*lang, MsgKey::ScriptLineError { line, error: error.localized(*lang) }));
+    }
+    ControlFlow::Continue(())
+}
+
+// 练习18：
+// 查询结果直接借用 HashMap 里的部门名和员工名，不做任何克隆
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct EmployeeRef<'a> {
+    department: &'a str,
+    name: &'a str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum QuerySort {
+    NameAsc,
+    NameDesc,
+    DeptThenName,
+    NameLenDesc,
+}
+
+// 查询构建器：每个方法只是记下条件，真正的过滤、排序都在 collect 里完成
+struct CompanyQuery<'a> {
+    map: &'a HashMap<String, Vec<String>>,
+    departments: Option<Vec<String>>,
+    name_contains: Option<String>,
+    name_glob: Option<String>,
+    sort: Option<QuerySort>,
+    limit: Option<usize>,
+}
+
+fn query(map: &HashMap<String, Vec<String>>) -> CompanyQuery<'_> {
+    CompanyQuery {
+        map,
+        departments: None,
+        name_contains: None,
+        name_glob: None,
+        sort: None,
+        limit: None,
+    }
+}
+
+impl<'a> CompanyQuery<'a> {
+    // 只看这些部门（部门名不区分大小写）
+    fn departments(mut self, names: &[&str]) -> Self {
+        self.departments = Some(names.iter().map(|name| name.to_lowercase()).collect());
+        self
+    }
+
+    fn name_contains(mut self, text: &str) -> Self {
+        self.name_contains = Some(text.to_string());
+        self
+    }
+
+    fn name_matches_glob(mut self, pattern: &str) -> Self {
+        self.name_glob = Some(pattern.to_string());
+        self
+    }
+
+    fn sort_by(mut self, sort: QuerySort) -> Self {
+        self.sort = Some(sort);
+        self
+    }
+
+    fn limit(mut self, n: usize) -> Self {
+        self.limit = Some(n);
+        self
+    }
+
+    fn collect(&self) -> Vec<EmployeeRef<'a>> {
+        // 为了结果稳定，先按部门名排序，部门内保持员工加入的顺序
+        let mut department_names: Vec<&'a String> = self.map.keys().collect();
+        department_names.sort();
+
+        let mut results: Vec<EmployeeRef<'a>> = Vec::new();
+        for department in department_names {
+            if let Some(wanted) = &self.departments
+                && !wanted.contains(&department.to_lowercase())
+            {
+                continue;
+            }
+            for name in &self.map[department] {
+                if let Some(text) = &self.name_contains
+                    && !name.contains(text.as_str())
+                {
+                    continue;
+                }
+                if let Some(pattern) = &self.name_glob
+                    && !glob_match(pattern, name)
+                {
+                    continue;
+                }
+                results.push(EmployeeRef { department: department.as_str(), name: name.as_str() });
+            }
+        }
+
+        // sort_by 和 sort_by_key 都是稳定排序：比较结果相同的元素会保持原来的先后顺序
+        match self.sort {
+            Some(QuerySort::NameAsc) => results.sort_by(|a, b| a.name.cmp(b.name)),
+            Some(QuerySort::NameDesc) => results.sort_by(|a, b| b.name.cmp(a.name)),
+            Some(QuerySort::DeptThenName) => {
+                results.sort_by(|a, b| a.department.cmp(b.department).then(a.name.cmp(b.name)))
+            }
+            Some(QuerySort::NameLenDesc) => {
+                results.sort_by_key(|r| Reverse(r.name.chars().count()))
+            }
+            None => {}
+        }
+        if let Some(n) = self.limit {
+            results.truncate(n);
+        }
+        results
+    }
+}
+
+// 简单的通配符匹配：'*' 匹配任意多个字符（包括零个），'?' 匹配恰好一个字符
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    // matches[j] 表示 pattern 的前 i 个字符能否匹配 text 的前 j 个字符（逐行滚动更新）
+    let mut matches = vec![false; text.len() + 1];
+    matches[0] = true;
+    for &p in &pattern {
+        let mut next = vec![false; text.len() + 1];
+        if p == '*' {
+            next[0] = matches[0];
+        }
+        for j in 1..=text.len() {
+            next[j] = match p {
+                '*' => matches[j] || next[j - 1],
+                '?' => matches[j - 1],
+                c => matches[j - 1] && text[j - 1] == c,
+            };
+        }
+        matches = next;
+    }
+    matches[text.len()]
+}
+
+// Query 命令的参数，例如 "Query dept=Engineering,Sales contains=a sort=len limit=5"
+#[derive(Debug, Clone, PartialEq, Default)]
+struct QuerySpec {
+    departments: Vec<String>,
+    contains: Option<String>,
+    glob: Option<String>,
+    sort: Option<QuerySort>,
+    limit: Option<usize>,
+}
+
+// 每个参数都是 key=value 的形式，出现不认识的参数就整体报错
+fn parse_query_args(args: &[&str]) -> Option<QuerySpec> {
+    let mut spec = QuerySpec::default();
+    for arg in args {
+        let (key, value) = arg.split_once('=')?;
+        match key {
+            "dept" => spec.departments = value.split(',').map(|d| d.to_string()).collect(),
+            "contains" => spec.contains = Some(value.to_string()),
+            "glob" => spec.glob = Some(value.to_string()),
+            "sort" => {
+                spec.sort = Some(match value {
+                    "name" => QuerySort::NameAsc,
+                    "name-desc" => QuerySort::NameDesc,
+                    "dept" => QuerySort::DeptThenName,
+                    "len" => QuerySort::NameLenDesc,
+                    _ => return None,
+                })
+            }
+            // 数量可以带单位，例如 limit=10k（练习28）
+            "limit" => spec.limit = Some(usize::try_from(parse_scaled_count(value).ok()?).ok()?),
+            _ => return None,
+        }
+    }
+    Some(spec)
+}
+
+// 把 QuerySpec 翻译成对构建器的链式调用
+fn run_query<'a>(map: &'a HashMap<String, Vec<String>>, spec: &QuerySpec) -> Vec<EmployeeRef<'a>> {
+    let mut q = query(map);
+    if !spec.departments.is_empty() {
+        let names: Vec<&str> = spec.departments.iter().map(|d| d.as_str()).collect();
+        q = q.departments(&names);
+    }
+    if let Some(text) = &spec.contains {
+        q = q.name_contains(text);
+    }
+    if let Some(pattern) = &spec.glob {
+        q = q.name_matches_glob(pattern);
+    }
+    if let Some(sort) = spec.sort {
+        q = q.sort_by(sort);
+    }
+    if let Some(n) = spec.limit {
+        q = q.limit(n);
+    }
+    q.collect()
+}
+
+// 练习19：
+// 用 CRC32 校验和检测文件是否被截断或损坏
+mod checksum {
+    use super::messages::{render, Lang, Localize, MsgKey};
+    use std::fmt;
+
+    // 查表法：先算好每个字节值对应的 256 个结果，计算时每个字节只需查一次表。
+    // const fn 让这张表在编译时就生成好
+    const TABLE: [u32; 256] = make_table();
+
+    const fn make_table() -> [u32; 256] {
+        let mut table = [0u32; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                // 0xEDB88320 是标准 CRC32（和 zip、png 相同）多项式的反转形式
+                crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    }
+
+    pub fn crc32(data: &[u8]) -> u32 {
+        let mut crc = 0xFFFF_FFFF;
+        for &byte in data {
+            crc = TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+        }
+        !crc
+    }
+
+    // 带校验行的文件第一行是 HEADER。有 HEADER 却找不到校验行，说明文件被截断了；
+    // 两个都没有的是旧版本（或者手工编写）的文件
+    const HEADER: &str = "#checksum=crc32\n";
+    const TRAILER_PREFIX: &str = "#crc32=";
+
+    // 校验和不一致，说明文件内容被改动过
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct ChecksumMismatch {
+        pub expected: u32,
+        pub actual: u32,
+    }
+
+    impl Localize for ChecksumMismatch {
+        fn localized(&self, lang: Lang) -> String {
+            render(lang, MsgKey::ChecksumMismatch { expected: self.expected, actual: self.actual })
+        }
+    }
+
+    impl fmt::Display for ChecksumMismatch {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.localized(Lang::Zh))
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum TrailerError {
+        Mismatch(ChecksumMismatch),
+        // 文件开头有 HEADER，末尾却没有完整的校验行
+        Truncated,
+    }
+
+    impl Localize for TrailerError {
+        fn localized(&self, lang: Lang) -> String {
+            match self {
+                TrailerError::Mismatch(mismatch) => mismatch.localized(lang),
+                TrailerError::Truncated => render(lang, MsgKey::ChecksumTruncated),
+            }
+        }
+    }
+
+    impl fmt::Display for TrailerError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.localized(Lang::Zh))
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum TrailerStatus {
+        Verified,
+        // 旧文件没有校验行，为了兼容照常读取，但要提醒用户
+        Missing,
+    }
+
+    // 在内容前面加上 HEADER，末尾追加一行 "#crc32=XXXXXXXX"，校验和覆盖这一行之前的全部内容
+    pub fn add_trailer(body: &str) -> String {
+        let mut text = format!("{}{}", HEADER, body);
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        let crc = crc32(text.as_bytes());
+        format!("{}{}{:08X}\n", text, TRAILER_PREFIX, crc)
+    }
+
+    // 最后一行的起始位置，以及这一行是不是完整的校验行（8 位十六进制数，并且以换行结尾）
+    fn last_line(text: &str) -> (usize, Option<u32>) {
+        let without_newline = text.strip_suffix('\n').unwrap_or(text);
+        let line_start = without_newline.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let expected = without_newline[line_start..]
+            .strip_prefix(TRAILER_PREFIX)
+            .filter(|hex| hex.len() == 8 && text.ends_with('\n'))
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok());
+        (line_start, expected)
+    }
+
+    // 找到最后一行的校验行并验证，返回去掉 HEADER 和校验行之后的内容。
+    // 有 HEADER 却没有完整的校验行时返回 Truncated；旧文件两个都没有，返回 Missing，
+    // 由调用者决定是否接受没有校验和的文件
+    pub fn check_trailer(text: &str) -> Result<(&str, TrailerStatus), TrailerError> {
+        let (line_start, expected) = last_line(text);
+        let expected = match expected {
+            Some(expected) => expected,
+            None if text.starts_with(HEADER) => return Err(TrailerError::Truncated),
+            None => return Ok((text, TrailerStatus::Missing)),
+        };
+        let checked = &text[..line_start];
+        let actual = crc32(checked.as_bytes());
+        if actual == expected {
+            Ok((checked.strip_prefix(HEADER).unwrap_or(checked), TrailerStatus::Verified))
+        } else {
+            Err(TrailerError::Mismatch(ChecksumMismatch { expected, actual }))
+        }
+    }
+
+    // 恢复用：不管校验和对不对都返回内容，第二个值为 true 表示内容可能有问题。
+    // 最后一行以 '#' 开头时当作校验行（可能只剩一半）去掉
+    pub fn strip_trailer_ignoring_checksum(text: &str) -> (&str, bool) {
+        if let Ok((body, status)) = check_trailer(text) {
+            return (body, status == TrailerStatus::Missing);
+        }
+        let body = text.strip_prefix(HEADER).unwrap_or(text);
+        let (line_start, _) = last_line(body);
+        if body[line_start..].starts_with('#') {
+            (&body[..line_start], true)
+        } else {
+            (body, true)
+        }
+    }
+}
+
+// 练习20：
+#[allow(dead_code)]
+fn has_unique_chars(s: &str) -> bool {
+    let mut seen: HashSet<char> = HashSet::new();
+    // insert 在元素已经存在时返回 false，说明遇到了重复字符
+    s.chars().all(|c| seen.insert(c))
+}
+
+// 练习21：
+// 部门可以写成用 '/' 分隔的路径，例如 "Engineering/Platform/Infra"。
+// 去掉多余的斜杠和空白段："/Engineering//Platform/" -> "Engineering/Platform"；全是斜杠时返回 None
+fn normalize_dept_path(path: &str) -> Option<String> {
+    let segments: Vec<&str> = path.split('/').map(|s| s.trim()).filter(|s| !s.is_empty()).collect();
+    if segments.is_empty() {
+        None
+    } else {
+        Some(segments.join("/"))
+    }
+}
+
+// "A/B/C" 的所有上级路径：["A", "A/B"]
+fn ancestor_paths(path: &str) -> Vec<String> {
+    let segments: Vec<&str> = path.split('/').collect();
+    (1..segments.len()).map(|n| segments[..n].join("/")).collect()
+}
+
+// path 下面的所有子部门（不区分大小写），按路径排序
+fn descendant_departments<'a>(map: &'a HashMap<String, Vec<String>>, path: &str) -> Vec<&'a String> {
+    let prefix = format!("{}/", path.to_lowercase());
+    let mut found: Vec<&String> = map.keys().filter(|key| key.to_lowercase().starts_with(&prefix)).collect();
+    found.sort();
+    found
+}
+
+// 专门为部门层级准备的树：每个节点有自己的员工和若干子部门。
+// 子部门用 BTreeMap 保存，遍历时自动按名字排好序
+#[derive(Debug, Clone, PartialEq, Default)]
+struct DeptNode {
+    employees: Vec<String>,
+    children: BTreeMap<String, DeptNode>,
+}
+
+impl DeptNode {
+    // 按缩进打印整棵树，每深一层多缩进两个空格，员工比所在的部门再多缩进两个空格
+    fn render(&self) -> String {
+        let mut out = String::new();
+        self.render_into(&mut out, 0);
+        out
+    }
+
+    fn render_into(&self, out: &mut String, depth: usize) {
+        let indent = "  ".repeat(depth);
+        for (name, child) in &self.children {
+            out.push_str(&format!("\n{} ## {} ##\n", indent, name));
+            // 排序后同名员工相邻，只打印一次（练习12）
+            let mut employees = child.employees.clone();
+            employees.sort();
+            for employee in employees.into_iter().dedup_consecutive() {
+                out.push_str(&format!("{}  - {}\n", indent, employee));
+            }
+            child.render_into(out, depth + 1);
+        }
+    }
+}
+
+// 扁平的 "路径 -> 员工" 映射转换成树；缺少的上级节点会自动补上。
+// HashMap 和 BTreeMap（例如 Company::list_all 的结果，练习49）的引用都可以传进来
+fn to_tree<'a>(map: impl IntoIterator<Item = (&'a String, &'a Vec<String>)>) -> DeptNode {
+    let mut root = DeptNode::default();
+    for (path, employees) in map {
+        let mut node = &mut root;
+        for segment in path.split('/') {
+            node = node.children.entry(segment.to_string()).or_default();
+        }
+        node.employees.extend(employees.iter().cloned());
+    }
+    root
+}
+
+// 树转换回扁平映射：每个节点（包括只有子部门、自己没有员工的中间节点）都是一个键
+#[allow(dead_code)]
+fn from_tree(root: &DeptNode) -> HashMap<String, Vec<String>> {
+    fn walk(node: &DeptNode, prefix: &str, map: &mut HashMap<String, Vec<String>>) {
+        for (name, child) in &node.children {
+            let path = if prefix.is_empty() { name.clone() } else { format!("{}/{}", prefix, name) };
+            map.insert(path.clone(), child.employees.clone());
+            walk(child, &path, map);
+        }
+    }
+    let mut map = HashMap::new();
+    walk(root, "", &mut map);
+    map
+}
+
+// 练习22：
+// 粗略估计一个值在内存中占多少字节：自身的大小 + 它在堆上分配的空间。
+// 只要求“大致正确”并且数据变多时估计值不会变小，不追求和分配器的真实情况完全一致
+trait EstimateSize {
+    fn estimate_bytes(&self) -> usize;
+}
+
+// 整数类型没有堆内存，大小就是 size_of。用宏为它们批量实现
+macro_rules! impl_estimate_for_int {
+    ($($t:ty),*) => {
+        $(
+            impl EstimateSize for $t {
+                fn estimate_bytes(&self) -> usize {
+                    std::mem::size_of::<$t>()
+                }
+            }
+        )*
+    };
+}
+
+impl_estimate_for_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl EstimateSize for String {
+    fn estimate_bytes(&self) -> usize {
+        // String 本身（指针、长度、容量）加上堆上分配的全部容量
+        std::mem::size_of::<String>() + self.capacity()
+    }
+}
+
+impl<T: EstimateSize> EstimateSize for Vec<T> {
+    fn estimate_bytes(&self) -> usize {
+        // 已有元素按各自的估计计算（其中已经包含了元素本身的大小），
+        // 还没用到的容量按每个槽位 size_of::<T>() 计算
+        let items: usize = self.iter().map(|item| item.estimate_bytes()).sum();
+        let unused = (self.capacity() - self.len()) * std::mem::size_of::<T>();
+        std::mem::size_of::<Vec<T>>() + items + unused
+    }
+}
+
+impl<K: EstimateSize, V: EstimateSize> EstimateSize for HashMap<K, V> {
+    fn estimate_bytes(&self) -> usize {
+        // 近似：标准库的 HashMap 为每个桶（bucket）额外保存大约 1 字节的控制信息，
+        // 空桶也占着一个 (K, V) 的位置。所以 = 自身 + 所有键值的估计 + 空桶 + 每个桶 1 字节
+        let entries: usize = self.iter().map(|(k, v)| k.estimate_bytes() + v.estimate_bytes()).sum();
+        let slot = std::mem::size_of::<K>() + std::mem::size_of::<V>();
+        let unused = (self.capacity() - self.len()) * slot;
+        std::mem::size_of::<HashMap<K, V>>() + entries + unused + self.capacity()
+    }
+}
+
+// 把字节数转换成容易阅读的形式，例如 1536 -> "1.5 KiB"
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+// Stats 命令需要的统计信息：部门数、员工数、平均名字长度和估计的内存大小
+fn company_stats(map: &HashMap<String, Vec<String>>) -> (usize, usize, f64, usize) {
+    let employees: usize = map.values().map(|names| names.len()).sum();
+    let total_chars: usize = map.values().flatten().map(|name| name.chars().count()).sum();
+    let average = if employees == 0 { 0.0 } else { total_chars as f64 / employees as f64 };
+    (map.len(), employees, average, map.estimate_bytes())
+}
+
+// 练习23：
+// Jaccard 相似度 = 交集大小 / 并集大小。先转换成 HashSet，重复元素只算一次
+#[allow(dead_code)]
+fn jaccard<T: Eq + Hash + Clone>(a: &[T], b: &[T]) -> f64 {
+    let set_a: HashSet<T> = a.iter().cloned().collect();
+    let set_b: HashSet<T> = b.iter().cloned().collect();
+    let union = set_a.union(&set_b).count();
+    // 两个都是空集合时并集也为空，约定它们完全相同
+    if union == 0 {
+        return 1.0;
+    }
+    let intersection = set_a.intersection(&set_b).count();
+    intersection as f64 / union as f64
+}
+
+// 练习24：
+// 把 部门 -> 员工 反过来变成 员工 -> 部门，方便找出同时属于多个部门的员工。
+// 同一个部门里重复出现的员工只记一次，每个员工的部门列表按字母排序
+#[allow(dead_code)]
+fn invert_multimap(map: &HashMap<String, Vec<String>>) -> HashMap<String, Vec<String>> {
+    let mut inverted: HashMap<String, Vec<String>> = HashMap::new();
+    for (key, values) in map {
+        for value in values {
+            inverted.entry(value.clone()).or_default().push(key.clone());
+        }
+    }
+    for keys in inverted.values_mut() {
+        keys.sort();
+        keys.dedup();
+    }
+    inverted
+}
+
+// 练习25：
+// 把“用 HashMap 计数”的写法包装成一个可以不断喂数据的类型。
+// 除了次数，还记下每个 key 第一次出现的顺序，让 top 在次数相同时结果固定
+struct Tally<K: Eq + Hash> {
+    counts: HashMap<K, (usize, usize)>,
+}
+
+impl<K: Eq + Hash> Tally<K> {
+    fn new() -> Self {
+        Tally { counts: HashMap::new() }
+    }
+
+    fn record(&mut self, key: K) {
+        let next = self.counts.len();
+        self.counts.entry(key).or_insert((0, next)).0 += 1;
+    }
+
+    #[allow(dead_code)]
+    fn count(&self, key: &K) -> usize {
+        self.counts.get(key).map_or(0, |&(count, _)| count)
+    }
+
+    // 出现次数最多的 n 个 key，次数从多到少；次数相同的按第一次出现的先后排列
+    #[allow(dead_code)]
+    fn top(&self, n: usize) -> Vec<(&K, usize)> {
+        let mut entries: Vec<(&K, usize, usize)> =
+            self.counts.iter().map(|(key, &(count, first))| (key, count, first)).collect();
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then(a.2.cmp(&b.2)));
+        entries.into_iter().take(n).map(|(key, count, _)| (key, count)).collect()
+    }
+
+    // 只要次数，丢掉第一次出现的顺序
+    fn into_counts(self) -> HashMap<K, usize> {
+        self.counts.into_iter().map(|(key, (count, _))| (key, count)).collect()
+    }
+}
+
+// 练习26：
+// Sørensen–Dice 系数 = 2 * 共同二元组数 / 两边二元组总数。
+// 二元组用 HashMap 计数，重复出现的二元组按次数匹配
+#[allow(dead_code)]
+fn dice_coefficient(a: &str, b: &str) -> f64 {
+    fn bigrams(s: &str) -> HashMap<(char, char), usize> {
+        let chars: Vec<char> = s.chars().collect();
+        let mut counts = HashMap::new();
+        for pair in chars.windows(2) {
+            *counts.entry((pair[0], pair[1])).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    // 少于两个字符就没有二元组，只能看两个字符串是否完全相同
+    if a.chars().count() < 2 || b.chars().count() < 2 {
+        return if a == b { 1.0 } else { 0.0 };
+    }
+    let bigrams_a = bigrams(a);
+    let bigrams_b = bigrams(b);
+    let common: usize = bigrams_a
+        .iter()
+        .map(|(pair, &count)| count.min(*bigrams_b.get(pair).unwrap_or(&0)))
+        .sum();
+    let total: usize = bigrams_a.values().sum::<usize>() + bigrams_b.values().sum::<usize>();
+    2.0 * common as f64 / total as f64
+}
+
+// 练习27：
+// 分页输出：超过一页时每打印 page_size 行就停下来问用户接下来怎么做。
+// 读和写都通过参数传进来，用 io::Cursor 和 Vec<u8> 就能模拟整个交互过程
+const PAGE_SIZE: usize = 20;
+const PAGER_PROMPT: &str = "-- more (Enter=next, q=quit, a=all) --";
+
+#[derive(Debug, PartialEq)]
+enum PagerOutcome {
+    // 所有行都打印完了
+    Completed(usize),
+    // 用户输入 q（或者输入已经结束），只打印了这么多行
+    Quit(usize),
+}
+
+fn paginate<W: Write, R: BufRead>(lines: &[String], page_size: usize, out: &mut W, input: &mut LineInput<R>) -> io::Result<PagerOutcome> {
+    // 一页就能放下（或者不分页）时直接全部打印，不出现提示
+    if page_size == 0 || lines.len() <= page_size {
+        for line in lines {
+            writeln!(out, "{}", line)?;
+        }
+        return Ok(PagerOutcome::Completed(lines.len()));
+    }
+
+    let mut shown = 0;
+    for page in lines.chunks(page_size) {
+        for line in page {
+            writeln!(out, "{}", line)?;
+        }
+        shown += page.len();
+        // 最后一页打印完就结束，不会多出一个空的提示
+        if shown == lines.len() {
+            break;
+        }
+
+        writeln!(out, "{}", PAGER_PROMPT)?;
+        out.flush()?;
+        let response = match input.read_line()? {
+            LineOutcome::Line(response) => response,
+            // 太长的一行不是 q 也不是 a，和直接按回车一样，显示下一页
+            LineOutcome::TooLong { .. } => String::new(),
+            // 输入结束，当作退出处理
+            LineOutcome::Eof => return Ok(PagerOutcome::Quit(shown)),
+        };
+        match response.trim() {
+            "q" | "Q" => return Ok(PagerOutcome::Quit(shown)),
+            "a" | "A" => {
+                for line in &lines[shown..] {
+                    writeln!(out, "{}", line)?;
+                }
+                shown = lines.len();
+                break;
+            }
+            _ => {}
+        }
+    }
+    Ok(PagerOutcome::Completed(shown))
+}
+
+// 练习28：
+// 带单位后缀的数量："250"、"10k"、"1.5M"、"2b"。
+// 全程用整数计算，不经过浮点数，所以 "1.23k" 精确地等于 1230
+#[derive(Debug, PartialEq)]
+enum ScaledParseError {
+    Empty,
+    Negative,
+    InvalidNumber(String),
+    UnknownSuffix(char),
+    // 乘上单位之后还有小数部分，例如 "1.2345k"
+    NotWhole(String),
+    Overflow,
+}
+
+impl Localize for ScaledParseError {
+    fn localized(&self, lang: Lang) -> String {
+        let key = match self {
+            ScaledParseError::Empty => MsgKey::ScaledEmpty,
+            ScaledParseError::Negative => MsgKey::ScaledNegative,
+            ScaledParseError::InvalidNumber(s) => MsgKey::ScaledInvalidNumber { text: s.clone() },
+            ScaledParseError::UnknownSuffix(c) => MsgKey::ScaledUnknownSuffix { suffix: *c },
+            ScaledParseError::NotWhole(s) => MsgKey::ScaledNotWhole { text: s.clone() },
+            ScaledParseError::Overflow => MsgKey::ScaledOverflow,
+        };
+        render(lang, key)
+    }
+}
+
+impl fmt::Display for ScaledParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.localized(Lang::Zh))
+    }
+}
+
+fn parse_scaled_count(s: &str) -> Result<u64, ScaledParseError> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(ScaledParseError::Empty);
+    }
+    if s.starts_with('-') {
+        return Err(ScaledParseError::Negative);
+    }
+
+    // 1. 最后一个字符是字母的话就是单位，决定小数点要右移几位
+    let last = s.chars().last().unwrap();
+    let (number, shift) = if last.is_ascii_digit() || last == '.' {
+        (s, 0)
+    } else {
+        let shift = match last.to_ascii_lowercase() {
+            'k' => 3,
+            'm' => 6,
+            'b' => 9,
+            _ => return Err(ScaledParseError::UnknownSuffix(last)),
+        };
+        (&s[..s.len() - last.len_utf8()], shift)
+    };
+
+    // 2. 拆成整数部分和小数部分，两部分都只能有数字
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let is_digits = |text: &str| text.chars().all(|c| c.is_ascii_digit());
+    if whole.is_empty() || !is_digits(whole) || !is_digits(fraction) {
+        return Err(ScaledParseError::InvalidNumber(s.to_string()));
+    }
+
+    // 3. 小数部分去掉末尾的 0 之后，位数不能超过单位的位数，否则换算后仍有小数
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > shift {
+        return Err(ScaledParseError::NotWhole(s.to_string()));
+    }
+
+    // 4. 整数部分 * 10^shift + 小数部分补齐到 shift 位，每一步都检查溢出
+    let whole: u64 = whole.parse().map_err(|_| ScaledParseError::Overflow)?;
+    let padded = format!("{:0<width$}", fraction, width = shift);
+    let fraction: u64 = if padded.is_empty() { 0 } else { padded.parse().unwrap() };
+    whole
+        .checked_mul(10u64.pow(shift as u32))
+        .and_then(|n| n.checked_add(fraction))
+        .ok_or(ScaledParseError::Overflow)
+}
+
+// 练习29：
+// 所有命令的说明都放在这一张表里：parse_command 用它识别命令名，
+// 参数出错时的用法提示、Help 命令的输出和拼写建议也都从这里生成
+#[derive(Debug, PartialEq)]
+struct CommandSpec {
+    verb: &'static str,
+    args: &'static str,
+    // 简介放在消息目录里，Help 按当前语言显示
+    summary: MsgKey,
+    examples: &'static [&'static str],
+}
+
+impl CommandSpec {
+    fn usage(&self) -> String {
+        if self.args.is_empty() { self.verb.to_string() } else { format!("{} {}", self.verb, self.args) }
+    }
+}
+
+const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        verb: "Add",
+        args: "<name> to <department>",
+        summary: MsgKey::HelpAdd,
+        examples: &["Add Sally to Engineering", "Add Amir to Sales/East"],
+    },
+    CommandSpec {
+        verb: "Remove",
+        args: "<name> from <department>",
+        summary: MsgKey::HelpRemove,
+        examples: &["Remove Sally from Engineering"],
+    },
+    CommandSpec {
+        verb: "Transfer",
+        args: "<name> from <department> to <department>",
+        summary: MsgKey::HelpTransfer,
+        examples: &["Transfer Sally from Sales to Engineering"],
+    },
+    CommandSpec {
+        verb: "Move",
+        args: "<name> from <department> to <department>",
+        summary: MsgKey::HelpMove,
+        examples: &["Move Sally from Sales to Engineering"],
+    },
+    CommandSpec {
+        verb: "Rename",
+        args: "<department> to <department>",
+        summary: MsgKey::HelpRename,
+        examples: &["Rename Enginering to Engineering"],
+    },
+    CommandSpec {
+        verb: "List",
+        args: "<department> | All",
+        summary: MsgKey::HelpList,
+        examples: &["List Sales", "List All"],
+    },
+    CommandSpec { verb: "Roster", args: "", summary: MsgKey::HelpRoster, examples: &["Roster"] },
+    CommandSpec { verb: "Import", args: "<file>", summary: MsgKey::HelpImport, examples: &["Import staff.csv"] },
+    CommandSpec {
+        verb: "Export",
+        args: "[html] <file>",
+        summary: MsgKey::HelpExport,
+        examples: &["Export company.csv", "Export html company.html"],
+    },
+    CommandSpec {
+        verb: "ExportJson",
+        args: "<file>",
+        summary: MsgKey::HelpExportJson,
+        examples: &["ExportJson company.json"],
+    },
+    CommandSpec {
+        verb: "ImportJson",
+        args: "<file>",
+        summary: MsgKey::HelpImportJson,
+        examples: &["ImportJson company.json"],
+    },
+    CommandSpec {
+        verb: "Undo",
+        args: "",
+        summary: MsgKey::HelpUndo,
+        examples: &["Undo"],
+    },
+    CommandSpec {
+        verb: "Save",
+        args: "<file>",
+        summary: MsgKey::HelpSave,
+        examples: &["Save company.txt"],
+    },
+    CommandSpec {
+        verb: "Load",
+        args: "<file> [--recover]",
+        summary: MsgKey::HelpLoad,
+        examples: &["Load company.txt", "Load company.txt --recover"],
+    },
+    CommandSpec { verb: "Lang", args: "<en|zh>", summary: MsgKey::HelpLang, examples: &["Lang en", "Lang zh"] },
+    CommandSpec {
+        verb: "Run",
+        args: "<file> [--lenient]",
+        summary: MsgKey::HelpRun,
+        examples: &["Run setup.txt", "Run setup.txt --lenient"],
+    },
+    CommandSpec {
+        verb: "Query",
+        args: "[dept=<a,b>] [contains=<text>] [glob=<pattern>] [sort=name|name-desc|dept|len] [limit=<n>]",
+        summary: MsgKey::HelpQuery,
+        examples: &["Query dept=Sales contains=a", "Query glob=S* sort=len limit=10k"],
+    },
+    CommandSpec { verb: "Stats", args: "", summary: MsgKey::HelpStats, examples: &["Stats"] },
+    CommandSpec {
+        verb: "Find",
+        args: "<name>",
+        summary: MsgKey::HelpFind,
+        examples: &["Find Sally"],
+    },
+    CommandSpec {
+        verb: "Count",
+        args: "[department]",
+        summary: MsgKey::HelpCount,
+        examples: &["Count", "Count Sales"],
+    },
+    CommandSpec {
+        verb: "Help",
+        args: "[command]",
+        summary: MsgKey::HelpHelp,
+        examples: &["Help", "Help Add"],
+    },
+    CommandSpec { verb: "Quit", args: "", summary: MsgKey::HelpQuit, examples: &["Quit"] },
+];
+
+fn find_spec(verb: &str) -> Option<&'static CommandSpec> {
+    COMMANDS.iter().find(|spec| spec.verb == verb)
+}
+
+// 两个字符串的编辑距离：把 a 变成 b 最少需要插入、删除或替换几个字符。
+// 只保留上一行，空间是 O(b 的长度)
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            current.push((previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+// 找出和输入最接近的命令名（不区分大小写），差得太多（超过 2 个字符）就不建议了
+fn suggest_verb(word: &str) -> Option<&'static str> {
+    let word = word.to_lowercase();
+    COMMANDS
+        .iter()
+        .map(|spec| (edit_distance(&word, &spec.verb.to_lowercase()), spec.verb))
+        .filter(|&(distance, _)| distance <= 2)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, verb)| verb)
+}
+
+fn unknown_command(word: &str) -> CommandError {
+    CommandError::UnknownCommand { word: word.to_string(), suggestion: suggest_verb(word) }
+}
+
+// "Help"：每个命令一行，命令名和简介对齐
+fn help_overview(lang: Lang) -> String {
+    let width = COMMANDS.iter().map(|spec| spec.verb.len()).max().unwrap_or(0);
+    let mut out = format!("{}\n", render(lang, MsgKey::HelpOverviewHeader));
+    for spec in COMMANDS {
+        out.push_str(&format!("  {:<width$}  {}\n", spec.verb, render(lang, spec.summary.clone()), width = width));
+    }
+    out
+}
+
+// "Help Add"：完整的用法和例子
+fn help_for(lang: Lang, spec: &CommandSpec) -> String {
+    let mut out = format!(
+        "{}\n{}\n{}\n",
+        render(lang, MsgKey::HelpUsage { usage: spec.usage() }),
+        render(lang, spec.summary.clone()),
+        render(lang, MsgKey::HelpExamples)
+    );
+    for example in spec.examples {
+        out.push_str(&format!("  {}\n", example));
+    }
+    out
+}
+
+// 练习30：
+// read_line 会把一整行都读进内存，如果输入是一个没有换行符的超大文件，内存会被撑爆。
+// 这里直接使用 BufRead 内部的缓冲区（fill_buf/consume），一块一块地找换行符，
+// 超过上限的行只计数不保存，所以占用的内存不会超过 max_bytes
+const DEFAULT_MAX_LINE_BYTES: usize = 4096;
+
+// 一行最多读多少个字节：设置了合法的正整数就用它，没有设置或者写错了就用默认值
+fn max_line_bytes_setting(value: Option<&str>) -> usize {
+    match value.map(|v| v.trim().parse::<usize>()) {
+        Some(Ok(max)) if max > 0 => max,
+        _ => DEFAULT_MAX_LINE_BYTES,
+    }
+}
+
+// 交互时的输入来源和每行的上限放在一起，读一行就是 read_limited_line(reader, max_line_bytes)。
+// 主程序里装的是 stdin，测试里换成 io::Cursor。
+// scripted 表示输入来自脚本而不是有人在终端前打字，默认是 true，主程序按 stdin 是不是终端来设置
+struct LineInput<R> {
+    reader: R,
+    max_line_bytes: usize,
+    scripted: bool,
+}
+
+impl<R: BufRead> LineInput<R> {
+    fn new(reader: R, max_line_bytes: usize) -> Self {
+        LineInput { reader, max_line_bytes, scripted: true }
+    }
+
+    fn scripted(mut self, scripted: bool) -> Self {
+        self.scripted = scripted;
+        self
+    }
+
+    fn read_line(&mut self) -> io::Result<LineOutcome> {
+        read_limited_line(&mut self.reader, self.max_line_bytes)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum LineOutcome {
+    // 一行内容，不包含末尾的换行符
+    Line(String),
+    // 这一行超过了上限，整行（包括换行符）都已经被读掉并丢弃
+    TooLong { consumed: usize },
+    Eof,
+}
+
+fn read_limited_line<R: BufRead>(r: &mut R, max_bytes: usize) -> io::Result<LineOutcome> {
+    let mut line = Vec::new();
+    let mut consumed = 0;
+    let mut too_long = false;
+    loop {
+        let available = match r.fill_buf() {
+            Ok(available) => available,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        // 输入结束
+        if available.is_empty() {
+            break;
+        }
+        let (chunk_len, found_newline) = match available.iter().position(|&b| b == b'\n') {
+            Some(i) => (i + 1, true),
+            None => (available.len(), false),
+        };
+        // 按字节计算长度，不会在多字节字符的中间切开字符串，所以不会 panic
+        let content = &available[..chunk_len - found_newline as usize];
+        if !too_long {
+            if line.len() + content.len() > max_bytes {
+                too_long = true;
+                line = Vec::new();
+            } else {
+                line.extend_from_slice(content);
+            }
+        }
+        consumed += chunk_len;
+        r.consume(chunk_len);
+        if found_newline {
+            break;
+        }
+    }
+
+    if consumed == 0 {
+        return Ok(LineOutcome::Eof);
+    }
+    if too_long {
+        return Ok(LineOutcome::TooLong { consumed });
+    }
+    // Windows 的换行是 \r\n，去掉剩下的 \r；不是合法 UTF-8 的字节替换成 �
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    Ok(LineOutcome::Line(String::from_utf8_lossy(&line).into_owned()))
+}
+
+// 练习31：
+#[derive(Debug, PartialEq)]
+enum RemoveOutcome {
+    // department_deleted 为 true 表示部门因此变空并被删掉了
+    Removed { department_deleted: bool },
+    EmployeeNotFound,
+    DepartmentNotFound,
+}
+
+// 从部门中移除员工（同名的记录全部移除）。部门变空之后把整个键删掉，
+// 这样 List All 里不会出现空部门；但如果它还有下级部门，就保留它作为上级
+fn remove_employee(departments: &mut HashMap<String, Vec<String>>, name: &str, department: &str) -> RemoveOutcome {
+    let employees = match departments.get_mut(department) {
+        Some(employees) => employees,
+        None => return RemoveOutcome::DepartmentNotFound,
+    };
+    let before = employees.len();
+    employees.retain(|employee| employee != name);
+    if employees.len() == before {
+        return RemoveOutcome::EmployeeNotFound;
+    }
+
+    let now_empty = employees.is_empty();
+    let department_deleted = now_empty && descendant_departments(departments, department).is_empty();
+    if department_deleted {
+        departments.remove(department);
+    }
+    RemoveOutcome::Removed { department_deleted }
+}
+
+// 练习32：
+#[derive(Debug, PartialEq)]
+enum TransferError {
+    SameDepartment,
+    SourceNotFound,
+    EmployeeNotFound,
+    // 目标部门里已经有同名员工，再放进去就会出现两个
+    AlreadyInDestination,
+}
+
+// 把员工从一个部门调到另一个部门。先检查所有条件，全部满足之后才修改数据，
+// 所以出错时 departments 完全不变；目标部门不存在时和 Add 一样自动创建（包括上级部门）
+fn transfer_employee(
+    departments: &mut HashMap<String, Vec<String>>,
+    name: &str,
+    from: &str,
+    to: &str,
+) -> Result<(), TransferError> {
+    if from == to {
+        return Err(TransferError::SameDepartment);
+    }
+    let position = departments
+        .get(from)
+        .ok_or(TransferError::SourceNotFound)?
+        .iter()
+        .position(|employee| employee == name)
+        .ok_or(TransferError::EmployeeNotFound)?;
+    if departments.get(to).is_some_and(|employees| employees.iter().any(|employee| employee == name)) {
+        return Err(TransferError::AlreadyInDestination);
+    }
+
+    departments.entry(to.to_string()).or_default().push(name.to_string());
+    for ancestor in ancestor_paths(to) {
+        departments.entry(ancestor).or_default();
+    }
+    // 上面已经确认过来源部门存在，这里的 get_mut 一定能成功
+    if let Some(employees) = departments.get_mut(from) {
+        employees.remove(position);
+    }
+    Ok(())
+}
+
+// 练习33：
+// 把整个公司保存成文本文件，每个部门一行："部门:员工1,员工2"，没有员工的部门写成 "部门:"。
+// 文件最后一行是 checksum 模块（练习19）生成的校验行，读取时用它发现被改坏或者被截断的文件
+#[derive(Debug)]
+enum RosterError {
+    Io(io::Error),
+    // line 是文件中的行号，从 1 开始；reason 是目录里的一条说明，显示时再翻译
+    Malformed { line: usize, reason: MsgKey },
+    Checksum(checksum::TrailerError),
+    // 名字里有 ':'、',' 或换行符，保存之后就没法正确读回来了
+    Unrepresentable(String),
+}
+
+impl Localize for RosterError {
+    fn localized(&self, lang: Lang) -> String {
+        match self {
+            // 系统给出的错误说明没有翻译，原样显示，后面附上按当前语言显示的提示
+            RosterError::Io(e) => report_error(lang, e),
+            RosterError::Malformed { line, reason } => {
+                render(lang, MsgKey::RosterMalformed { line: *line, reason: render(lang, reason.clone()) })
+            }
+            RosterError::Checksum(error) => error.localized(lang),
+            RosterError::Unrepresentable(name) => render(lang, MsgKey::RosterUnrepresentable { name: name.clone() }),
+        }
+    }
+}
+
+impl fmt::Display for RosterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.localized(Lang::Zh))
+    }
+}
+
+// 有了这两个 From 实现，下面的函数里就可以直接用 ? 把 io 错误和校验错误转换成 RosterError
+impl From<io::Error> for RosterError {
+    fn from(e: io::Error) -> Self {
+        RosterError::Io(e)
+    }
+}
+
+impl From<checksum::TrailerError> for RosterError {
+    fn from(e: checksum::TrailerError) -> Self {
+        RosterError::Checksum(e)
+    }
+}
+
+// 部门按名字排序，员工保持原来的顺序，这样读回来的数据和保存前完全一样
+fn serialize_roster(departments: &HashMap<String, Vec<String>>) -> Result<String, RosterError> {
+    let representable = |name: &String| -> Result<(), RosterError> {
+        if name.contains([':', ',', '\n', '\r']) {
+            Err(RosterError::Unrepresentable(name.clone()))
+        } else {
+            Ok(())
+        }
+    };
+    let mut names: Vec<&String> = departments.keys().collect();
+    names.sort();
+    let mut text = String::new();
+    for department in names {
+        representable(department)?;
+        let employees = &departments[department];
+        for employee in employees {
+            representable(employee)?;
+        }
+        text.push_str(&format!("{}:{}\n", department, employees.join(",")));
+    }
+    Ok(text)
+}
+
+fn parse_roster(text: &str) -> Result<HashMap<String, Vec<String>>, RosterError> {
+    let mut departments: HashMap<String, Vec<String>> = HashMap::new();
+    for (index, line) in text.lines().enumerate() {
+        let malformed = |reason: MsgKey| RosterError::Malformed { line: index + 1, reason };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (department, employees) = line.split_once(':').ok_or_else(|| malformed(MsgKey::RosterMissingColon))?;
+        let department = normalize_dept_path(department).ok_or_else(|| malformed(MsgKey::RosterEmptyDept))?;
+        if departments.contains_key(&department) {
+            return Err(malformed(MsgKey::RosterDuplicateDept));
+        }
+        let employees: Vec<String> = if employees.trim().is_empty() {
+            Vec::new()
+        } else {
+            employees.split(',').map(|name| name.trim().to_string()).collect()
+        };
+        if employees.iter().any(|name| name.is_empty()) {
+            return Err(malformed(MsgKey::RosterEmptyEmployee));
+        }
+        departments.insert(department, employees);
+    }
+    // 手工编辑过的文件可能缺少上级部门，和 Add 一样补上（练习21）
+    let paths: Vec<String> = departments.keys().cloned().collect();
+    for path in paths {
+        for ancestor in ancestor_paths(&path) {
+            departments.entry(ancestor).or_default();
+        }
+    }
+    Ok(departments)
+}
+
+// 和 export_html 一样用 atomic_write 写入，保存到一半出错时不会破坏原来的文件
+fn save_roster(departments: &HashMap<String, Vec<String>>, path: &str) -> Result<(), RosterError> {
+    let text = checksum::add_trailer(&serialize_roster(departments)?);
+    atomic_write(path, text.as_bytes())?;
+    Ok(())
+}
+
+// 返回读到的数据，以及文件有没有校验行（没有的话可能是手工编写的文件）
+fn load_roster(path: &str) -> Result<(HashMap<String, Vec<String>>, checksum::TrailerStatus), RosterError> {
+    let text = std::fs::read_to_string(path)?;
+    let (body, status) = checksum::check_trailer(&text)?;
+    Ok((parse_roster(body)?, status))
+}
+
+// 恢复用（练习19）：校验和不对或者文件被截断时也尽量读出来，第二个值为 true 表示数据可能不完整
+fn load_ignoring_checksum(path: &str) -> Result<(HashMap<String, Vec<String>>, bool), RosterError> {
+    let text = std::fs::read_to_string(path)?;
+    let (body, suspect) = checksum::strip_trailer_ignoring_checksum(&text);
+    Ok((parse_roster(body)?, suspect))
+}
+
+// 读到的数据，以及读取成功后还要提醒用户的警告
+type LoadedRoster = (HashMap<String, Vec<String>>, Option<MsgKey>);
+
+// Load 命令用：加上 --recover 时忽略校验和
+fn load_for_command(path: &str, recover: bool) -> Result<LoadedRoster, RosterError> {
+    if recover {
+        let (loaded, suspect) = load_ignoring_checksum(path)?;
+        Ok((loaded, suspect.then(|| MsgKey::LoadRecovered { path: path.to_string() })))
+    } else {
+        let (loaded, status) = load_roster(path)?;
+        let warning = (status == checksum::TrailerStatus::Missing).then(|| MsgKey::LoadNoChecksum { path: path.to_string() });
+        Ok((loaded, warning))
+    }
+}
+
+// 练习34：
+// 退出时把数据写到 departments.csv，下次启动时再读回来。每个员工一行："部门,名字"，
+// 字段用 csv_field（练习43）加引号，所以带逗号的名字也能保存；
+// 没有员工的部门写成名字为空的一行 "部门,"，这样空部门读回来之后也还在
+const AUTOSAVE_CSV: &str = "departments.csv";
+
+fn departments_to_csv(departments: &HashMap<String, Vec<String>>) -> String {
+    let mut names: Vec<&String> = departments.keys().collect();
+    names.sort();
+    let mut text = String::new();
+    for department in names {
+        let employees = &departments[department];
+        if employees.is_empty() {
+            text.push_str(&format!("{},\n", csv_field(department)));
+        }
+        for employee in employees {
+            text.push_str(&format!("{},{}\n", csv_field(department), csv_field(employee)));
+        }
+    }
+    text
+}
+
+// 读到的数据，以及格式不对、被跳过的记录序号（从 1 开始；名字里没有换行符时就是行号）
+type CsvLoad = (HashMap<String, Vec<String>>, Vec<usize>);
+
+// 引号没有闭合时整个文件都没法可靠地读，返回 Err
+fn departments_from_csv(text: &str) -> Result<CsvLoad, CsvError> {
+    let mut departments: HashMap<String, Vec<String>> = HashMap::new();
+    let mut bad_lines = Vec::new();
+    for (index, row) in parse_csv_rows(text)?.into_iter().enumerate() {
+        let parsed = match row.as_slice() {
+            // 空行
+            [only] if only.trim().is_empty() => continue,
+            [department, name] => normalize_dept_path(department).map(|department| (department, name.trim())),
+            _ => None,
+        };
+        match parsed {
+            Some((department, name)) => {
+                for ancestor in ancestor_paths(&department) {
+                    departments.entry(ancestor).or_default();
+                }
+                let employees = departments.entry(department).or_default();
+                // 名字为空表示这是一个没有员工的部门
+                if !name.is_empty() {
+                    employees.push(name.to_string());
+                }
+            }
+            None => bad_lines.push(index + 1),
+        }
+    }
+    Ok((departments, bad_lines))
+}
+
+// 启动时调用：文件不存在就从空数据开始，其他读取错误也只提示一下，不让程序崩溃
+fn load_autosave(lang: Lang, path: &str) -> HashMap<String, Vec<String>> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return HashMap::new(),
+        Err(e) => {
+            println!("{}", render(lang, MsgKey::CsvLoadFailed { path: path.to_string(), error: report_error(lang, &e) }));
+            return HashMap::new();
+        }
+    };
+    let (departments, bad_lines) = match departments_from_csv(&text) {
+        Ok(result) => result,
+        Err(error) => {
+            println!("{}", render(lang, MsgKey::CsvLoadFailed { path: path.to_string(), error: error.localized(lang) }));
+            return HashMap::new();
+        }
+    };
+    for line in bad_lines {
+        println!("{}", render(lang, MsgKey::CsvBadLine { path: path.to_string(), line }));
+    }
+    let employees = departments.values().map(|list| list.len()).sum();
+    println!("{}", render(lang, MsgKey::CsvLoaded { path: path.to_string(), employees }));
+    departments
+}
+
+// 练习36：
+// 每个部门的人数，人多的排在前面，人数相同时按部门名的字母顺序
+// 只负责计算，输出格式交给 Count 命令，两部分可以分开检查
+fn department_counts(departments: &HashMap<String, Vec<String>>) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> =
+        departments.iter().map(|(name, employees)| (name.clone(), employees.len())).collect();
+    counts.sort_by(|(name_a, count_a), (name_b, count_b)| count_b.cmp(count_a).then_with(|| name_a.cmp(name_b)));
+    counts
+}
+
+// 练习37：
+#[derive(Debug)]
+enum RenameError {
+    SameName,
+    NotFound,
+    // 有下级部门时不能改名，否则 "Old/Sub" 会变成没有上级的部门
+    HasSubDepartments,
+}
+
+// 练习38：
+// 名字完全相同的员工在哪些部门（按部门名排序）
+fn find_employee<'a>(departments: &'a HashMap<String, Vec<String>>, name: &str) -> Vec<&'a str> {
+    let mut found: Vec<&str> = departments
+        .iter()
+        .filter(|(_, employees)| employees.iter().any(|employee| employee == name))
+        .map(|(department, _)| department.as_str())
+        .collect();
+    found.sort();
+    found
+}
+
+// 没有完全相同的名字时用来给出建议：忽略大小写后编辑距离（练习29）不超过 2 的员工，
+// 返回 (名字, 部门)，距离近的排在前面
+fn close_employee_matches<'a>(departments: &'a HashMap<String, Vec<String>>, name: &str) -> Vec<(&'a str, &'a str)> {
+    let name = name.to_lowercase();
+    let mut matches: Vec<(usize, &str, &str)> = Vec::new();
+    for (department, employees) in departments {
+        for employee in employees {
+            let distance = edit_distance(&name, &employee.to_lowercase());
+            if distance <= 2 {
+                matches.push((distance, employee.as_str(), department.as_str()));
+            }
+        }
+    }
+    matches.sort();
+    matches.into_iter().map(|(_, employee, department)| (employee, department)).collect()
+}
+
+// 练习39：
+// 哈夫曼编码的树：叶子是一个字符，内部节点有左右两个子树
+#[allow(dead_code)]
+enum HuffmanNode {
+    Leaf(char),
+    Internal(Box<HuffmanNode>, Box<HuffmanNode>),
+}
+
+// 思路：先用 HashMap 数出每个字符出现的次数，每个字符都是一棵只有叶子的树。
+// 每次从优先队列（BinaryHeap）里取出次数最少的两棵树，合并成一棵新树放回去，
+// 直到只剩一棵。每个字符的编码长度就是它的叶子在树里的深度。
+// BinaryHeap 默认先弹出最大的，所以用 Reverse 包起来；次数相同时按放入的先后顺序，保证结果固定
+#[allow(dead_code)]
+fn code_lengths(text: &str) -> HashMap<char, usize> {
+    let mut frequencies: HashMap<char, usize> = HashMap::new();
+    for c in text.chars() {
+        *frequencies.entry(c).or_insert(0) += 1;
+    }
+
+    // 先按字符排序，这样 HashMap 的遍历顺序不会影响结果
+    let mut leaves: Vec<(char, usize)> = frequencies.into_iter().collect();
+    leaves.sort();
+
+    // 树本身放在 nodes 里，堆里只放 (次数, 放入顺序, 下标)
+    let mut nodes: Vec<Option<HuffmanNode>> = Vec::new();
+    let mut heap = BinaryHeap::new();
+    for (c, count) in leaves {
+        heap.push(Reverse((count, nodes.len())));
+        nodes.push(Some(HuffmanNode::Leaf(c)));
+    }
+
+    while heap.len() > 1 {
+        let (Some(Reverse((count_a, a))), Some(Reverse((count_b, b)))) = (heap.pop(), heap.pop()) else {
+            break;
+        };
+        // 下标只会被弹出一次，所以 take() 一定能拿到树
+        let (Some(left), Some(right)) = (nodes[a].take(), nodes[b].take()) else {
+            break;
+        };
+        heap.push(Reverse((count_a + count_b, nodes.len())));
+        nodes.push(Some(HuffmanNode::Internal(Box::new(left), Box::new(right))));
+    }
+
+    let mut lengths = HashMap::new();
+    if let Some(Reverse((_, root))) = heap.pop()
+        && let Some(root) = nodes[root].take()
+    {
+        collect_code_lengths(&root, 0, &mut lengths);
+    }
+    lengths
+}
+
+#[allow(dead_code)]
+fn collect_code_lengths(node: &HuffmanNode, depth: usize, lengths: &mut HashMap<char, usize>) {
+    match node {
+        // 只有一种字符时树只有一个叶子，深度是 0，但编码至少要 1 位
+        HuffmanNode::Leaf(c) => {
+            lengths.insert(*c, depth.max(1));
+        }
+        HuffmanNode::Internal(left, right) => {
+            collect_code_lengths(left, depth + 1, lengths);
+            collect_code_lengths(right, depth + 1, lengths);
+        }
+    }
+}
+
+// 练习40：
+// 按空白切分单词，统计每个单词出现的次数。
+// 每个单词先去掉首尾的 ASCII 标点，"world." 和 "world" 算同一个单词；
+// 中间的标点保留，所以 "don't" 不变。只由标点组成的片段（例如 "--"）不算单词
+// 计数交给 Tally（练习25），它和第 11 课的 Counter 是同一个思路
+fn count_words(text: &str) -> HashMap<String, usize> {
+    let mut word_counts = Tally::new();
+    for token in text.split_whitespace() {
+        let word = token.trim_matches(|c: char| c.is_ascii_punctuation());
+        if word.is_empty() {
+            continue;
+        }
+        word_counts.record(word.to_string());
+    }
+    word_counts.into_counts()
+}
+
+// 和 count_words 一样，但先把单词转成小写，"Hello" 和 "hello" 算同一个单词
+#[allow(dead_code)]
+fn count_words_ignore_case(text: &str) -> HashMap<String, usize> {
+    count_words(&text.to_lowercase())
+}
+
+// 练习41：
+// 出现次数最多的 n 个单词，次数从多到少；次数相同时按字母顺序，结果不受 HashMap 遍历顺序影响。
+// n 比不同单词的个数还大时，take(n) 只会拿到全部单词，不会出错
+#[allow(dead_code)]
+fn top_n_words(text: &str, n: usize) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = count_words(text).into_iter().collect();
+    counts.sort_by(|(word_a, count_a), (word_b, count_b)| count_b.cmp(count_a).then_with(|| word_a.cmp(word_b)));
+    counts.into_iter().take(n).collect()
+}
+
+// 练习42：
+// 从左到右把每个值放进 HashSet，集合的大小就是到目前为止见过的不同值的个数
+#[allow(dead_code)]
+fn running_distinct<T: Eq + Hash + Clone>(slice: &[T]) -> Vec<usize> {
+    let mut seen = HashSet::new();
+    let mut counts = Vec::with_capacity(slice.len());
+    for item in slice {
+        seen.insert(item.clone());
+        counts.push(seen.len());
+    }
+    counts
+}
+
+// 练习43：
+// 按 RFC 4180 写一个 CSV 字段：含有逗号、双引号或换行符的字段用双引号包起来，
+// 字段里的双引号写两次。例如 `Smith, "J"` 写成 `"Smith, ""J"""`
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// 写出 "department,employee" 表头和每个员工一行，先按部门再按名字排序，返回写出的员工行数。
+// 和 departments_to_csv（练习34）一样用 csv_field 写字段，但带表头、用 CRLF 换行，也不写空部门
+fn export_csv(departments: &HashMap<String, Vec<String>>, path: &str) -> io::Result<usize> {
+    let mut rows: Vec<(&String, &String)> = departments
+        .iter()
+        .flat_map(|(department, employees)| employees.iter().map(move |employee| (department, employee)))
+        .collect();
+    rows.sort();
+
+    let mut file = std::fs::File::create(path)?;
+    // RFC 4180 规定每行以 CRLF 结尾
+    write!(file, "department,employee\r\n")?;
+    for (department, employee) in &rows {
+        write!(file, "{},{}\r\n", csv_field(department), csv_field(employee))?;
+    }
+    file.flush()?;
+    Ok(rows.len())
+}
+
+// row 是出错的记录序号，从 1 开始
+#[derive(Debug, Clone, PartialEq)]
+enum CsvError {
+    UnclosedQuote { row: usize },
+    // 闭合引号后面不是逗号或行尾
+    TextAfterQuote { row: usize },
+}
+
+impl Localize for CsvError {
+    fn localized(&self, lang: Lang) -> String {
+        match *self {
+            CsvError::UnclosedQuote { row } => render(lang, MsgKey::CsvUnclosedQuote { row }),
+            CsvError::TextAfterQuote { row } => render(lang, MsgKey::CsvTextAfterQuote { row }),
+        }
+    }
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.localized(Lang::Zh))
+    }
+}
+
+// 按 RFC 4180 读取 CSV，返回每一行的字段。双引号里的逗号、换行和 "" 都当作普通字符；
+// 引号没有闭合，或者闭合引号后面不是逗号或行尾时返回 Err
+fn parse_csv_rows(text: &str) -> Result<Vec<Vec<String>>, CsvError> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if field.is_empty() => {
+                // 带引号的字段：一直读到单独的一个 '"' 为止
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        Some('"') => break,
+                        Some(other) => field.push(other),
+                        None => return Err(CsvError::UnclosedQuote { row: rows.len() + 1 }),
+                    }
+                }
+                if !matches!(chars.peek(), None | Some(',') | Some('\r') | Some('\n')) {
+                    return Err(CsvError::TextAfterQuote { row: rows.len() + 1 });
+                }
+            }
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    // 最后一行没有换行符时也要算上
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+// 练习44：
+// 直接交给 parse_command 判断，不另写一套规则，这样两边永远不会不一致
+#[allow(dead_code)]
+fn is_valid_command(input: &str) -> bool {
+    parse_command(input).is_ok()
+}
+
+// 练习45：
+// 不依赖 serde，自己写 JSON 的输出和解析。只支持这个程序需要的格式：
+// 一个对象，键是部门名，值是由员工名组成的字符串数组，例如 {"Engineering": ["Amir", "Sally"]}
+mod json {
+    use super::messages::{render, Lang, Localize, MsgKey};
+    use std::collections::HashMap;
+    use std::fmt;
+
+    // 解析失败时记下出错位置（从 0 开始的字节偏移）和原因
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct JsonError {
+        pub offset: usize,
+        pub reason: MsgKey,
+    }
+
+    impl Localize for JsonError {
+        fn localized(&self, lang: Lang) -> String {
+            render(lang, MsgKey::JsonSyntax { offset: self.offset, reason: render(lang, self.reason.clone()) })
+        }
+    }
+
+    impl fmt::Display for JsonError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.localized(Lang::Zh))
+        }
+    }
+
+    // 部门按名字排序，每个部门一行；员工保持原来的顺序
+    pub fn to_json(departments: &HashMap<String, Vec<String>>) -> String {
+        let mut names: Vec<&String> = departments.keys().collect();
+        names.sort();
+        if names.is_empty() {
+            return "{}\n".to_string();
+        }
+        let lines: Vec<String> = names
+            .into_iter()
+            .map(|department| {
+                let employees: Vec<String> = departments[department].iter().map(|name| quote(name)).collect();
+                format!("  {}: [{}]", quote(department), employees.join(", "))
+            })
+            .collect();
+        format!("{{\n{}\n}}\n", lines.join(",\n"))
+    }
+
+    // 双引号、反斜杠和控制字符需要转义；中文、emoji 等其他字符原样写出（JSON 文件本身就是 UTF-8）
+    fn quote(s: &str) -> String {
+        let mut out = String::from("\"");
+        for c in s.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+        out
+    }
+
+    pub fn from_json(text: &str) -> Result<HashMap<String, Vec<String>>, JsonError> {
+        let mut parser = Parser { text, pos: 0 };
+        let departments = parser.object()?;
+        parser.skip_whitespace();
+        if parser.pos < text.len() {
+            return Err(parser.error(MsgKey::JsonTrailingContent));
+        }
+        Ok(departments)
+    }
+
+    // 递归下降解析：每种语法结构（对象、数组、字符串）对应一个方法，
+    // pos 是下一个要读的字节的位置，只会停在字符边界上
+    struct Parser<'a> {
+        text: &'a str,
+        pos: usize,
+    }
+
+    impl<'a> Parser<'a> {
+        fn error(&self, reason: MsgKey) -> JsonError {
+            JsonError { offset: self.pos, reason }
+        }
+
+        fn peek(&self) -> Option<char> {
+            self.text[self.pos..].chars().next()
+        }
+
+        fn next_char(&mut self) -> Option<char> {
+            let c = self.peek()?;
+            self.pos += c.len_utf8();
+            Some(c)
+        }
+
+        fn skip_whitespace(&mut self) {
+            while let Some(' ' | '\t' | '\n' | '\r') = self.peek() {
+                self.pos += 1;
+            }
+        }
+
+        // 跳过空白后，下一个字符必须是 expected
+        fn expect(&mut self, expected: char) -> Result<(), JsonError> {
+            self.skip_whitespace();
+            if self.peek() == Some(expected) {
+                self.pos += 1;
+                Ok(())
+            } else {
+                Err(self.error(MsgKey::JsonExpected { expected }))
+            }
+        }
+
+        // 读一串用逗号分隔、以 close 结尾的元素，每个元素交给 item 处理。对象和数组都用它
+        fn list(&mut self, close: char, mut item: impl FnMut(&mut Self) -> Result<(), JsonError>) -> Result<(), JsonError> {
+            self.skip_whitespace();
+            if self.peek() == Some(close) {
+                self.pos += 1;
+                return Ok(());
+            }
+            loop {
+                item(self)?;
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(',') => self.pos += 1,
+                    Some(c) if c == close => {
+                        self.pos += 1;
+                        return Ok(());
+                    }
+                    _ => return Err(self.error(MsgKey::JsonExpectedCommaOr { close })),
+                }
+            }
+        }
+
+        fn object(&mut self) -> Result<HashMap<String, Vec<String>>, JsonError> {
+            self.expect('{')?;
+            let mut departments = HashMap::new();
+            self.list('}', |parser| {
+                parser.skip_whitespace();
+                let key_offset = parser.pos;
+                let department = parser.string()?;
+                parser.expect(':')?;
+                let employees = parser.array()?;
+                if departments.insert(department, employees).is_some() {
+                    return Err(JsonError { offset: key_offset, reason: MsgKey::JsonDuplicateDept });
+                }
+                Ok(())
+            })?;
+            Ok(departments)
+        }
+
+        fn array(&mut self) -> Result<Vec<String>, JsonError> {
+            self.expect('[')?;
+            let mut items = Vec::new();
+            self.list(']', |parser| {
+                parser.skip_whitespace();
+                items.push(parser.string()?);
+                Ok(())
+            })?;
+            Ok(items)
+        }
+
+        fn string(&mut self) -> Result<String, JsonError> {
+            if self.peek() != Some('"') {
+                return Err(self.error(MsgKey::JsonExpectedString));
+            }
+            self.pos += 1;
+            let mut out = String::new();
+            loop {
+                let start = self.pos;
+                match self.next_char() {
+                    None => return Err(self.error(MsgKey::JsonUnterminatedString)),
+                    Some('"') => return Ok(out),
+                    Some('\\') => out.push(self.escape(start)?),
+                    Some(c) if (c as u32) < 0x20 => {
+                        self.pos = start;
+                        return Err(self.error(MsgKey::JsonControlChar));
+                    }
+                    Some(c) => out.push(c),
+                }
+            }
+        }
+
+        // 反斜杠后面的部分；start 是反斜杠的位置，出错时报告这个位置
+        fn escape(&mut self, start: usize) -> Result<char, JsonError> {
+            let invalid = |reason: MsgKey| JsonError { offset: start, reason };
+            match self.next_char() {
+                Some('"') => Ok('"'),
+                Some('\\') => Ok('\\'),
+                Some('/') => Ok('/'),
+                Some('b') => Ok('\u{8}'),
+                Some('f') => Ok('\u{c}'),
+                Some('n') => Ok('\n'),
+                Some('r') => Ok('\r'),
+                Some('t') => Ok('\t'),
+                Some('u') => {
+                    let high = self.hex4().ok_or_else(|| invalid(MsgKey::JsonBadHexEscape))?;
+                    // 基本平面以外的字符（例如 emoji）写成两个 \u：高代理 + 低代理
+                    let code = if (0xD800..0xDC00).contains(&high) {
+                        let low = match (self.next_char(), self.next_char()) {
+                            (Some('\\'), Some('u')) => self.hex4(),
+                            _ => None,
+                        };
+                        match low {
+                            Some(low) if (0xDC00..0xE000).contains(&low) => 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00),
+                            _ => return Err(invalid(MsgKey::JsonMissingLowSurrogate)),
+                        }
+                    } else {
+                        high
+                    };
+                    char::from_u32(code).ok_or_else(|| invalid(MsgKey::JsonInvalidCodepoint))
+                }
+                _ => Err(invalid(MsgKey::JsonUnknownEscape)),
+            }
+        }
+
+        fn hex4(&mut self) -> Option<u32> {
+            let digits = self.text.get(self.pos..self.pos + 4)?;
+            // from_str_radix 也接受 "+1F6" 这样带符号的写法，所以先检查是不是 4 个十六进制数字
+            if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                return None;
+            }
+            self.pos += 4;
+            u32::from_str_radix(digits, 16).ok()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn errors_follow_the_language() {
+            let error = from_json(r#"{"A": [1]}"#).unwrap_err();
+            assert_eq!(error, JsonError { offset: 7, reason: MsgKey::JsonExpectedString });
+            assert_eq!(error.localized(Lang::En), "Invalid JSON (byte 7): expected a string here");
+            assert_eq!(error.to_string(), "JSON 格式错误（第 7 字节）: 这里应该是字符串");
+        }
+
+        #[test]
+        fn round_trips_unicode_and_escapes() {
+            let mut world: HashMap<String, Vec<String>> = HashMap::new();
+            world.insert("研发部".to_string(), vec!["张三".to_string(), "Amir 🚀".to_string()]);
+            world.insert("Sales".to_string(), vec!["\"Big\" Bo".to_string(), "C:\\temp".to_string()]);
+            world.insert("Empty".to_string(), vec![]);
+            let text = to_json(&world);
+            assert_eq!(text, "{\n  \"Empty\": [],\n  \"Sales\": [\"\\\"Big\\\" Bo\", \"C:\\\\temp\"],\n  \"研发部\": [\"张三\", \"Amir 🚀\"]\n}\n");
+            assert_eq!(from_json(&text), Ok(world));
+        }
+
+        #[test]
+        fn reads_unicode_escapes_and_surrogate_pairs() {
+            // \u5f20 是“张”，\ud83d\ude80 是 🚀
+            let parsed = from_json(r#" { "R&D" : [ "\u5f20", "\ud83d\ude80" ] } "#).unwrap();
+            assert_eq!(parsed["R&D"], vec!["张", "🚀"]);
+        }
+
+        #[test]
+        fn reports_the_byte_offset_of_each_error() {
+            let offset = |text| from_json(text).unwrap_err().offset;
+            assert_eq!(from_json(r#"{"Sales": ["Amir",]}"#).unwrap_err(), JsonError { offset: 18, reason: MsgKey::JsonExpectedString });
+            assert_eq!(offset(r#"{"Sales": ["Amir"]"#), 18);
+            // "研发" 每个汉字占 3 个字节
+            assert_eq!(offset(r#"{"研发": "张三"}"#), 11);
+            assert_eq!(offset(r#"{"A": [], "A": []} x"#), 10);
+        }
+    }
+}
+
+// 把读到的数据合并进现有的部门表，和 Import 一样不会重复添加同一个员工。
+// 部门名先规范化（练习21），部门名或员工名是空的记录会被跳过。
+// 返回 (新加入的员工数, 跳过的员工数, 已经存在的员工数, 新建的部门数)
+fn merge_departments(
+    departments: &mut HashMap<String, Vec<String>>,
+    incoming: HashMap<String, Vec<String>>,
+) -> (usize, usize, usize, usize) {
+    let (mut added, mut skipped, mut duplicates, mut new_departments) = (0, 0, 0, 0);
+    for (department, names) in incoming {
+        let Some(department) = normalize_dept_path(&department) else {
+            skipped += names.len();
+            continue;
+        };
+        if !departments.contains_key(&department) {
+            new_departments += 1;
+        }
+        for ancestor in ancestor_paths(&department) {
+            departments.entry(ancestor).or_default();
+        }
+        let employees = departments.entry(department).or_default();
+        for name in names {
+            let name = sanitize_field(&name);
+            if name.is_empty() {
+                skipped += 1;
+            } else if employees.contains(&name) {
+                duplicates += 1;
+            } else {
+                employees.push(name);
+                added += 1;
+            }
+        }
+    }
+    (added, skipped, duplicates, new_departments)
+}
+
+// 练习46：
+// 比较两份数据（例如两次 Save 的文件），返回 (新加入的员工, 离开的员工)，都按名字排序。
+// 只看整个公司：员工换了部门不算加入也不算离开
+#[allow(dead_code)]
+fn turnover(before: &HashMap<String, Vec<String>>, after: &HashMap<String, Vec<String>>) -> (Vec<String>, Vec<String>) {
+    let everyone = |map: &HashMap<String, Vec<String>>| -> HashSet<String> { map.values().flatten().cloned().collect() };
+    let (before, after) = (everyone(before), everyone(after));
+
+    let mut joined: Vec<String> = after.difference(&before).cloned().collect();
+    let mut left: Vec<String> = before.difference(&after).cloned().collect();
+    joined.sort();
+    left.sort();
+    (joined, left)
+}
+
+// 练习47：
+// 撤销（Undo）。每个会修改数据的操作（Add、Remove、Move、Rename）执行前，先记下它可能改动的
+// 每个部门原来的样子（包括“原来不存在”）；撤销时把这些部门恢复原样。
+// 这样不用为每种操作单独写反向操作，也能正确处理顺带创建的上级部门、被删掉的空部门和合并的部门。
+// 模块只操作 HashMap，不读写 stdin，可以单独调用和检查
+mod undo {
+    use std::fmt;
+
+    use super::ancestor_paths;
+    use super::company::Company;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Operation {
+        Add { name: String, department: String },
+        Remove { name: String, department: String },
+        Move { name: String, from: String, to: String },
+        Rename { old: String, new: String },
+    }
+
+    // 用命令的写法显示，例如 "Add Sally to Sales"
+    impl fmt::Display for Operation {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Operation::Add { name, department } => write!(f, "Add {} to {}", name, department),
+                Operation::Remove { name, department } => write!(f, "Remove {} from {}", name, department),
+                Operation::Move { name, from, to } => write!(f, "Move {} from {} to {}", name, from, to),
+                Operation::Rename { old, new } => write!(f, "Rename {} to {}", old, new),
+            }
+        }
+    }
+
+    impl Operation {
+        // 这个操作可能改动的所有部门：目标部门的上级部门可能会被顺带创建
+        fn touched_departments(&self) -> Vec<String> {
+            let (mut touched, target) = match self {
+                Operation::Add { department, .. } => (vec![], department),
+                Operation::Remove { department, .. } => (vec![], department),
+                Operation::Move { from, to, .. } => (vec![from.clone()], to),
+                Operation::Rename { old, new } => (vec![old.clone()], new),
+            };
+            touched.extend(ancestor_paths(target));
+            touched.push(target.clone());
+            touched
+        }
+    }
+
+    struct Entry {
+        operation: Operation,
+        // 每个部门在操作之前的员工列表；None 表示这个部门原来不存在
+        before: Vec<(String, Option<Vec<String>>)>,
+    }
+
+    pub struct Journal {
+        entries: Vec<Entry>,
+    }
+
+    impl Journal {
+        pub fn new() -> Self {
+            Journal { entries: Vec::new() }
+        }
+
+        // 清空历史。Load、Import 这类不能撤销的命令替换或合并了数据之后要调用它，
+        // 否则撤销更早的操作会把新数据覆盖掉
+        pub fn clear(&mut self) {
+            self.entries.clear();
+        }
+
+        // 执行 apply 并记录下来。apply 没有改动任何数据（例如员工不存在而失败）时不记录，
+        // 这样 Undo 不会撤销一个什么也没做的操作
+        pub fn apply<R>(&mut self, operation: Operation, company: &mut Company, apply: impl FnOnce(&mut Company) -> R) -> R {
+            let before: Vec<(String, Option<Vec<String>>)> = operation
+                .touched_departments()
+                .into_iter()
+                .map(|department| {
+                    let employees = company.departments().get(&department).cloned();
+                    (department, employees)
+                })
+                .collect();
+            let result = apply(company);
+            let changed =
+                before.iter().any(|(department, employees)| company.departments().get(department) != employees.as_ref());
+            if changed {
+                self.entries.push(Entry { operation, before });
+            }
+            result
+        }
+
+        // 撤销最近一次操作并返回它；没有可以撤销的操作时返回 None
+        pub fn undo(&mut self, company: &mut Company) -> Option<Operation> {
+            let entry = self.entries.pop()?;
+            for (department, employees) in entry.before {
+                company.restore_department(&department, employees);
+            }
+            Some(entry.operation)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::collections::HashMap;
+
+        fn org(entries: &[(&str, &[&str])]) -> HashMap<String, Vec<String>> {
+            entries
+                .iter()
+                .map(|(department, employees)| (department.to_string(), employees.iter().map(|e| e.to_string()).collect()))
+                .collect()
+        }
+
+        #[test]
+        fn undo_add_also_removes_created_parents() {
+            let mut departments = Company::from(org(&[("Sales", &["Amir"])]));
+            let original = departments.clone();
+            let mut journal = Journal::new();
+            let add = Operation::Add { name: "Sally".to_string(), department: "Eng/Web".to_string() };
+            journal.apply(add.clone(), &mut departments, |company| company.add_employee("Sally", "Eng/Web"));
+            assert_eq!(departments.departments(), &org(&[("Sales", &["Amir"]), ("Eng", &[]), ("Eng/Web", &["Sally"])]));
+
+            assert_eq!(journal.undo(&mut departments), Some(add));
+            assert_eq!(departments, original);
+            assert_eq!(journal.undo(&mut departments), None);
+        }
+
+        #[test]
+        fn undo_remove_brings_back_a_deleted_department() {
+            let mut departments = Company::from(org(&[("Sales", &["Amir"]), ("Eng", &["Sally"])]));
+            let original = departments.clone();
+            let mut journal = Journal::new();
+            let remove = Operation::Remove { name: "Amir".to_string(), department: "Sales".to_string() };
+            journal.apply(remove, &mut departments, |company| company.remove_employee("Amir", "Sales"));
+            assert!(!departments.departments().contains_key("Sales"));
+
+            assert_eq!(journal.undo(&mut departments).map(|op| op.to_string()), Some("Remove Amir from Sales".to_string()));
+            assert_eq!(departments, original);
+        }
+
+        #[test]
+        fn undo_move_restores_both_departments() {
+            let mut departments = Company::from(org(&[("Sales", &["Amir"]), ("Eng", &["Sally", "Cy"])]));
+            let original = departments.clone();
+            let mut journal = Journal::new();
+            let move_op = Operation::Move { name: "Sally".to_string(), from: "Eng".to_string(), to: "R&D/Sales".to_string() };
+            let result = journal.apply(move_op, &mut departments, |company| company.move_employee("Sally", "Eng", "R&D/Sales"));
+            assert!(result.is_ok());
+            assert_eq!(departments.departments()["R&D/Sales"], vec!["Sally"]);
+            assert_eq!(departments.departments()["Eng"], vec!["Cy"]);
+
+            assert_eq!(
+                journal.undo(&mut departments).map(|op| op.to_string()),
+                Some("Move Sally from Eng to R&D/Sales".to_string())
+            );
+            assert_eq!(departments, original);
+        }
+
+        #[test]
+        fn undo_rename_into_a_new_path() {
+            let mut departments = Company::from(org(&[("Sales", &["Amir"])]));
+            let original = departments.clone();
+            let mut journal = Journal::new();
+            let rename = Operation::Rename { old: "Sales".to_string(), new: "R&D/Sales".to_string() };
+            let result = journal.apply(rename, &mut departments, |company| company.rename_department("Sales", "R&D/Sales"));
+            assert!(matches!(result, Ok(None)));
+            assert_eq!(departments.departments(), &org(&[("R&D", &[]), ("R&D/Sales", &["Amir"])]));
+
+            journal.undo(&mut departments);
+            assert_eq!(departments, original);
+        }
+
+        #[test]
+        fn undo_rename_merge_splits_the_departments_again() {
+            let mut departments = Company::from(org(&[("Sales", &["Amir", "Bo"]), ("Team", &["Bo", "Cy"])]));
+            let original = departments.clone();
+            let mut journal = Journal::new();
+            let rename = Operation::Rename { old: "Sales".to_string(), new: "Team".to_string() };
+            let result = journal.apply(rename, &mut departments, |company| company.rename_department("Sales", "Team"));
+            // 只有 Amir 是新加入的，Bo 原来就在 Team 里
+            assert!(matches!(result, Ok(Some(1))));
+            assert_eq!(departments.departments(), &org(&[("Team", &["Bo", "Cy", "Amir"])]));
+
+            journal.undo(&mut departments);
+            assert_eq!(departments, original);
+        }
+
+        #[test]
+        fn failed_operations_are_not_recorded_and_undo_goes_backwards() {
+            let mut departments = Company::from(org(&[("Sales", &["Amir"])]));
+            let mut journal = Journal::new();
+            let add = Operation::Add { name: "Bo".to_string(), department: "Sales".to_string() };
+            journal.apply(add, &mut departments, |company| company.add_employee("Bo", "Sales"));
+            let after_add = departments.clone();
+            let failed = Operation::Remove { name: "Zed".to_string(), department: "Sales".to_string() };
+            journal.apply(failed, &mut departments, |company| company.remove_employee("Zed", "Sales"));
+            let move_op = Operation::Move { name: "Amir".to_string(), from: "Sales".to_string(), to: "Eng".to_string() };
+            let _ = journal.apply(move_op, &mut departments, |company| company.move_employee("Amir", "Sales", "Eng"));
+            assert!(matches!(journal.undo(&mut departments), Some(Operation::Move { .. })));
+            assert_eq!(departments, after_add);
+            assert!(matches!(journal.undo(&mut departments), Some(Operation::Add { .. })));
+            assert_eq!(departments.departments(), &org(&[("Sales", &["Amir"])]));
+            // Remove Zed 什么也没改，没有第三条记录
+            assert_eq!(journal.undo(&mut departments), None);
+
+            journal.apply(Operation::Add { name: "Cy".to_string(), department: "Eng".to_string() }, &mut departments, |company| {
+                company.add_employee("Cy", "Eng")
+            });
+            journal.clear();
+            assert_eq!(journal.undo(&mut departments), None);
+        }
+    }
+}
+
+// 练习48：
+// 和 List All 显示的内容相同：部门按名字排序，子部门多缩进一层，员工排序后以 "  - " 开头列在部门下面。
+// 结果只取决于数据本身，和 HashMap 的遍历顺序无关，所以可以直接和期望的字符串比较
+fn render_org_tree(map: &HashMap<String, Vec<String>>) -> String {
+    to_tree(map).render()
+}
+
+// 练习49：
+// Company 独占公司数据（练习35 里它还只是借用 main 的 HashMap）。
+// 修改数据的命令都调用它的方法，方法只返回数据、不打印任何东西，打印和翻译都留给 execute，
+// 这样不用模拟输入输出就能检查每个方法的结果。只需要读取数据的函数（导出、查询、统计）用 departments() 拿到只读引用
+mod company {
+    use std::collections::{BTreeMap, HashMap};
+    use std::convert::Infallible;
+    use std::ops::ControlFlow;
+
+    use super::{
+        ancestor_paths, departments_ci, descendant_departments, merge_departments, merge_import_chunked,
+        remove_employee, transfer_employee, ChunkError, ProcessOutcome, Progress, RemoveOutcome, RenameError,
+        TransferError,
+    };
+
+    #[derive(Debug, Clone, Default, PartialEq)]
+    pub struct Company {
+        departments: HashMap<String, Vec<String>>,
+    }
+
+    // 用已有的数据创建，例如启动时读回来的 departments.csv，或者 Load 读到的文件
+    impl From<HashMap<String, Vec<String>>> for Company {
+        fn from(departments: HashMap<String, Vec<String>>) -> Self {
+            Company { departments }
+        }
+    }
+
+    impl Company {
+        #[allow(dead_code)]
+        pub fn new() -> Self {
+            Company::default()
+        }
+
+        pub fn departments(&self) -> &HashMap<String, Vec<String>> {
+            &self.departments
+        }
+
+        pub fn add_employee(&mut self, name: &str, department: &str) {
+            // 1. .entry(department.to_string()): 检查 'department' 这个键是否存在。
+            // 2. .or_default(): 如果键不存在，就插入 Vec 的默认值（一个新的空 Vec）作为值。
+            // 3. 无论键是本来就存在还是刚刚插入的，.entry().or_default() 都会返回一个指向 Vec 的可变引用。
+            // 4. .push(name.to_string()): 最后，调用 Vec 的 push 方法，把员工名字加进去。
+            self.departments.entry(department.to_string()).or_default().push(name.to_string());
+            // 5. 多级部门的每一个上级部门也要存在（练习21）
+            for ancestor in ancestor_paths(department) {
+                self.departments.entry(ancestor).or_default();
+            }
+        }
+
+        // 部门变空时连部门一起删掉（见 remove_employee，练习31）
+        pub fn remove_employee(&mut self, name: &str, department: &str) -> RemoveOutcome {
+            remove_employee(&mut self.departments, name, department)
+        }
+
+        // Move 和 Transfer 命令都用它；出错时数据保持不变（见 transfer_employee，练习32、练习35）
+        pub fn move_employee(&mut self, name: &str, from: &str, to: &str) -> Result<(), TransferError> {
+            transfer_employee(&mut self.departments, name, from, to)
+        }
+
+        // 练习37：
+        // 把 old 部门改名为 new，员工列表跟着一起移过去。new 已经存在时把两边的员工合并并去重，
+        // 返回 Some(合并进去的人数)；只是改名时返回 None。出错时数据保持不变
+        pub fn rename_department(&mut self, old: &str, new: &str) -> Result<Option<usize>, RenameError> {
+            if old == new {
+                return Err(RenameError::SameName);
+            }
+            if !self.departments.contains_key(old) {
+                return Err(RenameError::NotFound);
+            }
+            if !descendant_departments(&self.departments, old).is_empty() {
+                return Err(RenameError::HasSubDepartments);
+            }
+
+            let moved = self.departments.remove(old).unwrap_or_default();
+            for ancestor in ancestor_paths(new) {
+                self.departments.entry(ancestor).or_default();
+            }
+            match self.departments.get_mut(new) {
+                Some(existing) => {
+                    let before = existing.len();
+                    for name in moved {
+                        if !existing.contains(&name) {
+                            existing.push(name);
+                        }
+                    }
+                    Ok(Some(existing.len() - before))
+                }
+                None => {
+                    self.departments.insert(new.to_string(), moved);
+                    Ok(None)
+                }
+            }
+        }
+
+        // ImportJson 读到的数据合并进来，返回值见 merge_departments（练习45）
+        pub fn merge(&mut self, incoming: HashMap<String, Vec<String>>) -> (usize, usize, usize, usize) {
+            merge_departments(&mut self.departments, incoming)
+        }
+
+        // Import 命令分块合并，中途取消时数据保持不变（见 merge_import_chunked，练习7）
+        pub fn import_chunked(
+            &mut self,
+            records: &[(String, String)],
+            chunk_size: usize,
+            on_progress: impl FnMut(Progress) -> ControlFlow<()>,
+        ) -> Result<(ProcessOutcome, usize), ChunkError<Infallible>> {
+            merge_import_chunked(&mut self.departments, records, chunk_size, on_progress)
+        }
+
+        // Undo 用（练习47）：把部门恢复成原来的员工列表；None 表示这个部门原来不存在，直接删掉
+        pub fn restore_department(&mut self, department: &str, employees: Option<Vec<String>>) {
+            match employees {
+                Some(employees) => {
+                    self.departments.insert(department.to_string(), employees);
+                }
+                None => {
+                    self.departments.remove(department);
+                }
+            }
+        }
+
+        // 部门里的员工，按名字排好序；部门存在但没有员工时返回 Some(空列表)，部门不存在时返回 None。
+        // 通过不区分大小写的视图来查询，"list sales" 也能找到 "Sales"（练习9）
+        pub fn list_department(&self, department: &str) -> Option<Vec<String>> {
+            let lookup = departments_ci(&self.departments);
+            let mut employees: Vec<String> = lookup.get(department)?.iter().map(|employee| employee.to_string()).collect();
+            employees.sort();
+            Some(employees)
+        }
+
+        // 所有部门，BTreeMap 按部门名排序；每个部门的员工也排好序
+        pub fn list_all(&self) -> BTreeMap<String, Vec<String>> {
+            self.departments
+                .iter()
+                .map(|(department, employees)| {
+                    let mut employees = employees.clone();
+                    employees.sort();
+                    (department.clone(), employees)
+                })
+                .collect()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn names(list: &[&str]) -> Vec<String> {
+            list.iter().map(|name| name.to_string()).collect()
+        }
+
+        fn sample() -> Company {
+            let mut company = Company::new();
+            company.add_employee("Sally", "Engineering/Web");
+            company.add_employee("Amir", "Sales");
+            company.add_employee("Bo", "Sales");
+            company
+        }
+
+        #[test]
+        fn add_employee_creates_the_department_and_its_parents() {
+            let company = sample();
+            assert_eq!(company.departments().len(), 3);
+            assert_eq!(company.departments()["Engineering"], names(&[]));
+            assert_eq!(company.departments()["Engineering/Web"], names(&["Sally"]));
+            assert_eq!(company.departments()["Sales"], names(&["Amir", "Bo"]));
+        }
+
+        #[test]
+        fn add_employee_keeps_repeated_names() {
+            let mut company = Company::new();
+            company.add_employee("Amir", "Sales");
+            company.add_employee("Amir", "Sales");
+            assert_eq!(company.departments()["Sales"], names(&["Amir", "Amir"]));
+        }
+
+        #[test]
+        fn list_department_sorts_and_ignores_case() {
+            let mut company = sample();
+            company.add_employee("Abe", "Sales");
+            company.add_employee("Bo", "Sales");
+            assert_eq!(company.list_department("sales"), Some(names(&["Abe", "Amir", "Bo", "Bo"])));
+            assert_eq!(company.list_department("ENGINEERING/WEB"), Some(names(&["Sally"])));
+        }
+
+        #[test]
+        fn list_department_tells_empty_from_missing() {
+            let company = sample();
+            // Engineering 只有下级部门，自己没有员工，但部门是存在的
+            assert_eq!(company.list_department("Engineering"), Some(vec![]));
+            assert_eq!(company.list_department("Design"), None);
+            assert_eq!(Company::new().list_department("Sales"), None);
+        }
+
+        #[test]
+        fn list_all_returns_every_department_sorted() {
+            let mut company = sample();
+            company.add_employee("Amir", "Sales");
+            company.add_employee("Abe", "Sales");
+            let all = company.list_all();
+            assert_eq!(all.keys().collect::<Vec<_>>(), vec!["Engineering", "Engineering/Web", "Sales"]);
+            assert_eq!(all["Engineering"], names(&[]));
+            assert_eq!(all["Sales"], names(&["Abe", "Amir", "Amir", "Bo"]));
+            assert!(Company::new().list_all().is_empty());
+        }
+
+        #[test]
+        fn remove_employee_reports_what_happened() {
+            let mut company = sample();
+            assert_eq!(company.remove_employee("Zed", "Sales"), RemoveOutcome::EmployeeNotFound);
+            assert_eq!(company.remove_employee("Amir", "Design"), RemoveOutcome::DepartmentNotFound);
+            assert_eq!(company.remove_employee("Amir", "Sales"), RemoveOutcome::Removed { department_deleted: false });
+            assert_eq!(company.remove_employee("Bo", "Sales"), RemoveOutcome::Removed { department_deleted: true });
+            assert_eq!(company.list_department("Sales"), None);
+        }
+
+        #[test]
+        fn move_employee_changes_nothing_when_it_fails() {
+            let mut company = sample();
+            let before = company.clone();
+            assert!(matches!(company.move_employee("Sally", "Sales", "Design"), Err(TransferError::EmployeeNotFound)));
+            assert!(matches!(company.move_employee("Amir", "Design", "Sales"), Err(TransferError::SourceNotFound)));
+            assert!(matches!(company.move_employee("Amir", "Sales", "Sales"), Err(TransferError::SameDepartment)));
+            assert_eq!(company, before);
+
+            assert!(company.move_employee("Amir", "Sales", "Design").is_ok());
+            assert_eq!(company.list_department("Sales"), Some(names(&["Bo"])));
+            assert_eq!(company.list_department("Design"), Some(names(&["Amir"])));
+        }
+
+        #[test]
+        fn rename_department_merges_into_an_existing_department() {
+            let mut company = Company::new();
+            company.add_employee("Amir", "Enginering");
+            company.add_employee("Bo", "Enginering");
+            company.add_employee("Bo", "Engineering");
+            company.add_employee("Cy", "Engineering");
+            // Bo 两边都有，只算一次
+            assert!(matches!(company.rename_department("Enginering", "Engineering"), Ok(Some(1))));
+            assert_eq!(company.departments().len(), 1);
+            assert_eq!(company.departments()["Engineering"], names(&["Bo", "Cy", "Amir"]));
+
+            assert!(matches!(company.rename_department("Engineering", "R&D/Core"), Ok(None)));
+            assert_eq!(company.list_department("R&D/Core"), Some(names(&["Amir", "Bo", "Cy"])));
+            assert_eq!(company.list_department("R&D"), Some(vec![]));
+            assert_eq!(company.list_department("Engineering"), None);
+        }
+
+        #[test]
+        fn failed_renames_leave_the_company_unchanged() {
+            let mut company = sample();
+            let before = company.clone();
+            assert!(matches!(company.rename_department("Enginering", "Sales"), Err(RenameError::NotFound)));
+            assert!(matches!(company.rename_department("Sales", "Sales"), Err(RenameError::SameName)));
+            assert!(matches!(company.rename_department("Engineering", "R&D"), Err(RenameError::HasSubDepartments)));
+            assert_eq!(company, before);
+        }
+
+        #[test]
+        fn merge_and_import_add_to_the_existing_data() {
+            let mut company = sample();
+            let mut incoming = HashMap::new();
+            incoming.insert("Sales".to_string(), names(&["Amir", "Cy"]));
+            assert_eq!(company.merge(incoming), (1, 0, 1, 0));
+            assert_eq!(company.list_department("Sales"), Some(names(&["Amir", "Bo", "Cy"])));
+
+            let records = vec![("Design".to_string(), "Dee".to_string())];
+            let outcome = company.import_chunked(&records, 10, |_| ControlFlow::Continue(()));
+            assert_eq!(outcome, Ok((ProcessOutcome::Completed { processed: 1 }, 1)));
+            assert_eq!(company.list_department("Design"), Some(names(&["Dee"])));
+        }
+
+        #[test]
+        fn restore_department_puts_back_or_deletes() {
+            let mut company = sample();
+            company.restore_department("Sales", Some(names(&["Zoe"])));
+            company.restore_department("Engineering/Web", None);
+            assert_eq!(company.list_department("Sales"), Some(names(&["Zoe"])));
+            assert_eq!(company.list_department("Engineering/Web"), None);
+            assert_eq!(Company::from(company.departments().clone()), company);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 练习5
+    fn mapping(name: &str, department: &str, ignore_unknown: bool) -> ColumnMapping {
+        ColumnMapping { name_column: name.to_string(), department_column: department.to_string(), ignore_unknown }
+    }
+
+    #[test]
+    fn import_reads_quoted_fields_with_commas() {
+        let csv = "id,dept,name\n1,\"R&D, West\",\"Smith, J\"\n2,Sales, Amir \n";
+        assert_eq!(csv_header(csv).unwrap(), vec!["id", "dept", "name"]);
+        let result = import_with_mapping(csv, &mapping("name", "dept", true)).unwrap();
+        assert_eq!(result.records, vec![
+            ("R&D, West".to_string(), "Smith, J".to_string()),
+            ("Sales".to_string(), "Amir".to_string()),
+        ]);
+        assert_eq!(result.rows_imported, 2);
+    }
+
+    #[test]
+    fn import_counts_skipped_and_merged_rows() {
+        let csv = "department,name\n\nSales,Amir\nSales, Amir \n,Bob\nEng,\n\"Eng\",\"Sally\"\n";
+        let result = import_with_mapping(csv, &mapping("name", "department", false)).unwrap();
+        assert_eq!(result.records.len(), 2);
+        assert_eq!((result.rows_imported, result.rows_skipped, result.duplicates_merged), (2, 2, 1));
+    }
+
+    #[test]
+    fn import_reports_header_and_mapping_problems() {
+        assert!(matches!(csv_header("\n \n"), Err(ImportError::MissingHeader)));
+        let header_only = import_with_mapping("name,department\n", &mapping("name", "department", false)).unwrap();
+        assert_eq!(header_only.records, Vec::new());
+        assert!(matches!(
+            import_with_mapping("name,dept\n", &mapping("name", "department", true)),
+            Err(ImportError::UnknownColumn(column)) if column == "department"
+        ));
+        assert!(matches!(
+            import_with_mapping("name,dept,id\n", &mapping("name", "dept", false)),
+            Err(ImportError::UnmappedColumns(columns)) if columns == vec!["id"]
+        ));
+        assert!(matches!(
+            csv_header("name,\"dept\n"),
+            Err(ImportError::Csv(CsvError::UnclosedQuote { row: 1 }))
+        ));
+    }
+
+    #[test]
+    fn prompt_reads_the_answer_from_the_given_input() {
+        let mut input = LineInput::new(io::Cursor::new(" 2 \nSales\n"), 80);
+        assert_eq!(prompt(&mut input, "Which column?").unwrap(), "2");
+        assert_eq!(prompt(&mut input, "Which column?").unwrap(), "Sales");
+        assert_eq!(prompt(&mut input, "Which column?").unwrap(), "");
+    }
+
+    // 练习7
+    fn sum_in_chunks(items: &[u32], chunk_size: usize, stop_after: usize) -> (Result<ProcessOutcome, ChunkError<String>>, u32, Vec<usize>) {
+        let mut sum = 0;
+        let mut reported = Vec::new();
+        let outcome = process_chunked(
+            items,
+            chunk_size,
+            |chunk| {
+                sum += chunk.iter().sum::<u32>();
+                Ok(())
+            },
+            |progress| {
+                reported.push(progress.done);
+                assert_eq!(progress.total, items.len());
+                if progress.done >= stop_after { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+            },
+        );
+        (outcome, sum, reported)
+    }
+
+    #[test]
+    fn process_chunked_reports_every_chunk_boundary() {
+        let items: Vec<u32> = (1..=10).collect();
+        let (outcome, sum, reported) = sum_in_chunks(&items, 4, usize::MAX);
+        assert_eq!(outcome, Ok(ProcessOutcome::Completed { processed: 10 }));
+        assert_eq!(sum, 55);
+        assert_eq!(reported, vec![4, 8, 10]);
+        // 正好整除时最后一块也是满的
+        assert_eq!(sum_in_chunks(&items, 5, usize::MAX).2, vec![5, 10]);
+        // 块比输入还大：只有一块
+        assert_eq!(sum_in_chunks(&items, 100, usize::MAX).2, vec![10]);
+    }
+
+    #[test]
+    fn process_chunked_stops_after_the_chunk_that_asked_to_cancel() {
+        let items: Vec<u32> = (1..=10).collect();
+        let (outcome, sum, reported) = sum_in_chunks(&items, 4, 8);
+        assert_eq!(outcome, Ok(ProcessOutcome::Cancelled { completed: 8 }));
+        assert_eq!(sum, 36);
+        assert_eq!(reported, vec![4, 8]);
+        // 最后一块之后才要求取消，所有元素都已经处理完，算作完成
+        assert_eq!(sum_in_chunks(&items, 4, 10).0, Ok(ProcessOutcome::Completed { processed: 10 }));
+    }
+
+    #[test]
+    fn process_chunked_rejects_zero_and_propagates_work_errors() {
+        let items: Vec<u32> = (1..=10).collect();
+        assert_eq!(sum_in_chunks(&items, 0, usize::MAX).0, Err(ChunkError::ZeroChunkSize));
+        let failing = process_chunked(
+            &items,
+            3,
+            |chunk| if chunk.contains(&5) { Err("bad item 5".to_string()) } else { Ok(()) },
+            |_| ControlFlow::Continue(()),
+        );
+        assert_eq!(failing, Err(ChunkError::Work { completed: 3, error: "bad item 5".to_string() }));
+    }
+
+    fn records(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(dept, name)| (dept.to_string(), name.to_string())).collect()
+    }
+
+    #[test]
+    fn cancelled_import_leaves_the_map_unchanged() {
+        let mut departments: HashMap<String, Vec<String>> = HashMap::new();
+        departments.insert("Sales".to_string(), vec!["Amir".to_string()]);
+        let before = departments.clone();
+        let incoming = records(&[("Sales", "Bob"), ("Eng", "Sally"), ("Eng", "Cy")]);
+        let outcome = merge_import_chunked(&mut departments, &incoming, 1, |progress| {
+            if progress.done == 2 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+        });
+        assert_eq!(outcome, Ok((ProcessOutcome::Cancelled { completed: 2 }, 0)));
+        assert_eq!(departments, before);
+
+        let outcome = merge_import_chunked(&mut departments, &incoming, 2, |_| ControlFlow::Continue(()));
+        assert_eq!(outcome, Ok((ProcessOutcome::Completed { processed: 3 }, 1)));
+        assert_eq!(departments["Sales"], vec!["Amir", "Bob"]);
+        assert_eq!(departments["Eng"], vec!["Sally", "Cy"]);
+    }
+
+    #[test]
+    fn cancel_sentinel_is_consumed_only_when_it_is_the_next_line() {
+        let mut input = io::Cursor::new("CANCEL\nList All\n");
+        assert!(cancel_requested(&mut input));
+        assert!(!cancel_requested(&mut input));
+        let mut rest = String::new();
+        input.read_line(&mut rest).unwrap();
+        assert_eq!(rest, "List All\n");
+        assert!(!cancel_requested(&mut io::Cursor::new("")));
+        assert!(cancel_requested(&mut io::Cursor::new("  CANCEL\r\n")));
+    }
+
+    #[test]
+    fn import_listens_for_cancel_only_when_the_input_is_scripted() {
+        let path = temp_path("import_cancel.csv");
+        let mut csv = String::from("name,department\n");
+        for i in 0..1200 {
+            csv.push_str(&format!("E{},D{}\n", i, i));
+        }
+        std::fs::write(&path, csv).unwrap();
+        let import = |input: &mut LineInput<io::Cursor<&str>>| {
+            let mut company = Company::new();
+            import_interactively(Lang::En, &path, &mut company, input);
+            company.departments().len()
+        };
+
+        // 脚本输入：第一块合并完之后读到 CANCEL，取消导入，已经合并的部分也会撤回
+        let mut scripted = LineInput::new(io::Cursor::new("1\n2\nCANCEL\n"), 80);
+        let imported_scripted = import(&mut scripted);
+        let rest_scripted = scripted.read_line().unwrap();
+        // 终端输入：不去读 CANCEL，全部导入，这一行留给命令循环
+        let mut terminal = LineInput::new(io::Cursor::new("1\n2\nCANCEL\n"), 80).scripted(false);
+        let imported_terminal = import(&mut terminal);
+        let rest_terminal = terminal.read_line().unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(imported_scripted, 0);
+        assert_eq!(rest_scripted, LineOutcome::Eof);
+        assert_eq!(imported_terminal, 1200);
+        assert_eq!(rest_terminal, LineOutcome::Line("CANCEL".to_string()));
+    }
+
+    // 练习48
+    #[test]
+    fn org_tree_is_sorted_and_indented() {
+        let mut org: HashMap<String, Vec<String>> = HashMap::new();
+        org.insert("Sales".to_string(), vec!["Zoe".to_string(), "Amir".to_string(), "Zoe".to_string()]);
+        org.insert("Eng/Web".to_string(), vec!["Cy".to_string()]);
+        org.insert("Eng".to_string(), vec![]);
+        let expected = "\n ## Eng ##\n\n   ## Web ##\n    - Cy\n\n ## Sales ##\n  - Amir\n  - Zoe\n";
+        assert_eq!(render_org_tree(&org), expected);
+        assert_eq!(render_org_tree(&HashMap::new()), "");
+    }
+
+    // 练习29
+    fn one_of_each_command() -> Vec<Command> {
+        let s = || "A".to_string();
+        vec![
+            Command::Add { name: s(), department: s() },
+            Command::Remove { name: s(), department: s() },
+            Command::Transfer { name: s(), from: s(), to: s() },
+            Command::Rename { old: s(), new: s() },
+            Command::ListAll,
+            Command::List(s()),
+            Command::Roster,
+            Command::Import(s()),
+            Command::ExportHtml(s()),
+            Command::ExportCsv(s()),
+            Command::ExportJson(s()),
+            Command::ImportJson(s()),
+            Command::Save(s()),
+            Command::Load { path: s(), recover: false },
+            Command::Lang(Lang::En),
+            Command::Run { path: s(), lenient: false },
+            Command::Query(QuerySpec::default()),
+            Command::Stats,
+            Command::Count(None),
+            Command::Find(s()),
+            Command::Undo,
+            Command::Help(None),
+            Command::Quit,
+        ]
+    }
+
+    // 每个 Command 变体对应的命令名。这里的 match 没有 `_`，
+    // 以后添加新的变体却忘了写在这里，编译器会直接报错
+    fn verb_of(command: &Command) -> &'static str {
+        match command {
+            Command::Add { .. } => "Add",
+            Command::Remove { .. } => "Remove",
+            Command::Transfer { .. } => "Transfer",
+            Command::Rename { .. } => "Rename",
+            Command::ListAll | Command::List(_) => "List",
+            Command::Roster => "Roster",
+            Command::Import(_) => "Import",
+            Command::ExportHtml(_) | Command::ExportCsv(_) => "Export",
+            Command::ExportJson(_) => "ExportJson",
+            Command::ImportJson(_) => "ImportJson",
+            Command::Save(_) => "Save",
+            Command::Load { .. } => "Load",
+            Command::Lang(_) => "Lang",
+            Command::Run { .. } => "Run",
+            Command::Query(_) => "Query",
+            Command::Stats => "Stats",
+            Command::Count(_) => "Count",
+            Command::Find(_) => "Find",
+            Command::Undo => "Undo",
+            Command::Help(_) => "Help",
+            Command::Quit => "Quit",
+        }
+    }
+
+    // verb_of 的 match 没有通配分支，新变体一定有命令名；这里再检查命令名在命令表里有说明。
+    // 从 COMMANDS 删掉任何一项，对应的命令就会出现在 missing 里
+    #[test]
+    fn every_command_has_a_help_entry() {
+        let missing: Vec<&str> =
+            one_of_each_command().iter().map(verb_of).filter(|verb| find_spec(verb).is_none()).collect();
+        assert_eq!(missing, Vec::<&str>::new());
+    }
+
+    #[test]
+    fn help_overview_lists_each_verb_once_in_the_current_language() {
+        for lang in [Lang::En, Lang::Zh] {
+            let overview = help_overview(lang);
+            let verbs: Vec<&str> = overview.lines().skip(1).filter_map(|line| line.split_whitespace().next()).collect();
+            assert_eq!(verbs, COMMANDS.iter().map(|spec| spec.verb).collect::<Vec<_>>());
+        }
+        assert!(help_overview(Lang::En).contains("Quit the program"));
+        assert!(help_overview(Lang::Zh).contains("退出程序"));
+    }
+
+    #[test]
+    fn help_for_a_command_shows_usage_and_examples() {
+        let help = help_for(Lang::En, find_spec("Add").unwrap());
+        assert!(help.starts_with("Usage: Add <name> to <department>\n"));
+        assert!(help.contains("  Add Sally to Engineering\n"));
+        assert!(help.contains("  Add Amir to Sales/East\n"));
+        assert!(help_for(Lang::Zh, find_spec("Add").unwrap()).starts_with("用法: Add <name> to <department>\n"));
+    }
+
+    #[test]
+    fn help_for_an_unknown_command_suggests_the_closest_verb() {
+        assert_eq!(parse_command("Help Add"), Ok(Command::Help(find_spec("Add"))));
+        assert_eq!(
+            parse_command("Help Quitt"),
+            Err(CommandError::UnknownCommand { word: "Quitt".to_string(), suggestion: Some("Quit") })
+        );
+        assert_eq!(parse_command("Help Frobnicate").unwrap_err().localized(Lang::En), "Unknown command 'Frobnicate'");
+        assert_eq!(
+            parse_command("Add Sally").unwrap_err().localized(Lang::En),
+            "Wrong arguments, usage: Add <name> to <department>"
+        );
+    }
+
+    // 练习30
+    #[test]
+    fn limited_line_at_and_over_the_cap() {
+        let mut input = io::Cursor::new("abcd\nabcde\nList All\n");
+        assert_eq!(read_limited_line(&mut input, 4).unwrap(), LineOutcome::Line("abcd".to_string()));
+        // 多了一个字节，整行连同换行符都被丢弃，下一行不受影响
+        assert_eq!(read_limited_line(&mut input, 4).unwrap(), LineOutcome::TooLong { consumed: 6 });
+        assert_eq!(read_limited_line(&mut input, 20).unwrap(), LineOutcome::Line("List All".to_string()));
+        assert_eq!(read_limited_line(&mut input, 20).unwrap(), LineOutcome::Eof);
+    }
+
+    #[test]
+    fn limited_line_discards_a_huge_line_without_storing_it() {
+        let mut huge = vec![b'x'; 3 * 1024 * 1024];
+        huge.extend_from_slice(b"\nQuit\n");
+        let mut input = io::BufReader::new(huge.as_slice());
+        assert_eq!(
+            read_limited_line(&mut input, DEFAULT_MAX_LINE_BYTES).unwrap(),
+            LineOutcome::TooLong { consumed: 3 * 1024 * 1024 + 1 }
+        );
+        assert_eq!(read_limited_line(&mut input, DEFAULT_MAX_LINE_BYTES).unwrap(), LineOutcome::Line("Quit".to_string()));
+    }
+
+    #[test]
+    fn limited_line_never_splits_a_multibyte_char() {
+        // "ab中" 是 5 个字节，"中" 跨过了 4 字节的上限
+        assert_eq!(read_limited_line(&mut io::Cursor::new("ab中\n"), 4).unwrap(), LineOutcome::TooLong { consumed: 6 });
+        assert_eq!(read_limited_line(&mut io::Cursor::new("ab中\n"), 5).unwrap(), LineOutcome::Line("ab中".to_string()));
+        // \r 计入长度，返回前去掉
+        assert_eq!(read_limited_line(&mut io::Cursor::new("ab中\r\n"), 6).unwrap(), LineOutcome::Line("ab中".to_string()));
+    }
+
+    #[test]
+    fn line_input_uses_its_configured_cap() {
+        assert_eq!(max_line_bytes_setting(None), DEFAULT_MAX_LINE_BYTES);
+        assert_eq!(max_line_bytes_setting(Some(" 64 ")), 64);
+        assert_eq!(max_line_bytes_setting(Some("0")), DEFAULT_MAX_LINE_BYTES);
+        assert_eq!(max_line_bytes_setting(Some("lots")), DEFAULT_MAX_LINE_BYTES);
+
+        let mut input = LineInput::new(io::Cursor::new("Add Sally to Sales\nList\n"), 8);
+        assert_eq!(input.read_line().unwrap(), LineOutcome::TooLong { consumed: 19 });
+        assert_eq!(input.read_line().unwrap(), LineOutcome::Line("List".to_string()));
+    }
+
+    #[test]
+    fn oversized_answers_do_not_reach_prompt_or_pager() {
+        let mut input = LineInput::new(io::Cursor::new("Department\ndept\n"), 4);
+        assert_eq!(prompt(&mut input, "Which column?").unwrap(), "");
+        assert_eq!(prompt(&mut input, "Which column?").unwrap(), "dept");
+
+        // 太长的回答当作回车，继续显示下一页
+        let lines: Vec<String> = (1..=4).map(|i| format!("line {}", i)).collect();
+        let mut input = LineInput::new(io::Cursor::new("qqqqqqqq\n"), 4);
+        assert_eq!(paginate(&lines, 2, &mut Vec::new(), &mut input).unwrap(), PagerOutcome::Completed(4));
+    }
+
+    // 每次读取都失败的输入，用来检查原来会 panic 的地方现在只是报告错误
+    struct BrokenInput;
+
+    impl io::Read for BrokenInput {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "input closed"))
+        }
+    }
+
+    #[test]
+    fn failed_reads_are_reported_instead_of_panicking() {
+        let mut input = LineInput::new(io::BufReader::new(BrokenInput), 80);
+        assert_eq!(prompt(&mut input, "Which column?").unwrap_err().kind(), io::ErrorKind::BrokenPipe);
+
+        // Import 在提问时读不到回答：什么都不导入，程序继续运行
+        let path = temp_path("import_broken_input.csv");
+        std::fs::write(&path, "name,department\nSally,Sales\n").unwrap();
+        let mut company = Company::new();
+        let mut lang = Lang::En;
+        let mut journal = undo::Journal::new();
+        let mut input = LineInput::new(io::BufReader::new(BrokenInput), 80);
+        let flow = execute(Command::Import(path.clone()), &mut company, &mut journal, &mut lang, &mut input);
+        let _ = std::fs::remove_file(&path);
+        assert!(flow.is_continue());
+        assert!(company.departments().is_empty());
+    }
+
+    #[test]
+    fn report_error_walks_the_source_chain_and_adds_a_hint() {
+        // 三层：外层的分块错误 -> 内层的分块错误 -> io 错误
+        let inner = ChunkError::Work { completed: 2, error: io::Error::new(io::ErrorKind::NotFound, "no such file") };
+        let outer = ChunkError::Work { completed: 5, error: inner };
+        assert_eq!(
+            report_error(Lang::Zh, &outer),
+            "处理完 5 个元素之后出错\n  caused by: 处理完 2 个元素之后出错\n  caused by: no such file\n提示: 请检查文件路径是否正确"
+        );
+        assert_eq!(
+            report_error(Lang::En, &outer).lines().last(),
+            Some("Hint: check that the path is correct")
+        );
+        assert_eq!(report_error(Lang::Zh, &ChunkError::<io::Error>::ZeroChunkSize), "分块大小不能为 0");
+    }
+
+    #[test]
+    fn report_error_hints_depend_on_the_io_error_kind() {
+        let hint = |kind| report_error(Lang::En, &io::Error::new(kind, "oops")).lines().nth(1).map(String::from);
+        assert_eq!(hint(io::ErrorKind::NotFound).as_deref(), Some("Hint: check that the path is correct"));
+        assert_eq!(hint(io::ErrorKind::PermissionDenied).as_deref(), Some("Hint: check the file's read/write permissions"));
+        assert_eq!(hint(io::ErrorKind::InvalidData).as_deref(), Some("Hint: the file must be UTF-8 text"));
+        assert_eq!(hint(io::ErrorKind::Other), None);
+
+        // 真实的文件错误：读不存在的文件
+        let missing = std::fs::read_to_string(temp_path("no_such_file.csv")).unwrap_err();
+        assert!(report_error(Lang::Zh, &missing).ends_with("\n提示: 请检查文件路径是否正确"));
+    }
+
+    // 练习33
+    fn sample_company() -> HashMap<String, Vec<String>> {
+        let mut company = HashMap::new();
+        company.insert("Engineering".to_string(), vec!["Sally".to_string(), "Amir".to_string()]);
+        company.insert("Engineering/Web".to_string(), vec!["Bob".to_string()]);
+        company.insert("Sales".to_string(), vec![]);
+        company
+    }
+
+    // 每个测试用自己的文件名，测试并行运行时不会互相覆盖
+    fn temp_path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("{}_{}", std::process::id(), name));
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn roster_round_trips_through_a_file() {
+        let company = sample_company();
+        assert_eq!(serialize_roster(&company).unwrap(), "Engineering:Sally,Amir\nEngineering/Web:Bob\nSales:\n");
+        let path = temp_path("roster_round_trip.txt");
+        save_roster(&company, &path).unwrap();
+        let loaded = load_roster(&path);
+        let _ = std::fs::remove_file(&path);
+        let (loaded, status) = loaded.unwrap();
+        assert_eq!(loaded, company);
+        assert_eq!(status, checksum::TrailerStatus::Verified);
+    }
+
+    #[test]
+    fn truncated_roster_is_rejected_but_can_be_recovered() {
+        let path = temp_path("roster_truncated.txt");
+        save_roster(&sample_company(), &path).unwrap();
+        // 模拟写到一半崩溃：只留下前两行（HEADER 和第一个部门）
+        let saved = std::fs::read_to_string(&path).unwrap();
+        let truncated: String = saved.lines().take(2).map(|line| format!("{}\n", line)).collect();
+        std::fs::write(&path, truncated).unwrap();
+        let strict = load_roster(&path);
+        let recovered = load_ignoring_checksum(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(matches!(strict, Err(RosterError::Checksum(checksum::TrailerError::Truncated))));
+        let (recovered, suspect) = recovered.unwrap();
+        assert_eq!(recovered["Engineering"], vec!["Sally", "Amir"]);
+        assert!(suspect);
+        assert!(matches!(load_roster(&path), Err(RosterError::Io(_))));
+    }
+
+    #[test]
+    fn malformed_roster_lines_report_their_line_number() {
+        assert!(matches!(
+            parse_roster("Sales:Amir\nEngineering Sally\n"),
+            Err(RosterError::Malformed { line: 2, reason: MsgKey::RosterMissingColon })
+        ));
+        assert!(matches!(
+            parse_roster("Sales:Amir,,Bob\n"),
+            Err(RosterError::Malformed { line: 1, reason: MsgKey::RosterEmptyEmployee })
+        ));
+        assert!(matches!(
+            parse_roster("Sales:\n\nSales:Amir\n"),
+            Err(RosterError::Malformed { line: 3, reason: MsgKey::RosterDuplicateDept })
+        ));
+        assert_eq!(parse_roster("Sales:Amir\nEngineering Sally\n").unwrap_err().to_string(), "第 2 行格式错误: 缺少 ':'");
+        // 手工写的文件缺少上级部门时自动补上
+        assert!(parse_roster("Eng/Web:Bob\n").unwrap().contains_key("Eng"));
+    }
+
+    #[test]
+    fn load_command_accepts_a_recover_flag() {
+        assert!(matches!(parse_command("Load company.txt"), Ok(Command::Load { recover: false, .. })));
+        assert!(matches!(parse_command("Load company.txt --recover"), Ok(Command::Load { recover: true, .. })));
+        assert!(parse_command("Load company.txt --force").is_err());
+        assert!(matches!(load_for_command(&temp_path("roster_missing.txt"), true), Err(RosterError::Io(_))));
+    }
+
+    #[test]
+    fn names_with_separators_cannot_be_saved() {
+        let mut odd = HashMap::new();
+        odd.insert("A:B".to_string(), vec![]);
+        assert!(matches!(serialize_roster(&odd), Err(RosterError::Unrepresentable(name)) if name == "A:B"));
+        let mut odd = HashMap::new();
+        odd.insert("Sales".to_string(), vec!["Smith, J".to_string()]);
+        assert!(matches!(serialize_roster(&odd), Err(RosterError::Unrepresentable(name)) if name == "Smith, J"));
+    }
+
+    // 练习40
+    #[test]
+    fn count_words_handles_empty_and_blank_text() {
+        assert!(count_words("").is_empty());
+        assert!(count_words("  \n\t ").is_empty());
+        assert!(count_words_ignore_case("").is_empty());
+    }
+
+    #[test]
+    fn count_words_counts_repeated_words() {
+        let repeated = count_words("go go go stop");
+        assert_eq!(repeated.get("go"), Some(&3));
+        assert_eq!(repeated.get("stop"), Some(&1));
+        assert_eq!(repeated.len(), 2);
+    }
+
+    #[test]
+    fn count_words_is_case_sensitive_unless_asked_not_to_be() {
+        let mixed = count_words("Hello hello HELLO world");
+        assert_eq!((mixed.get("hello"), mixed.get("Hello"), mixed.get("HELLO")), (Some(&1), Some(&1), Some(&1)));
+        let folded = count_words_ignore_case("Hello hello HELLO world");
+        assert_eq!(folded.get("hello"), Some(&3));
+        assert_eq!(folded.get("Hello"), None);
+    }
+
+    #[test]
+    fn count_words_strips_surrounding_punctuation() {
+        let punctuated = count_words("Hello, world. Don't say \"world\" -- say world!");
+        assert_eq!(punctuated.get("world"), Some(&3));
+        assert_eq!(punctuated.get("Don't"), Some(&1));
+        // 只有标点的片段不算单词
+        assert_eq!(punctuated.get("--"), None);
+    }
+
+    // 练习43
+    #[test]
+    fn csv_field_quotes_only_when_needed() {
+        assert_eq!(csv_field("Sally"), "Sally");
+        assert_eq!(csv_field("Smith, \"J\""), "\"Smith, \"\"J\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn exported_csv_parses_back_to_the_same_rows() {
+        let mut tricky: HashMap<String, Vec<String>> = HashMap::new();
+        tricky.insert("Sales".to_string(), vec!["Smith, \"J\"".to_string(), "Amir".to_string()]);
+        tricky.insert("R&D, West".to_string(), vec!["Bo".to_string()]);
+        tricky.insert("Empty".to_string(), vec![]);
+        let path = temp_path("export.csv");
+        let written = export_csv(&tricky, &path);
+        let text = std::fs::read_to_string(&path);
+        let _ = std::fs::remove_file(&path);
+
+        // 空部门不写，表头不算在行数里
+        assert_eq!(written.unwrap(), 3);
+        let text = text.unwrap();
+        assert!(text.starts_with("department,employee\r\n"));
+        assert_eq!(parse_csv_rows(&text).unwrap(), vec![
+            vec!["department", "employee"],
+            vec!["R&D, West", "Bo"],
+            vec!["Sales", "Amir"],
+            vec!["Sales", "Smith, \"J\""],
+        ]);
+    }
+
+    #[test]
+    fn csv_parser_keeps_quoted_newlines_and_reports_bad_quotes() {
+        assert_eq!(parse_csv_rows("a,\"b\nc\"\n").unwrap(), vec![vec!["a", "b\nc"]]);
+        assert_eq!(parse_csv_rows("a,\"b"), Err(CsvError::UnclosedQuote { row: 1 }));
+        assert_eq!(parse_csv_rows("x\n\"a\"b,c"), Err(CsvError::TextAfterQuote { row: 2 }));
+    }
+
+    fn staff(entries: &[(&str, &[&str])]) -> HashMap<String, Vec<String>> {
+        entries
+            .iter()
+            .map(|(department, names)| (department.to_string(), names.iter().map(|name| name.to_string()).collect()))
+            .collect()
+    }
+
+    // 按脚本逐条执行命令，输入为空（不会有任何提问被回答）
+    fn run_lines(lines: &[&str]) -> Company {
+        let mut company = Company::new();
+        let mut lang = Lang::En;
+        let mut journal = undo::Journal::new();
+        for line in lines {
+            let command = parse_command(line).unwrap();
+            let _ = execute(command, &mut company, &mut journal, &mut lang, &mut LineInput::new(io::empty(), DEFAULT_MAX_LINE_BYTES));
+        }
+        company
+    }
+
+    // 练习3
+    #[test]
+    fn most_common_word_length_counts_by_chars() {
+        // 长度为 3 的单词最多（the, cat, sat, the, big, red, mat）
+        assert_eq!(most_common_word_length("the cat sat on the big red mat"), Some(3));
+        assert_eq!(most_common_word_length(""), None);
+    }
+
+    // 练习4
+    #[test]
+    fn avg_name_length_skips_empty_departments() {
+        let sample = staff(&[("Engineering", &["Sally", "Amir"]), ("Sales", &["Bob"]), ("Empty", &[])]);
+        let mut averages: Vec<_> = avg_name_length(&sample).into_iter().collect();
+        averages.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(averages, vec![("Engineering".to_string(), 4.5), ("Sales".to_string(), 3.0)]);
+    }
+
+    // 练习6
+    #[test]
+    fn largest_department_breaks_ties_alphabetically() {
+        let mut sample = staff(&[("Engineering", &["Sally", "Amir"]), ("Sales", &["Bob"]), ("Empty", &[])]);
+        assert_eq!(largest_department(&sample), Some(("Engineering".to_string(), 2)));
+        sample.insert("Design".to_string(), vec!["Lin".to_string(), "Mia".to_string()]);
+        assert_eq!(largest_department(&sample), Some(("Design".to_string(), 2)));
+        assert_eq!(largest_department(&HashMap::new()), None);
+    }
+
+    // 练习8
+    #[test]
+    fn all_employees_sorted_lists_each_name_once() {
+        let overlapping = staff(&[("Engineering", &["Sally", "Amir"]), ("Sales", &["Amir", "Bob"])]);
+        assert_eq!(all_employees_sorted(&overlapping), vec!["Amir", "Bob", "Sally"]);
+    }
+
+    // 练习9
+    #[test]
+    fn ci_multimap_keeps_the_first_spelling() {
+        let mut ci: CiMultiMap<u32> = CiMultiMap::new();
+        ci.insert("Sales", 1);
+        ci.insert("SALES", 2);
+        ci.insert("engineering", 3);
+        let mut keys: Vec<&str> = ci.keys_display().collect();
+        keys.sort();
+        assert_eq!(keys, vec!["Sales", "engineering"]);
+        assert_eq!(ci.get("sAlEs"), Some(&[1, 2][..]));
+
+        // 值删光以后键也随之消失
+        assert_eq!(ci.remove_value("sales", |v| *v > 0), 2);
+        assert_eq!((ci.len(), ci.total_values(), ci.is_empty()), (1, 1, false));
+        assert_eq!(ci.get("Sales"), None);
+    }
+
+    #[test]
+    fn word_line_index_ignores_case() {
+        let index = word_line_index("The cat\nthe dog\nA CAT");
+        assert_eq!(index.get("cat"), Some(&[1, 3][..]));
+        assert_eq!(index.get("THE"), Some(&[1, 2][..]));
+        assert_eq!(index.get("bird"), None);
+    }
+
+    // 练习10
+    #[test]
+    fn shannon_entropy_of_simple_strings() {
+        assert_eq!(shannon_entropy("abab"), 1.0);
+        assert_eq!(shannon_entropy("aaaa"), 0.0);
+        assert!((shannon_entropy("hello") - 1.922).abs() < 0.001);
+    }
+
+    // 练习12
+    #[test]
+    fn dedup_consecutive_keeps_separated_repeats() {
+        let runs = vec![1, 2, 2, 3, 3, 3, 3, 3, 1];
+        assert_eq!(runs.into_iter().dedup_consecutive().collect::<Vec<_>>(), vec![1, 2, 3, 1]);
+        assert_eq!((0..5).map(|n| n / 2).dedup_consecutive().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(Vec::<i32>::new().into_iter().dedup_consecutive().next(), None);
+        assert_eq!((0..10).dedup_consecutive().size_hint(), (1, Some(10)));
+    }
+
+    #[test]
+    fn enumerate1_counts_from_one() {
+        let numbered: Vec<_> = ["apple", "pear"].iter().enumerate1().collect();
+        assert_eq!(numbered, vec![(1, &"apple"), (2, &"pear")]);
+        assert_eq!((10..13).enumerate1().size_hint(), (3, Some(3)));
+    }
+
+    // 练习13
+    #[test]
+    fn word_ladder_finds_a_shortest_valid_chain() {
+        let dictionary: HashSet<String> = LADDER_WORDS.iter().map(|w| w.to_string()).collect();
+        let chain = word_ladder("cold", "warm", &dictionary).unwrap();
+        // 每一步都只改了一个字母，并且都是词典里的单词
+        assert_eq!(chain.len(), 5);
+        assert_eq!((chain[0].as_str(), chain[4].as_str()), ("cold", "warm"));
+        assert!(chain.windows(2).all(|w| differs_by_one(&w[0], &w[1]) && dictionary.contains(&w[1])));
+
+        assert_eq!(word_ladder("cold", "cold", &dictionary), Some(vec!["cold".to_string()]));
+        // "wart" 不在词典里；"warmth" 长度不同；"lynx" 没有路径可以到达
+        assert_eq!(word_ladder("cold", "wart", &dictionary), None);
+        assert_eq!(word_ladder("cold", "warmth", &dictionary), None);
+        assert_eq!(word_ladder("cold", "lynx", &dictionary), None);
+        assert!(differs_by_one("cold", "cord"));
+        assert!(!differs_by_one("cold", "card"));
+    }
+
+    // 练习14
+    #[test]
+    fn alphabet_by_frequency_breaks_ties_by_char() {
+        assert_eq!(alphabet_by_frequency("hello world"), vec!['l', 'o', ' ', 'd', 'e', 'h', 'r', 'w']);
+        assert_eq!(alphabet_by_frequency(""), Vec::<char>::new());
+    }
+
+    // 练习15
+    #[test]
+    fn html_escapes_special_characters() {
+        assert_eq!(
+            html::escape("<script>alert('hi')</script> & \"co\""),
+            "&lt;script&gt;alert(&#39;hi&#39;)&lt;/script&gt; &amp; &quot;co&quot;"
+        );
+    }
+
+    #[test]
+    fn html_doc_renders_one_page() {
+        let mut doc = html::HtmlDoc::new("Demo");
+        doc.heading(1, "Departments");
+        doc.paragraph("Tom & Jerry <3");
+        doc.table(&["Name", "Count"], &[vec!["A".to_string(), "1".to_string()], vec!["B".to_string(), "2".to_string()]]);
+        doc.table(&["Empty"], &[]);
+        let page = doc.render();
+        assert_eq!(page.matches("<td>").count(), 4);
+        assert_eq!(page.matches("<html").count(), 1);
+        assert!(page.contains("Tom &amp; Jerry &lt;3"));
+    }
+
+    #[test]
+    fn exported_html_escapes_the_data() {
+        let path = temp_path("departments.html");
+        export_html(&staff(&[("R&D", &["<script>", "Bob"])]), &path).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(written.contains("&lt;script&gt;"));
+        assert!(!written.contains("<script>"));
+    }
+
+    #[test]
+    fn atomic_write_overwrites_without_leaving_temp_files() {
+        // 单独的目录，方便检查里面有没有残留的临时文件
+        let dir = temp_path("atomic_write_dir");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = format!("{}/report.json", dir);
+        atomic_write(&path, b"first").unwrap();
+        atomic_write(&path, b"second").unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        let entries: Vec<_> = std::fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+        // 目标所在的目录不存在时直接报错，也不会留下任何东西
+        let missing = atomic_write(&format!("{}/no_such_dir/report.json", dir), b"x");
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(written, "second");
+        assert_eq!(entries, vec![std::ffi::OsString::from("report.json")]);
+        assert_eq!(missing.unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
+
+    // 练习16
+    #[test]
+    fn department_percentages_are_sorted_by_name() {
+        let sample = staff(&[("Engineering", &["Sally", "Amir"]), ("Sales", &["Bob"]), ("Empty", &[]), ("Design", &["Lin", "Mia"])]);
+        let shares = department_percentages(&sample);
+        let expected = [("Design", 40.0), ("Empty", 0.0), ("Engineering", 40.0), ("Sales", 20.0)];
+        assert_eq!(shares.len(), expected.len());
+        for ((department, share), (expected_department, expected_share)) in shares.iter().zip(expected) {
+            assert_eq!(department, expected_department);
+            assert!((share - expected_share).abs() < 1e-9);
+        }
+        assert!(department_percentages(&HashMap::new()).is_empty());
+    }
+
+    // 练习17
+    #[test]
+    fn parse_script_reports_every_bad_line() {
+        // 行号和原文件一致，注释和空行也算行数
+        let script = "# 先添加两个人\nAdd Sally to Engineering\n\nAdd Bob Sales\nList All\nFly away\n";
+        let errors = parse_script(script).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(errors[0], (4, CommandError::BadArguments { .. })));
+        assert!(matches!(&errors[1], (6, CommandError::UnknownCommand { word, .. }) if word == "Fly"));
+
+        let (good, bad) = parse_lines(script);
+        assert_eq!((good.len(), bad.len()), (2, 2));
+        assert_eq!(parse_script("Run other.txt"), Err(vec![(1, CommandError::NestedRun)]));
+    }
+
+    #[test]
+    fn script_commands_run_through_execute() {
+        let mut company = Company::new();
+        let mut lang = Lang::En;
+        let mut journal = undo::Journal::new();
+        for command in parse_script("Add Sally to Engineering\nList Engineering").unwrap() {
+            let flow = execute(command, &mut company, &mut journal, &mut lang, &mut LineInput::new(io::empty(), DEFAULT_MAX_LINE_BYTES));
+            assert_eq!(flow, ControlFlow::Continue(()));
+        }
+        assert_eq!(company.list_department("Engineering"), Some(vec!["Sally".to_string()]));
+    }
+
+    // 练习18
+    fn query_staff() -> HashMap<String, Vec<String>> {
+        staff(&[("Engineering", &["Sally", "Alexander", "Bo"]), ("Sales", &["Amir", "Sam"]), ("Design", &["Samantha"])])
+    }
+
+    fn query_names(results: &[EmployeeRef]) -> Vec<String> {
+        results.iter().map(|e| e.name.to_string()).collect()
+    }
+
+    #[test]
+    fn query_filters_sorts_and_limits() {
+        let staff = query_staff();
+        // 工程部和销售部里名字最长的两个人
+        let longest = query(&staff).departments(&["engineering", "Sales"]).sort_by(QuerySort::NameLenDesc).limit(2).collect();
+        assert_eq!(query_names(&longest), vec!["Alexander", "Sally"]);
+        // "Amir" 是大写的 A，不算
+        let with_a = query(&staff).name_contains("a").sort_by(QuerySort::NameAsc).collect();
+        assert_eq!(query_names(&with_a), vec!["Alexander", "Sally", "Sam", "Samantha"]);
+        let sa = query(&staff).name_matches_glob("Sa*").sort_by(QuerySort::DeptThenName).collect();
+        assert_eq!(
+            sa,
+            vec![
+                EmployeeRef { department: "Design", name: "Samantha" },
+                EmployeeRef { department: "Engineering", name: "Sally" },
+                EmployeeRef { department: "Sales", name: "Sam" },
+            ]
+        );
+        assert_eq!(query(&staff).limit(0).collect().len(), 0);
+        assert_eq!(query(&staff).limit(100).collect().len(), 6);
+    }
+
+    #[test]
+    fn glob_match_supports_star_and_question_mark() {
+        assert!(glob_match("S?m*", "Samantha"));
+        assert!(!glob_match("*x", "Sam"));
+        assert!(glob_match("*", ""));
+    }
+
+    #[test]
+    fn query_args_are_parsed_into_a_spec() {
+        let spec = parse_query_args(&["dept=Sales,Design", "contains=Sam", "limit=1"]).unwrap();
+        assert_eq!(spec.departments, vec!["Sales", "Design"]);
+        assert_eq!(spec.contains, Some("Sam".to_string()));
+        assert_eq!((spec.glob, spec.sort, spec.limit), (None, None, Some(1)));
+        assert_eq!(parse_query_args(&["limit=many"]), None);
+    }
+
+    // 练习19
+    #[test]
+    fn crc32_matches_the_standard_check_value() {
+        assert_eq!(checksum::crc32(b"123456789"), 0xCBF43926);
+    }
+
+    #[test]
+    fn checksum_trailer_detects_corruption_and_truncation() {
+        let body = "Engineering,Sally\nSales,Amir\n";
+        let saved = checksum::add_trailer(body);
+        assert_eq!(checksum::check_trailer(&saved), Ok((body, checksum::TrailerStatus::Verified)));
+
+        // 中间的一个字节被改掉了
+        let corrupted = saved.replacen("Sally", "Sblly", 1);
+        assert!(matches!(checksum::check_trailer(&corrupted), Err(checksum::TrailerError::Mismatch(_))));
+        // 开头有 HEADER 却丢了校验行，或者校验行只剩一半
+        assert_eq!(checksum::check_trailer(&saved[..26]), Err(checksum::TrailerError::Truncated));
+        assert_eq!(checksum::check_trailer(&saved[..saved.len() - 3]), Err(checksum::TrailerError::Truncated));
+        // 旧文件本来就没有校验行
+        assert_eq!(checksum::check_trailer("Sales,Amir\n"), Ok(("Sales,Amir\n", checksum::TrailerStatus::Missing)));
+
+        assert_eq!(checksum::strip_trailer_ignoring_checksum(&corrupted), ("Engineering,Sblly\nSales,Amir\n", true));
+        assert_eq!(checksum::strip_trailer_ignoring_checksum(&saved[..saved.len() - 3]), (body, true));
+    }
+
+    // 练习20
+    #[test]
+    fn has_unique_chars_cases() {
+        assert!(has_unique_chars("abcdef"));
+        assert!(!has_unique_chars("hello"));
+        assert!(has_unique_chars(""));
+    }
+
+    // 练习21
+    #[test]
+    fn normalize_dept_path_drops_empty_segments() {
+        assert_eq!(normalize_dept_path("/Engineering//Platform/"), Some("Engineering/Platform".to_string()));
+        assert_eq!(normalize_dept_path("//"), None);
+    }
+
+    #[test]
+    fn nested_departments_round_trip_through_the_tree() {
+        let company = run_lines(&[
+            "Add Ann to Engineering/Platform/Infra",
+            "Add Bob to Engineering",
+            "Add Cy to Engineering/Web",
+            "Add Dee to Sales",
+        ]);
+        let org = company.departments();
+        // 中间节点 "Engineering/Platform" 被自动创建了
+        assert_eq!(org.get("Engineering/Platform"), Some(&vec![]));
+        assert_eq!(from_tree(&to_tree(org)), *org);
+        assert_eq!(descendant_departments(org, "engineering/platform"), vec!["Engineering/Platform/Infra"]);
+    }
+
+    // 练习22
+    #[test]
+    fn estimate_bytes_counts_capacity() {
+        let mut ten = String::with_capacity(10);
+        ten.push_str("abc");
+        // String 本身 24 字节 + 容量 10
+        assert_eq!(ten.estimate_bytes(), 34);
+        let mut numbers: Vec<u32> = Vec::with_capacity(4);
+        numbers.push(1);
+        numbers.push(2);
+        // Vec 本身 24 + 两个元素 8 + 两个空位 8
+        assert_eq!(numbers.estimate_bytes(), 40);
+        // 空的 HashMap 还没有分配任何桶
+        assert_eq!(HashMap::<String, Vec<String>>::new().estimate_bytes(), 48);
+    }
+
+    #[test]
+    fn estimate_bytes_only_grows() {
+        let mut growing: HashMap<String, Vec<String>> = HashMap::new();
+        let mut last = growing.estimate_bytes();
+        for i in 0..50 {
+            growing.entry(format!("Dept{}", i % 7)).or_default().push(format!("Employee{}", i));
+            let now = growing.estimate_bytes();
+            assert!(now >= last);
+            last = now;
+        }
+        let parts: usize = growing.iter().map(|(k, v)| k.estimate_bytes() + v.estimate_bytes()).sum();
+        assert!(last >= parts);
+    }
+
+    #[test]
+    fn format_bytes_picks_a_unit() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MiB");
+        assert_eq!(company_stats(&staff(&[("Sales", &["Amir"]), ("Empty", &[])])).0, 2);
+    }
+
+    // 练习23
+    #[test]
+    fn jaccard_similarity() {
+        assert_eq!(jaccard(&[1, 2, 3], &[3, 2, 1]), 1.0);
+        assert_eq!(jaccard(&["a", "b"], &["c", "d"]), 0.0);
+        // 交集 {3, 4}，并集 {1, 2, 3, 4, 5}
+        assert_eq!(jaccard(&[1, 2, 3, 4], &[3, 4, 5]), 0.4);
+        // 两个空集合看作完全相同
+        assert_eq!(jaccard::<i32>(&[], &[]), 1.0);
+    }
+
+    // 练习24
+    #[test]
+    fn invert_multimap_lists_every_department_of_an_employee() {
+        let teams = staff(&[("Sales", &["Amir", "Sally"]), ("Engineering", &["Sally", "Bob"])]);
+        let by_employee = invert_multimap(&teams);
+        assert_eq!(by_employee["Sally"], vec!["Engineering", "Sales"]);
+        assert_eq!(by_employee["Amir"], vec!["Sales"]);
+        let cross: Vec<&String> = by_employee.iter().filter(|(_, depts)| depts.len() > 1).map(|(name, _)| name).collect();
+        assert_eq!(cross, vec!["Sally"]);
+    }
+
+    // 练习25
+    #[test]
+    fn tally_top_keeps_first_seen_order_on_ties() {
+        let mut tally = Tally::new();
+        for word in "the cat saw the dog and the dog saw the cat run".split_whitespace() {
+            tally.record(word);
+        }
+        assert_eq!(tally.count(&"the"), 4);
+        assert_eq!(tally.count(&"bird"), 0);
+        assert_eq!(tally.top(3), vec![(&"the", 4), (&"cat", 2), (&"saw", 2)]);
+        assert_eq!(tally.top(100).len(), 6);
+    }
+
+    // 练习26
+    #[test]
+    fn dice_coefficient_compares_bigrams() {
+        assert_eq!(dice_coefficient("night", "night"), 1.0);
+        assert_eq!(dice_coefficient("abc", "xyz"), 0.0);
+        // 只有 "ht" 相同：2 * 1 / (4 + 4)
+        assert_eq!(dice_coefficient("night", "nacht"), 0.25);
+        // 不足两个字符时只比较是否相等
+        assert_eq!(dice_coefficient("a", "a"), 1.0);
+        assert_eq!(dice_coefficient("a", "ab"), 0.0);
+    }
+
+    // 练习27
+    fn numbered_lines() -> Vec<String> {
+        (1..=6).map(|i| format!("line {}", i)).collect()
+    }
+
+    #[test]
+    fn paginate_prompts_between_pages_only() {
+        let mut screen = Vec::new();
+        let outcome = paginate(&numbered_lines(), 3, &mut screen, &mut LineInput::new(io::Cursor::new("\n"), 80)).unwrap();
+        assert_eq!(outcome, PagerOutcome::Completed(6));
+        // 最后一页后面没有提示
+        assert_eq!(String::from_utf8_lossy(&screen).matches(PAGER_PROMPT).count(), 1);
+        // 一页放得下，不提示
+        let outcome = paginate(&numbered_lines(), 10, &mut Vec::new(), &mut LineInput::new(io::Cursor::new(""), 80)).unwrap();
+        assert_eq!(outcome, PagerOutcome::Completed(6));
+    }
+
+    #[test]
+    fn paginate_quits_or_shows_all_on_request() {
+        let mut input = LineInput::new(io::Cursor::new("\nq\n"), 80);
+        assert_eq!(paginate(&numbered_lines(), 2, &mut Vec::new(), &mut input).unwrap(), PagerOutcome::Quit(4));
+        let mut input = LineInput::new(io::Cursor::new("a\n"), 80);
+        assert_eq!(paginate(&numbered_lines(), 2, &mut Vec::new(), &mut input).unwrap(), PagerOutcome::Completed(6));
+        // 提示时输入已经结束
+        let mut input = LineInput::new(io::Cursor::new(""), 80);
+        assert_eq!(paginate(&numbered_lines(), 4, &mut Vec::new(), &mut input).unwrap(), PagerOutcome::Quit(4));
+    }
+
+    // 练习28
+    #[test]
+    fn parse_scaled_count_accepts_suffixes() {
+        assert_eq!(parse_scaled_count("250"), Ok(250));
+        assert_eq!(parse_scaled_count("10k"), Ok(10_000));
+        assert_eq!(parse_scaled_count("10K"), Ok(10_000));
+        assert_eq!(parse_scaled_count("1.5m"), Ok(1_500_000));
+        assert_eq!(parse_scaled_count("1.5M"), Ok(1_500_000));
+        assert_eq!(parse_scaled_count("2b"), Ok(2_000_000_000));
+        assert_eq!(parse_scaled_count("1.23k"), Ok(1230));
+        // 末尾的 0 不影响
+        assert_eq!(parse_scaled_count("1.2300k"), Ok(1230));
+        // Query 的 limit 也能用单位
+        assert_eq!(parse_query_args(&["limit=1k"]).map(|spec| spec.limit), Some(Some(1000)));
+    }
+
+    #[test]
+    fn parse_scaled_count_rejects_bad_input() {
+        assert!(matches!(parse_scaled_count("1.2345k"), Err(ScaledParseError::NotWhole(_))));
+        assert!(matches!(parse_scaled_count("1.5"), Err(ScaledParseError::NotWhole(_))));
+        assert_eq!(parse_scaled_count("20000000000b"), Err(ScaledParseError::Overflow));
+        assert_eq!(parse_scaled_count("-5"), Err(ScaledParseError::Negative));
+        assert_eq!(parse_scaled_count("7x"), Err(ScaledParseError::UnknownSuffix('x')));
+        assert_eq!(parse_scaled_count(""), Err(ScaledParseError::Empty));
+        assert!(matches!(parse_scaled_count("1.2.3k"), Err(ScaledParseError::InvalidNumber(_))));
+    }
+
+    // 练习31
+    #[test]
+    fn remove_employee_deletes_emptied_departments_only() {
+        let mut office = staff(&[("Sales", &["Amir", "Sally"]), ("Eng", &[]), ("Eng/Web", &["Bob"])]);
+        assert_eq!(remove_employee(&mut office, "Amir", "Sales"), RemoveOutcome::Removed { department_deleted: false });
+        assert_eq!(remove_employee(&mut office, "Amir", "Sales"), RemoveOutcome::EmployeeNotFound);
+        assert_eq!(remove_employee(&mut office, "Sally", "Sales"), RemoveOutcome::Removed { department_deleted: true });
+        assert_eq!(remove_employee(&mut office, "Sally", "Sales"), RemoveOutcome::DepartmentNotFound);
+        assert_eq!(remove_employee(&mut office, "Bob", "Eng/Web"), RemoveOutcome::Removed { department_deleted: true });
+        // 空的上级部门不是 Remove 删掉的，保持原样
+        assert!(office.contains_key("Eng"));
+    }
+
+    // 练习32
+    #[test]
+    fn transfer_employee_creates_parents_and_rejects_bad_moves() {
+        let mut moves = staff(&[("Sales", &["Amir", "Sally"])]);
+        assert_eq!(transfer_employee(&mut moves, "Sally", "Sales", "Eng/Web"), Ok(()));
+        assert_eq!(moves["Sales"], vec!["Amir"]);
+        assert_eq!(moves["Eng/Web"], vec!["Sally"]);
+        // 和 Add 一样补上了上级部门
+        assert!(moves.contains_key("Eng"));
+
+        assert_eq!(transfer_employee(&mut moves, "Sally", "Sales", "Eng"), Err(TransferError::EmployeeNotFound));
+        assert_eq!(transfer_employee(&mut moves, "Amir", "Design", "Eng"), Err(TransferError::SourceNotFound));
+        assert_eq!(transfer_employee(&mut moves, "Amir", "Sales", "Sales"), Err(TransferError::SameDepartment));
+        moves.get_mut("Eng/Web").unwrap().push("Amir".to_string());
+        assert_eq!(transfer_employee(&mut moves, "Amir", "Sales", "Eng/Web"), Err(TransferError::AlreadyInDestination));
+        // 出错的调动既没有移走员工，也没有创建任何部门
+        assert_eq!(moves["Sales"], vec!["Amir"]);
+        assert_eq!(moves.len(), 3);
+    }
+
+    // 练习34
+    #[test]
+    fn departments_csv_round_trips_and_reports_bad_lines() {
+        let to_save = staff(&[("Sales", &["Amir", "Sally"]), ("Eng/Web", &["Bob"]), ("R,D", &["Eve"]), ("Empty", &[])]);
+        let csv = departments_to_csv(&to_save);
+        assert_eq!(csv, "Empty,\nEng/Web,Bob\n\"R,D\",Eve\nSales,Amir\nSales,Sally\n");
+
+        let (restored, bad_lines) = departments_from_csv(&format!("{}no comma here\n", csv)).unwrap();
+        let mut expected = to_save.clone();
+        expected.insert("Eng".to_string(), vec![]);
+        assert_eq!(restored, expected);
+        assert_eq!(bad_lines, vec![6]);
+        // 引号没有闭合
+        assert!(departments_from_csv("Sales,\"Amir\n").is_err());
+    }
+
+    // 练习36
+    #[test]
+    fn department_counts_sort_by_size_then_name() {
+        let counted = staff(&[("Sales", &["Amir", "Sally"]), ("Design", &["Bo"]), ("Audit", &["Cy"])]);
+        let counts = department_counts(&counted);
+        assert_eq!(counts, vec![("Sales".to_string(), 2), ("Audit".to_string(), 1), ("Design".to_string(), 1)]);
+        assert_eq!(counts.iter().map(|(_, count)| count).sum::<usize>(), 4);
+    }
+
+    // 练习38
+    #[test]
+    fn find_employee_matches_exactly_and_suggests_close_names() {
+        let find_map = staff(&[("Sales", &["Sally", "Amir"]), ("Design", &["Sally"])]);
+        assert_eq!(find_employee(&find_map, "Sally"), vec!["Design", "Sales"]);
+        assert!(find_employee(&find_map, "Sal").is_empty());
+        assert_eq!(close_employee_matches(&find_map, "amr"), vec![("Amir", "Sales")]);
+        assert!(close_employee_matches(&find_map, "Zed").is_empty());
+    }
+
+    // 练习39
+    #[test]
+    fn code_lengths_give_rare_chars_longer_codes() {
+        // a 8 次，b 4 次，c 2 次，d 1 次
+        let mut table: Vec<(char, usize)> = code_lengths("aaaaaaaabbbbccd").into_iter().collect();
+        table.sort();
+        assert_eq!(table, vec![('a', 1), ('b', 2), ('c', 3), ('d', 3)]);
+        assert_eq!(code_lengths("zzz"), HashMap::from([('z', 1)]));
+        assert!(code_lengths("").is_empty());
+        let skewed = code_lengths("the quick brown fox jumps over the lazy dog eeeeeeee");
+        assert!(skewed[&'e'] < skewed[&'z']);
+    }
+
+    // 练习41
+    #[test]
+    fn top_n_words_breaks_ties_alphabetically() {
+        let story = "the cat and the dog and the bird";
+        let top = |text, n| -> Vec<(String, usize)> { top_n_words(text, n) };
+        assert_eq!(top(story, 2), vec![("the".to_string(), 3), ("and".to_string(), 2)]);
+        // bird、cat、dog 都只出现一次，按字母顺序排，所以第 3 个是 bird
+        assert_eq!(top(story, 3)[2], ("bird".to_string(), 1));
+        assert_eq!(top("b a c b a c", 2), vec![("a".to_string(), 2), ("b".to_string(), 2)]);
+        assert_eq!(top(story, 100).len(), 5);
+        assert!(top(story, 0).is_empty());
+        assert!(top("", 3).is_empty());
+    }
+
+    // 练习42
+    #[test]
+    fn running_distinct_counts_new_values() {
+        assert_eq!(running_distinct(&[1, 2, 1, 3]), vec![1, 2, 2, 3]);
+        assert_eq!(running_distinct(&["a", "a", "a"]), vec![1, 1, 1]);
+        assert!(running_distinct::<i32>(&[]).is_empty());
+    }
+
+    // 练习44
+    #[test]
+    fn every_command_example_is_valid() {
+        let invalid: Vec<&str> = COMMANDS
+            .iter()
+            .flat_map(|spec| spec.examples.iter().copied())
+            .filter(|example| !is_valid_command(example))
+            .collect();
+        assert!(invalid.is_empty(), "{:?}", invalid);
+    }
+
+    #[test]
+    fn is_valid_command_cases() {
+        // 首尾的空白会被忽略
+        assert!(is_valid_command("  List All  "));
+        assert!(!is_valid_command("Add Sally Engineering"));
+        assert!(!is_valid_command("Ad Sally to Engineering"));
+        assert!(!is_valid_command("Lang fr"));
+        assert!(!is_valid_command(""));
+    }
+
+    // 练习45
+    #[test]
+    fn merge_departments_skips_blank_and_duplicate_names() {
+        let mut merged_into = staff(&[("Sales", &["Amir"])]);
+        let incoming = staff(&[("Sales", &["Amir", "Bo", " "]), ("Eng/Web", &["Cy"]), ("/", &["Dan"])]);
+        // 加入 Bo 和 Cy，跳过空名字和 Dan，Amir 已经存在
+        assert_eq!(merge_departments(&mut merged_into, incoming), (2, 2, 1, 1));
+        // 和 Add 一样补上了上级部门
+        assert!(merged_into.contains_key("Eng"));
+    }
+
+    // 练习46
+    #[test]
+    fn turnover_ignores_transfers() {
+        let january = staff(&[("Sales", &["Amir", "Sally"]), ("Design", &["Bo"])]);
+        let february = staff(&[("Sales", &["Amir", "Zoe"]), ("Engineering", &["Bo", "Cy"])]);
+        // Bo 从 Design 调到了 Engineering，不算离开
+        assert_eq!(turnover(&january, &february), (vec!["Cy".to_string(), "Zoe".to_string()], vec!["Sally".to_string()]));
+        assert_eq!(turnover(&january, &january), (vec![], vec![]));
+    }
+}
+
+/*
+ * =====================================================================================
+ * 练习挑战 (Challenge Section)
+ * =====================================================================================
+ *
+ * 1. 公司部门管理:
+ *    使用 `HashMap` 和 `Vec`，创建一个文本界面来允许用户向公司的某个部门添加员工。
+ *    例如，用户可以输入 "Add Sally to Engineering" 或 "Add Amir to Sales"。
+ *    然后，用户应该能够输入一个部门名称，程序会打印出该部门所有员工的列表，
+ *    并按字母顺序排序。
+ *
+ * 2. (来自 `11_collections_vector.rs` 的挑战) 使用 HashMap 计算众数:
+ *    现在你已经学习了 `HashMap`，请重新完成之前 `Vec` 那一课的挑战：
+ *    给定一个整数 `Vec`，编写一个函数返回众数（出现次数最多的值）。
+ *    使用 `HashMap` 来记录每个数字出现的次数，会使这个问题变得简单很多。
+ *
+ * 3. 最常见的单词长度:
+ *    编写函数 `most_common_word_length(text: &str) -> Option<usize>`，
+ *    用一个 `HashMap<usize, usize>` 统计每种单词长度（按字符数）出现的次数，返回出现次数最多的长度。
+ *    如果文本中没有任何单词，返回 `None`。
+ *
+ * 4. 部门平均名字长度:
+ *    编写函数 `avg_name_length(map: &HashMap<String, Vec<String>>) -> HashMap<String, f64>`，
+ *    返回每个部门员工名字的平均长度（按字符数），没有员工的部门直接跳过。
+ *
+ * 5. 从 CSV 导入员工:
+ *    HR 导出的 CSV 表头很少和我们期望的一致。定义
+ *    `ColumnMapping { name_column: String, department_column: String, ignore_unknown: bool }`，
+ *    编写 `import_with_mapping(csv_text: &str, mapping: &ColumnMapping) -> Result<ImportResult, ImportError>`，
+ *    统计导入的行数、因名字或部门为空而跳过的行数、合并掉的重复记录数。
+ *    然后添加一个 `Import <file>` 命令：先只读表头并展示所有列，询问用户哪一列是名字、哪一列是部门，
+ *    再执行导入并打印汇总（包括新建了几个部门）。
+ *
+ * 6. 人数最多的部门:
+ *    编写函数 `largest_department(map: &HashMap<String, Vec<String>>) -> Option<(String, usize)>`，
+ *    返回员工最多的部门及其人数；人数相同时按部门名的字母顺序取第一个，空表返回 `None`。
+ *
+ *
+ * 7. 分块处理与进度回调:
+ *    编写泛型函数 `process_chunked(items, chunk_size, work, on_progress)`：
+ *    把 `items` 按 `chunk_size` 分块交给闭包 `work` 处理，每块结束后用 `Progress`（已完成数、总数、耗时）
+ *    调用 `on_progress`；如果它返回 `ControlFlow::Break(())`，就在当前块结束后停止，
+ *    并返回 `Cancelled { completed }`。`chunk_size` 为 0 时要报错，`work` 出错时要把已完成的数量一起返回。
+ *    最后让 `Import` 命令用它来分块合并数据并打印进度。
+ *
+ * 8. 全公司员工名单:
+ *    编写函数 `all_employees_sorted(map: &HashMap<String, Vec<String>>) -> Vec<String>`，
+ *    返回所有部门中的员工，去重并按字母排序。再添加一个 `Roster` 命令来打印这个名单。
+ *
+ * 9. 不区分大小写的多值映射:
+ *    编写泛型结构体 `CiMultiMap<V>`：查找时键不区分大小写，显示时保留第一次插入时的写法。
+ *    实现 `insert`、`get`、`keys_display`（按折叠后的键排序）、`remove_value`（按条件删除值）、
+ *    `len`、`is_empty`、`total_values`。然后用它让 `List` 命令不再区分部门名的大小写，
+ *    并用它为一段文本建立“单词 -> 行号”的索引。
+ *
+ * 10. 字符串的香农熵:
+ *    编写函数 `shannon_entropy(s: &str) -> f64`，用 `HashMap<char, usize>` 统计字符频率，
+ *    按公式 H = -Σ p·log2(p) 计算熵（单位：比特）。空字符串或只有一种字符时返回 0.0。
+ *    例如 "abab" 的熵是 1.0。
+ *
+ * 11. 中英文消息目录:
+ *    部门管理程序里中英文消息混在一起。写一个 `messages` 模块：`Lang` 枚举（En、Zh），
+ *    `MsgKey` 枚举表示每一条消息（需要的参数放在变体里，例如 `AddedOk { name, dept }`），
+ *    `Catalog` 保存每种语言的模板，`render(lang, key)` 负责把参数填进模板。
+ *    某种语言缺少翻译时退回英文，并在前面加上 "[en] " 标记。
+ *    然后让循环里所有的输出都经过 `render`，并添加 `Lang <en|zh>` 命令在运行时切换语言。
+ *
+ * 12. 自定义迭代器适配器:
+ *    实现两个迭代器结构体：`DedupConsecutive<I>`（相邻的重复元素只保留第一个）和
+ *    `Enumerate1<I>`（产出从 1 开始的 `(序号, 元素)`），都要正确地转发 `size_hint`。
+ *    再写一个扩展 trait，让任何迭代器都能调用 `.dedup_consecutive()` 和 `.enumerate1()`，
+ *    并用它们改写员工列表的去重和导入时的列编号输出。
+ *
+ * 13. 单词阶梯:
+ *    编写函数 `word_ladder(start, end, dictionary: &HashSet<String>) -> Option<Vec<String>>`，
+ *    找出从 `start` 到 `end` 的最短单词链，相邻两个单词只差一个字母，并且都在词典里。
+ *    用 `VecDeque` 做广度优先搜索，用 `HashMap` 记录每个单词的“上一个单词”来还原路径；
+ *    生成邻居时把每一位依次换成 'a'..='z'，再查 `HashSet`，而不是每一步都扫描整个词典。
+ *    再写一个辅助函数 `differs_by_one(a, b)`，并用一个小词典演示 "cold" -> "warm"。
+ *
+ * 14. 按频率排序的字母表:
+ *    编写函数 `alphabet_by_frequency(text: &str) -> Vec<char>`，返回文本中出现过的所有不同字符，
+ *    按出现次数从多到少排序，次数相同的按字符顺序排列。简单的压缩算法常用这样的字母表来分配短编码。
+ *    例如 "hello world" 返回 ['l', 'o', ' ', 'd', 'e', 'h', 'r', 'w']。
+ *
+ * 15. 导出 HTML 报表:
+ *    写一个 `html` 模块：`escape` 转义 `& < > " '` 五个字符，`HtmlDoc` 提供 `heading`、`paragraph`、
+ *    `table` 和 `render`（生成带内嵌 CSS 的完整文档），所有用户数据都要经过 `escape`。
+ *    然后编写 `export_html(departments, path)` 把部门报表写成 HTML 表格（先写临时文件再改名），
+ *    并添加 `Export html <file>` 命令。
+ *
+ * 16. 部门人数占比:
+ *    编写函数 `department_percentages(map: &HashMap<String, Vec<String>>) -> Vec<(String, f64)>`，
+ *    返回每个部门人数占全公司总人数的百分比，按部门名排序。
+ *    全公司没有员工时返回空的 `Vec`，注意不要除以 0。
+ *
+ * 17. 先解析、后执行的脚本:
+ *    定义 `Command` 枚举和 `parse_command(line) -> Result<Command, CommandError>`，
+ *    让交互循环先解析、再用 `execute` 执行。然后编写
+ *    `parse_script(text: &str) -> Result<Vec<Command>, Vec<(usize, CommandError)>>`：
+ *    跳过空行和 `#` 注释，收集所有出错的行号和原因。再添加 `Run <file>` 命令：
+ *    脚本全部解析成功才开始执行；加上 `--lenient` 时跳过错误的行，最后报告跳过了哪些行。
+ *
+ * 18. 链式查询:
+ *    编写查询构建器 `CompanyQuery<'a>`，用 `query(&departments)` 创建，支持链式调用
+ *    `departments(&[&str])`、`name_contains`、`name_matches_glob`（自己实现 `*` 和 `?` 通配符）、
+ *    `sort_by(QuerySort)` 和 `limit`，最后用 `collect()` 得到 `Vec<EmployeeRef<'a>>`。
+ *    结果只借用原数据里的字符串，不做克隆；所有的工作都推迟到 `collect()` 时才做。
+ *    再添加 `Query dept=<a,b> contains=<text> limit=<n>` 命令。
+ *
+ * 19. CRC32 校验和:
+ *    不借助外部库，用查表法实现 `crc32(data: &[u8]) -> u32`（"123456789" 的结果应该是 0xCBF43926）。
+ *    再编写 `add_trailer` 在文本末尾追加一行 `#crc32=XXXXXXXX`，以及 `check_trailer` 在读取时验证它：
+ *    校验和不一致时返回 `ChecksumMismatch { expected, actual }`，没有校验行的旧文件照常读取但给出提示。
+ *
+ * 20. 字符是否各不相同:
+ *    编写函数 `has_unique_chars(s: &str) -> bool`，用 `HashSet<char>` 判断字符串中的每个字符是否都只出现一次。
+ *    例如 "abcdef" 返回 true，"hello" 返回 false，空字符串返回 true。
+ *
+ * 21. 多级部门:
+ *    允许部门写成 "Engineering/Platform/Infra" 这样的路径。编写 `normalize_dept_path`
+ *    去掉多余的斜杠；`Add` 到新的多级路径时自动创建中间的部门；`List` 一个上级部门时，
+ *    按子路径分组列出所有下级部门的员工。再定义 `DeptNode` 树，实现 `to_tree`、`from_tree`
+ *    和带缩进的 `render`，让 `List All` 按层级显示。不带 '/' 的部门名要和原来一样工作。
+ *
+ * 22. 估计内存占用:
+ *    定义 trait `EstimateSize { fn estimate_bytes(&self) -> usize; }`，为整数类型、`String`、
+ *    `Vec<T>` 和 `HashMap<K, V>` 实现它（`HashMap` 的桶开销可以粗略估计，但要写清楚是怎么估计的）。
+ *    估计值不需要精确，但数据变多时不能变小。再添加 `Stats` 命令，打印部门数、员工数、
+ *    平均名字长度和估计的内存大小（例如 "1.5 KiB"）。
+ *
+ * 23. Jaccard 相似度:
+ *    编写泛型函数 `jaccard<T: Eq + Hash + Clone>(a: &[T], b: &[T]) -> f64`，
+ *    用 `HashSet` 计算两组元素交集大小除以并集大小。两个都为空时返回 1.0。
+ *    例如 [1, 2, 3, 4] 和 [3, 4, 5] 的相似度是 2 / 5 = 0.4。
+ *
+ * 24. 反转多值映射:
+ *    编写 `invert_multimap(map: &HashMap<String, Vec<String>>) -> HashMap<String, Vec<String>>`，
+ *    把“部门 -> 员工列表”反转成“员工 -> 部门列表”，每个列表按字母排序。
+ *    用它找出同时属于两个部门的员工。
+ *
+ * 25. 流式计数器:
+ *    定义 `struct Tally<K: Eq + Hash>`，提供 `record(&mut self, key: K)`、
+ *    `count(&self, key: &K) -> usize` 和 `top(&self, n: usize) -> Vec<(&K, usize)>`，
+ *    `top` 返回出现次数最多的 n 个 key。次数相同时结果也要固定，想想该怎么排序。
+ *
+ * 26. 二元组相似度:
+ *    编写 `dice_coefficient(a: &str, b: &str) -> f64`，计算两个字符串相邻字符对（二元组）的
+ *    Sørensen–Dice 系数：2 * 共同二元组数 / 两边二元组总数。
+ *    字符串少于两个字符时，相等返回 1.0，否则返回 0.0。
+ *
+ * 27. 分页显示:
+ *    部门很多时 `List All` 会一下子滚出屏幕。编写
+ *    `paginate<W: Write, R: BufRead>(lines: &[String], page_size: usize, out: &mut W, input: &mut R) -> io::Result<PagerOutcome>`：
+ *    每打印 `page_size` 行就显示 "-- more (Enter=next, q=quit, a=all) --"，回车显示下一页，
+ *    q 退出，a 显示剩下的全部。输入结束时当作退出；一页放得下时不显示提示。
+ *    然后在 `List All` 中使用它。
+ *
+ * 28. 带单位的数量:
+ *    编写 `parse_scaled_count(s: &str) -> Result<u64, ScaledParseError>`，接受 "250"、"10k"/"10K"（×1000）、
+ *    "1.5m"/"1.5M"（×1000000）和 "2b"（×1000000000）。换算后必须是整数（"1.23k" 可以，"1.2345k" 不行），
+ *    负数、溢出和未知的单位都要返回对应的错误。然后让 `Query` 的 `limit=` 参数也支持这种写法。
+ *
+ * 29. 命令表和 Help 命令:
+ *    用法提示散落在各处的字符串里，很容易和解析器对不上。定义
+ *    `struct CommandSpec { verb, args, summary, examples }` 和 `const COMMANDS: &[CommandSpec]`，
+ *    让 `parse_command` 先在表里查找命令名再解析参数，参数出错时的用法提示也从表里生成。
+ *    再添加 `Help`（列出所有命令和简介）和 `Help Add`（完整用法和例子）。
+ *    输错命令名时（包括 `Help Xyz`），用编辑距离找出最接近的命令名作为建议。
+ *
+ * 30. 限制一行的长度:
+ *    编写 `read_limited_line<R: BufRead>(r: &mut R, max_bytes: usize) -> io::Result<LineOutcome>`，
+ *    `LineOutcome` 是 `Line(String)`、`TooLong { consumed }` 或 `Eof`。超过上限的行要整行读掉并丢弃，
+ *    让下一次读取从新的一行开始，而且不能把整行都存进内存（提示：`fill_buf` 和 `consume`）。
+ *    在主循环中用它代替 `read_line`，上限默认 4096 字节，遇到太长的行打印提示后继续。
+ *
+ * 31. 移除员工:
+ *    添加 `Remove <name> from <department>` 命令：用 `get_mut` 找到部门，再从 `Vec` 中移除这个员工。
+ *    员工不在部门里、部门不存在时分别给出不同的提示；部门移除后变空的话，把整个键删掉，
+ *    这样 `List All` 里就不会出现空部门。
+ *
+ * 32. 调动员工:
+ *    添加 `Transfer <name> from <dept> to <dept>` 命令。先确认来源部门存在并且有这个员工，
+ *    再把名字放进目标部门（不存在就用 `entry().or_insert` 创建），最后才从来源部门移除。
+ *    任何一步出错都不能修改数据；调到同一个部门时什么也不做，并给出提示。
+ *
+ * 33. 保存和读取:
+ *    添加 `Save <path>` 和 `Load <path>` 命令，把 `HashMap<String, Vec<String>>` 保存成
+ *    "Engineering:Sally,Amir" 这样每个部门一行的文本，再读回来。用 `Result` 和 `?` 处理错误，
+ *    格式错误的行要返回带行号的错误而不是 panic。想好读取时是合并还是替换现有数据，并写进帮助文字里。
+ *    保存、读取之后比较两个 `HashMap`，确认数据没有变化。
+ *
+ * 34. 退出时自动保存:
+ *    `Quit` 时把数据写到 `departments.csv`，每个员工一行 "department,name"；
+ *    程序启动时如果有这个文件，就先读回到 `HashMap` 里再进入循环。
+ *    文件不存在时从空数据开始，格式不对的行打印警告后跳过，不要让程序崩溃。
+ *
+ * 35. Move 命令和 Company 类型:
+ *    添加 `Move <name> from <dept_a> to <dept_b>`：从第一个部门移除，再放进第二个部门
+ *    （不存在就用 `entry().or_insert()` 创建）。员工不在来源部门时不能修改任何数据，并打印错误。
+ *    把这个操作写成一个小的 `Company` 包装类型上的方法，而不是直接写在 `main` 里，这样方便单独测试。
+ *
+ * 36. 各部门人数:
+ *    添加 `Count` 命令：打印每个部门的名字和人数，人数多的在前，人数相同时按部门名排序。
+ *    做法是把 `(部门名, vec.len())` 收集到一个 `Vec` 里，再用 `sort_by` 排序。
+ *    `Count <department>` 只打印一个部门的人数，部门不存在时打印提示。
+ *    把计算写成纯函数 `department_counts(&HashMap<String, Vec<String>>) -> Vec<(String, usize)>`，
+ *    和输出格式分开；`Count` 在最后再打印一行总人数。
+ *
+ * 37. 部门改名:
+ *    添加 `Rename <old> to <new>`：从 HashMap 里删掉旧的键，再用新名字插入原来的 `Vec<String>`。
+ *    新名字已经存在时，把两个员工列表合并并去重，然后告诉用户合并进去了多少名员工。
+ *    旧部门不存在时打印提示，数据保持不变。
+ *
+ * 38. 查找员工:
+ *    添加 `Find <name>`：遍历每一对 `(部门, 员工列表)`，打印这个名字出现的所有部门，
+ *    一个都没有时打印 "not found"。名字必须完全相同；没有完全相同的名字时，顺便列出相近的名字。
+ *
+ * 39. 哈夫曼编码长度:
+ *    编写 `code_lengths(text: &str) -> HashMap<char, usize>`：用 HashMap 统计每个字符出现的次数，
+ *    用优先队列（例如 `BinaryHeap`）每次合并次数最少的两棵树，建出哈夫曼树，
+ *    然后返回每个字符的编码长度（叶子的深度）。出现得越少的字符，编码应该越长。
+ *
+ * 40. 单词计数函数:
+ *    把“基于旧值来更新值”那段单词计数的代码从 `main` 里提出来，写成 `count_words(text: &str) -> HashMap<String, usize>`，
+ *    按空白切分并统计次数。再写一个忽略大小写的版本，让 "Hello" 和 "hello" 算在一起。
+ *    检查空字符串、重复的单词和大小写混合的情况。
+ *    然后让 `count_words` 在计数前用 `trim_matches` 和 `char::is_ascii_punctuation` 去掉每个单词首尾的标点，
+ *    这样 "world." 和 "world" 算同一个单词，但 "don't" 中间的撇号要保留。
+ *
+ * 41. 最常见的单词:
+ *    在 `count_words` 的基础上编写 `top_n_words(text: &str, n: usize) -> Vec<(String, usize)>`，
+ *    返回出现次数最多的 `n` 个单词，次数从多到少，次数相同时按字母顺序。
+ *    `n` 比不同单词的个数还大时返回全部单词。
+ *
+ * 42. 不同值个数的变化:
+ *    编写 `running_distinct<T: Eq + Hash + Clone>(slice: &[T]) -> Vec<usize>`，对每个位置返回
+ *    从开头到这个位置（包含）一共出现过多少个不同的值。用一个不断变大的 `HashSet` 实现。
+ *    例如 `[1, 2, 1, 3]` 返回 `[1, 2, 2, 3]`。
+ *
+ * 43. 导出 CSV:
+ *    添加 `Export <path>` 命令，写出一个 CSV 文件：表头是 `department,employee`，每个员工一行，
+ *    先按部门再按名字排序。名字或部门里有逗号或双引号时要按 RFC 4180 加引号和转义。
+ *    用 `std::fs::File` 写文件，函数返回 `Result`，出错时用 `?` 传出去。
+ *    导出到一个临时文件，再把它读回来解析，检查和原来的数据一致。
+ *
+ * 44. 检查命令是否合法:
+ *    编写 `is_valid_command(input: &str) -> bool`，判断一行输入能不能解析成一个有效的 `Command`。
+ *    复用已有的解析函数，不要重新写一遍规则。这样可以在执行脚本之前先检查每一行。
+ *
+ * 45. JSON 导入导出:
+ *    添加 `ExportJson <path>` 和 `ImportJson <path>`，读写 `{"Engineering": ["Amir", "Sally"]}` 这样的 JSON 对象。
+ *    不使用 serde，在一个新模块里自己写输出函数和一个小的递归下降解析器：要能跳过空白、
+ *    处理名字里转义的双引号，出现语法错误时在 `Err` 里给出出错位置的字节偏移。
+ *    含有中文和 emoji 的名字导出再导入后要和原来完全一样。
+ *
+ * 46. 人员变动:
+ *    编写 `turnover(before: &HashMap<String, Vec<String>>, after: &HashMap<String, Vec<String>>) -> (Vec<String>, Vec<String>)`，
+ *    比较两份数据，返回整个公司新加入的员工和离开的员工，两个列表都按名字排序。
+ *    可以用来比较两次保存的文件。
+ *
+ * 47. 撤销:
+ *    添加 `Undo` 命令，撤销最近一次修改数据的操作（Add、Remove、Move、Rename）。用一个 `Vec<Operation>` 记录历史，
+ *    `Operation` 是一个枚举，保存足够撤销这个操作的信息。连续多次 `Undo` 按相反的顺序一步步撤销；
+ *    没有可以撤销的操作时打印友好的提示。历史记录和执行、撤销的逻辑放在一个单独的模块里，不依赖 stdin。
+ *
+ * 48. 整个公司的树形列表:
+ *    编写 `render_org_tree(map: &HashMap<String, Vec<String>>) -> String`，生成固定的缩进列表：
+ *    部门按名字排序，员工也排序，并以 "  - " 缩进列在部门下面。`List All` 用它来生成输出，
+ *    然后用一个小的 HashMap 检查输出的每一行是否和期望的完全相同。
  *
+ * 49. 用 Company 的方法实现命令:
+ *    给 `Company` 添加 `add_employee`、`list_department` 和 `list_all` 方法，分别返回数据
+ *    （`Option<Vec<String>>`、`BTreeMap<String, Vec<String>>`）而不是直接打印。
+ *    命令的执行只负责解析输入、调用这些方法并把结果格式化输出，原来的行为保持不变。
+ *    检查每个方法的结果，包括部门不存在的情况。
  */
\ No newline at end of file
@@ -1,6 +1,74 @@
 // 14_packages_and_modules.rs
 // 核心内容：如何使用mod组织代码，use关键字的用法，以及如何将模块分散到不同文件中。
 
+#[cfg(test)]
+mod tests {
+    use super::restaurant::errors::ServiceError;
+    use super::restaurant::money::Money;
+    use super::restaurant::service::{evening_service, EveningScript};
+
+    // 和 main 里演示的是同一个晚上
+    fn evening() -> EveningScript<'static> {
+        EveningScript {
+            table_capacities: vec![2, 2, 4, 4, 6, 8],
+            reservations: vec![("张三", 1, 2), ("李四", 3, 4), ("王五", 3, 3), ("赵六", 6, 7)],
+            walk_ins: vec![("散客A", 2, 5), ("散客B", 10, 10), ("散客C", 5, 20)],
+            seat_walk_ins_at: 30,
+            orders: vec![
+                (1, vec![("番茄汤", 1200), ("牛排", 4800)]),
+                (3, vec![("沙拉", 900), ("烤鸡", 3600), ("甜点", 1100)]),
+                (5, vec![("火锅", 8800)]),
+            ],
+            payments: vec![(1, 6000), (3, 5000), (3, 5600), (5, 9000)],
+        }
+    }
+
+    #[test]
+    fn evening_service_end_to_end() {
+        let report = evening_service(&evening()).unwrap();
+        // 张三 2 人、李四 4 人、散客C 5 人付了款
+        assert_eq!(report.covers_served, 11);
+        assert_eq!(report.revenue, Money::from_cents(20400));
+        // 散客A 等了 25 分钟，散客C 等了 10 分钟
+        assert_eq!(report.average_wait, 17.5);
+        assert_eq!(report.unseated_parties, vec!["散客B"]);
+        assert_eq!(
+            report.rejected,
+            vec![
+                ServiceError::TableTaken { table: 3 },
+                ServiceError::Underpaid { table: 3, due: Money::from_cents(5600), offered: Money::from_cents(5000) },
+            ]
+        );
+        assert_eq!(
+            report.to_string(),
+            "== 今晚营业报告 ==\n接待客人: 11 位\n营业额: $204.00\n散客平均等待: 17.5 分钟\n未能入座: 散客B\n被拒绝的操作: 2"
+        );
+    }
+
+    #[test]
+    fn each_order_of_a_table_is_paid_separately() {
+        let mut script = evening();
+        // 1 号桌又加了一单，付两次钱，客人只算一次
+        script.orders.push((1, vec![("咖啡", 500)]));
+        script.payments = vec![(1, 6000), (1, 500), (1, 100)];
+        let report = evening_service(&script).unwrap();
+        assert_eq!(report.covers_served, 2);
+        assert_eq!(report.revenue, Money::from_cents(6500));
+        // 两单都付清以后的第三次付款被拒绝
+        assert_eq!(report.rejected, vec![ServiceError::TableTaken { table: 3 }, ServiceError::OrderNotServed(1)]);
+    }
+
+    #[test]
+    fn ordering_at_an_empty_table_stops_the_evening() {
+        let mut script = evening();
+        script.orders.push((4, vec![("面包", 300)]));
+        assert_eq!(evening_service(&script).unwrap_err(), ServiceError::NoPartyAtTable(4));
+        script.orders.push((9, vec![("面包", 300)]));
+        script.orders.remove(3);
+        assert_eq!(evening_service(&script).unwrap_err(), ServiceError::NoSuchTable(9));
+    }
+}
+
 /*
  * =====================================================================================
  * 核心概念讲解 (Comments Section)
@@ -137,6 +205,368 @@ fn order_food() {
 fn main() {
     eat_at_restaurant();
     order_food();
+
+    // 练习3：
+    // 一个完整的晚上：6 张桌子，4 个预订（其中一个和别人订了同一张桌），
+    // 3 组散客排队，三张桌点菜，其中一桌第一次付款不够
+    use restaurant::service::{evening_service, EveningScript};
+    let script = EveningScript {
+        table_capacities: vec![2, 2, 4, 4, 6, 8],
+        reservations: vec![("张三", 1, 2), ("李四", 3, 4), ("王五", 3, 3), ("赵六", 6, 7)],
+        walk_ins: vec![("散客A", 2, 5), ("散客B", 10, 10), ("散客C", 5, 20)],
+        seat_walk_ins_at: 30,
+        orders: vec![
+            (1, vec![("番茄汤", 1200), ("牛排", 4800)]),
+            (3, vec![("沙拉", 900), ("烤鸡", 3600), ("甜点", 1100)]),
+            (5, vec![("火锅", 8800)]),
+        ],
+        payments: vec![(1, 6000), (3, 5000), (3, 5600), (5, 9000)],
+    };
+    match evening_service(&script) {
+        Ok(report) => {
+            println!("{}", report);
+            for e in &report.rejected {
+                println!("  - {}", e);
+            }
+        }
+        Err(e) => println!("营业中断: {}", e),
+    }
+    // 输出：接待客人 11 位，营业额 $204.00，散客平均等待 17.5 分钟，散客B（10 人）没有桌子能坐下；
+    // 被拒绝的是王五的订座和 3 号桌的第一次付款
+}
+
+// 练习3：
+// 把餐厅的一个晚上串起来：订座、排队、点菜、上菜、结账，最后出一份报表。
+// 每个子模块只公开别人需要用的部分，模块之间用 super:: 互相引用
+mod restaurant {
+    pub mod money {
+        use std::fmt;
+
+        // 用“分”保存金额，避免浮点数的舍入误差
+        #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+        pub struct Money {
+            cents: u64,
+        }
+
+        impl Money {
+            pub fn from_cents(cents: u64) -> Money {
+                Money { cents }
+            }
+
+            pub fn cents(&self) -> u64 {
+                self.cents
+            }
+
+            pub fn add(self, other: Money) -> Money {
+                Money { cents: self.cents + other.cents }
+            }
+        }
+
+        impl fmt::Display for Money {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "${}.{:02}", self.cents / 100, self.cents % 100)
+            }
+        }
+    }
+
+    pub mod errors {
+        use super::money::Money;
+        use super::orders::OrderStatus;
+        use std::fmt;
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum ServiceError {
+            NoSuchTable(usize),
+            TableTaken { table: usize },
+            TooManyGuests { table: usize, size: u32, capacity: u32 },
+            NoPartyAtTable(usize),
+            InvalidTransition { table: usize, from: OrderStatus },
+            OrderNotServed(usize),
+            Underpaid { table: usize, due: Money, offered: Money },
+        }
+
+        impl fmt::Display for ServiceError {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    ServiceError::NoSuchTable(t) => write!(f, "没有 {} 号桌", t),
+                    ServiceError::TableTaken { table } => write!(f, "{} 号桌已经被预订", table),
+                    ServiceError::TooManyGuests { table, size, capacity } => {
+                        write!(f, "{} 号桌只能坐 {} 人，来了 {} 人", table, capacity, size)
+                    }
+                    ServiceError::NoPartyAtTable(t) => write!(f, "{} 号桌没有客人", t),
+                    ServiceError::InvalidTransition { table, from } => {
+                        write!(f, "{} 号桌的订单在 {:?} 状态下不能继续推进", table, from)
+                    }
+                    ServiceError::OrderNotServed(t) => write!(f, "{} 号桌还没上菜，不能结账", t),
+                    ServiceError::Underpaid { table, due, offered } => {
+                        write!(f, "{} 号桌应付 {}，只付了 {}", table, due, offered)
+                    }
+                }
+            }
+        }
+    }
+
+    // 桌子和预订
+    pub mod tables {
+        use super::errors::ServiceError;
+
+        pub struct ReservationBook {
+            capacities: Vec<u32>,
+            // occupants[i] 是坐在 i + 1 号桌的客人名字和人数
+            occupants: Vec<Option<(String, u32)>>,
+        }
+
+        impl ReservationBook {
+            // 桌号从 1 开始，capacities[0] 是 1 号桌能坐的人数
+            pub fn new(capacities: &[u32]) -> ReservationBook {
+                ReservationBook { capacities: capacities.to_vec(), occupants: vec![None; capacities.len()] }
+            }
+
+            pub fn book(&mut self, name: &str, table: usize, size: u32) -> Result<(), ServiceError> {
+                let index = self.index(table)?;
+                if self.occupants[index].is_some() {
+                    return Err(ServiceError::TableTaken { table });
+                }
+                let capacity = self.capacities[index];
+                if size > capacity {
+                    return Err(ServiceError::TooManyGuests { table, size, capacity });
+                }
+                self.occupants[index] = Some((name.to_string(), size));
+                Ok(())
+            }
+
+            // 能坐下 size 个人的空桌中最小的那张，尽量把大桌留给人多的客人
+            pub fn smallest_free_table(&self, size: u32) -> Option<usize> {
+                (0..self.capacities.len())
+                    .filter(|&i| self.occupants[i].is_none() && self.capacities[i] >= size)
+                    .min_by_key(|&i| self.capacities[i])
+                    .map(|i| i + 1)
+            }
+
+            pub fn party_at(&self, table: usize) -> Result<&(String, u32), ServiceError> {
+                let index = self.index(table)?;
+                self.occupants[index].as_ref().ok_or(ServiceError::NoPartyAtTable(table))
+            }
+
+            fn index(&self, table: usize) -> Result<usize, ServiceError> {
+                if table == 0 || table > self.capacities.len() {
+                    Err(ServiceError::NoSuchTable(table))
+                } else {
+                    Ok(table - 1)
+                }
+            }
+        }
+    }
+
+    // 没有预订的散客先排队，有合适的空桌再入座
+    pub mod waitlist {
+        use super::tables::ReservationBook;
+        use std::collections::VecDeque;
+
+        pub struct WalkIn {
+            pub name: String,
+            pub size: u32,
+            pub arrived_at: u32,
+        }
+
+        #[derive(Default)]
+        pub struct Waitlist {
+            parties: VecDeque<WalkIn>,
+        }
+
+        impl Waitlist {
+            pub fn join(&mut self, party: WalkIn) {
+                self.parties.push_back(party);
+            }
+
+            // 按到达顺序给每一组找桌子；坐不下的继续排队，不挡住后面人少的客人。
+            // 返回 (名字, 桌号, 等待的分钟数)
+            pub fn seat_all(&mut self, book: &mut ReservationBook, now: u32) -> Vec<(String, usize, u32)> {
+                let mut seated = Vec::new();
+                let mut still_waiting = VecDeque::new();
+                while let Some(party) = self.parties.pop_front() {
+                    match book.smallest_free_table(party.size) {
+                        Some(table) if book.book(&party.name, table, party.size).is_ok() => {
+                            seated.push((party.name, table, now.saturating_sub(party.arrived_at)));
+                        }
+                        _ => still_waiting.push_back(party),
+                    }
+                }
+                self.parties = still_waiting;
+                seated
+            }
+
+            pub fn waiting(&self) -> Vec<String> {
+                self.parties.iter().map(|p| p.name.clone()).collect()
+            }
+        }
+    }
+
+    pub mod orders {
+        use super::errors::ServiceError;
+        use super::money::Money;
+
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum OrderStatus {
+            Placed,
+            Cooking,
+            Served,
+            Paid,
+        }
+
+        pub struct TrackedOrder {
+            pub table: usize,
+            items: Vec<(String, Money)>,
+            status: OrderStatus,
+        }
+
+        impl TrackedOrder {
+            pub fn new(table: usize, items: &[(&str, u64)]) -> TrackedOrder {
+                let items = items.iter().map(|&(name, cents)| (name.to_string(), Money::from_cents(cents))).collect();
+                TrackedOrder { table, items, status: OrderStatus::Placed }
+            }
+
+            pub fn status(&self) -> OrderStatus {
+                self.status
+            }
+
+            pub fn total(&self) -> Money {
+                self.items.iter().fold(Money::default(), |sum, (_, price)| sum.add(*price))
+            }
+
+            // 厨房推进订单：Placed -> Cooking -> Served。结账要走 pay
+            pub fn advance(&mut self) -> Result<OrderStatus, ServiceError> {
+                self.status = match self.status {
+                    OrderStatus::Placed => OrderStatus::Cooking,
+                    OrderStatus::Cooking => OrderStatus::Served,
+                    from => return Err(ServiceError::InvalidTransition { table: self.table, from }),
+                };
+                Ok(self.status)
+            }
+
+            // 付款必须在上菜之后，并且不能少于应付金额；返回找零
+            pub fn pay(&mut self, offered: Money) -> Result<Money, ServiceError> {
+                if self.status != OrderStatus::Served {
+                    return Err(ServiceError::OrderNotServed(self.table));
+                }
+                let due = self.total();
+                if offered < due {
+                    return Err(ServiceError::Underpaid { table: self.table, due, offered });
+                }
+                self.status = OrderStatus::Paid;
+                Ok(Money::from_cents(offered.cents() - due.cents()))
+            }
+        }
+    }
+
+    pub mod service {
+        use super::errors::ServiceError;
+        use super::money::Money;
+        use super::orders::{OrderStatus, TrackedOrder};
+        use super::tables::ReservationBook;
+        use super::waitlist::{WalkIn, Waitlist};
+        use std::fmt;
+
+        // 整个晚上要发生的事情，全部是普通数据，方便完整地控制输入
+        pub struct EveningScript<'a> {
+            pub table_capacities: Vec<u32>,
+            // (名字, 桌号, 人数)
+            pub reservations: Vec<(&'a str, usize, u32)>,
+            // (名字, 人数, 到达时间（分钟）)
+            pub walk_ins: Vec<(&'a str, u32, u32)>,
+            // 什么时候安排排队的客人入座
+            pub seat_walk_ins_at: u32,
+            // (桌号, 菜品和价格（分）)
+            pub orders: Vec<(usize, Vec<(&'a str, u64)>)>,
+            // (桌号, 付款金额（分）)，同一张桌可以付多次，比如第一次钱不够
+            pub payments: Vec<(usize, u64)>,
+        }
+
+        #[derive(Debug)]
+        pub struct NightReport {
+            pub covers_served: u32,
+            pub revenue: Money,
+            pub average_wait: f64,
+            pub unseated_parties: Vec<String>,
+            // 晚上遇到但不影响营业的问题，按发生的顺序排列
+            pub rejected: Vec<ServiceError>,
+        }
+
+        impl fmt::Display for NightReport {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                writeln!(f, "== 今晚营业报告 ==")?;
+                writeln!(f, "接待客人: {} 位", self.covers_served)?;
+                writeln!(f, "营业额: {}", self.revenue)?;
+                writeln!(f, "散客平均等待: {:.1} 分钟", self.average_wait)?;
+                writeln!(f, "未能入座: {}", if self.unseated_parties.is_empty() { "无".to_string() } else { self.unseated_parties.join(", ") })?;
+                write!(f, "被拒绝的操作: {}", self.rejected.len())
+            }
+        }
+
+        // 订座冲突、付款不足这类问题会记录在报表里，营业继续；
+        // 给没有客人的桌子下单这类脚本本身的错误会直接返回 Err
+        pub fn evening_service(script: &EveningScript) -> Result<NightReport, ServiceError> {
+            let mut rejected = Vec::new();
+            let mut book = ReservationBook::new(&script.table_capacities);
+
+            // 1. 预订
+            for &(name, table, size) in &script.reservations {
+                if let Err(e) = book.book(name, table, size) {
+                    rejected.push(e);
+                }
+            }
+
+            // 2. 散客排队，到点后尽量入座
+            let mut waitlist = Waitlist::default();
+            for &(name, size, arrived_at) in &script.walk_ins {
+                waitlist.join(WalkIn { name: name.to_string(), size, arrived_at });
+            }
+            let seated = waitlist.seat_all(&mut book, script.seat_walk_ins_at);
+            let average_wait = if seated.is_empty() {
+                0.0
+            } else {
+                seated.iter().map(|(_, _, wait)| *wait as f64).sum::<f64>() / seated.len() as f64
+            };
+
+            // 3. 点菜，然后厨房把每个订单推进到上菜
+            let mut orders = Vec::new();
+            for (table, items) in &script.orders {
+                book.party_at(*table)?;
+                let mut order = TrackedOrder::new(*table, items);
+                while order.status() != OrderStatus::Served {
+                    order.advance()?;
+                }
+                orders.push(order);
+            }
+
+            // 4. 结账：一张桌可能点了好几单，每次付款给这张桌第一个还没付清的订单；
+            // 都付清了就交给最后一单，让 pay 报告错误
+            for &(table, cents) in &script.payments {
+                let index = orders
+                    .iter()
+                    .position(|order| order.table == table && order.status() != OrderStatus::Paid)
+                    .or_else(|| orders.iter().rposition(|order| order.table == table))
+                    .ok_or(ServiceError::NoPartyAtTable(table))?;
+                if let Err(e) = orders[index].pay(Money::from_cents(cents)) {
+                    rejected.push(e);
+                }
+            }
+
+            // 5. 汇总：只统计已经付款的订单，同一桌的客人只算一次
+            let mut covers_served = 0;
+            let mut revenue = Money::default();
+            let mut counted_tables = Vec::new();
+            for order in orders.iter().filter(|order| order.status() == OrderStatus::Paid) {
+                if !counted_tables.contains(&order.table) {
+                    covers_served += book.party_at(order.table)?.1;
+                    counted_tables.push(order.table);
+                }
+                revenue = revenue.add(order.total());
+            }
+
+            Ok(NightReport { covers_served, revenue, average_wait, unseated_parties: waitlist.waiting(), rejected })
+        }
+    }
 }
 
 /*
@@ -156,6 +586,10 @@ fn main() {
  *    - 你需要在 `src/front_of_house.rs` 中使用 `pub mod hosting;` 来声明它。
  *    - 再次确认程序可以正常工作。这个练习能帮助你理解多层级的文件组织方式。
  * 这个练习需要自己做，主要是关于包管理的，代码无法呈现，详情看https://github.com/EurekaO-O/Rust-learn，第14小节的学习
+ *
+ * 3. 餐厅的一个晚上:
+ *    用 restaurant 模块里的订座、排队、订单和付款，写 evening_service 把一整晚串起来，
+ *    订座冲突和付款不足记在报表里，最后打印 NightReport。
  */
 // 最终的main.rs code(只需要复制然后测试能不能跑通):
 // 练习1&练习2：
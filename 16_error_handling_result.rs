@@ -55,11 +55,17 @@
 // 代码示例 (Code Section)
 // =====================================================================================
 
-use std::fs::File;
-use std::io::{self, Read};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
 use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
 // 练习2：
-fn main() -> Result<(), Box<dyn Error>> {
+// 返回 Reported 而不是 Box<dyn Error>，出错时会用 report_error 打印整条错误链（练习7）
+fn main() -> Result<(), Reported> {
     // // 2. 处理 Result
     // let f = File::open("hello.txt");
 
@@ -95,6 +101,123 @@ fn main() -> Result<(), Box<dyn Error>> {
         Err(e) => println!("  => 失败! 错误信息是: {}", e),
     }
 
+    // 练习3：
+    for input in ["21.5C", "70f", "101.3kPa", "45%", "-273.15C", "100%", "42", "101%", "-500F"] {
+        match sensors::parse_reading(input) {
+            Ok(reading) => println!("  {:>10} => {:?}", input, reading),
+            Err(e) => println!("  {:>10} => 错误: {}", input, e),
+        }
+    }
+    let batch = "20C\n68F\nabcC\n\n30%\n120%\n101.3KPA";
+    let (readings, errors) = sensors::parse_batch(batch);
+    println!("  批量解析: {} 条成功, 错误: {:?}", readings.len(), errors);
+    // 20C 和 68F(=20C) 的最小值/平均值/最大值都是 20
+    let stats = sensors::normalize(&readings);
+    println!("  温度统计(°C): {:?}", stats.temperature_c);
+    println!("  湿度统计(%): {:?}", stats.humidity);
+    println!("  气压统计(kPa): {:?}", stats.pressure_kpa);
+
+    // 练习4：
+    let target = std::env::temp_dir().join("rust_learn_atomic_demo.txt");
+    write_string_atomic(&target, "first version")?;
+    write_string_atomic(&target, "second version")?; // 覆盖写，后写入的为准
+    println!("  原子写入后的内容: {}", std::fs::read_to_string(&target)?);
+    // 模拟写到一半出错：目标文件保持旧内容，临时文件也会被清理掉
+    let failed = atomic_write_with(&target, |w| {
+        w.write_all(b"half of the new")?;
+        Err(io::Error::other("disk unplugged"))
+    });
+    println!("  写入中途失败: {:?}", failed.map_err(|e| e.to_string()));
+    println!("  目标文件仍然是: {}", std::fs::read_to_string(&target)?);
+    // 重试：临时性错误（Interrupted）最多尝试 3 次，然后返回最后一次的错误
+    let mut calls = 0;
+    let retried: io::Result<()> = retry(3, 0, || {
+        calls += 1;
+        Err(io::Error::new(io::ErrorKind::Interrupted, "try again"))
+    });
+    println!("  重试 {} 次后放弃: {:?}", calls, retried.map_err(|e| e.kind()));
+    atomic_write_with_retry(&target, b"third version", 3, 10)?;
+    println!("  带重试的原子写入: {}", std::fs::read_to_string(&target)?);
+    std::fs::remove_file(&target)?;
+
+    // 练习5：
+    // a) FailingWriter：写满 5 个字节之后就报错
+    let mut sink = Vec::new();
+    let mut failing = testio::FailingWriter::new(&mut sink, 5, io::ErrorKind::Other);
+    println!("  FailingWriter: {:?}, 已写入 {} 字节", failing.write_all(b"hello world").map_err(|e| e.kind()), failing.written());
+    // b) 用它模拟“原子写入时磁盘写到一半出错”，目标文件不会被破坏
+    let target = std::env::temp_dir().join("rust_learn_testio_demo.txt");
+    write_string_atomic(&target, "intact")?;
+    let result = atomic_write_with(&target, |w| {
+        let mut w = testio::FailingWriter::new(w, 3, io::ErrorKind::WriteZero);
+        w.write_all(b"corrupted!")
+    });
+    println!("  中途失败: {:?}, 目标文件: {}", result.map_err(|e| e.kind()), std::fs::read_to_string(&target)?);
+    std::fs::remove_file(&target)?;
+    // c) ShortReader 每次最多返回 3 个字节，read_to_string 会循环读取直到读完
+    let mut short = testio::ShortReader::new(io::Cursor::new("chunked reads reassemble"), 3);
+    let mut reassembled = String::new();
+    short.read_to_string(&mut reassembled)?;
+    println!("  ShortReader: {}", reassembled);
+    // d) InterruptingReader 每隔一次调用返回 Interrupted；read_to_string 会自动重试
+    let mut interrupting = testio::InterruptingReader::new(testio::ShortReader::new(io::Cursor::new("alice"), 2));
+    let mut username = String::new();
+    interrupting.read_to_string(&mut username)?;
+    println!("  InterruptingReader: {} (共调用 read {} 次)", username, interrupting.calls());
+
+    // 练习6：
+    println!("{:?}", expand_ranges("1-3,5,7-9")); // Ok([1, 2, 3, 5, 7, 8, 9])
+    println!("{:?}", expand_ranges("5, 1-2, 2")); // Ok([1, 2, 5])，排序并去重
+    println!("{:?}", expand_ranges("3-1"));       // Err("区间 '3-1' 的起点大于终点")
+    println!("{:?}", expand_ranges("1,x"));       // Err("无法解析的数字 'x'")
+    println!("{:?}", expand_ranges("0-4294967295")); // Err("展开后超过 1000000 个数字")
+
+    // 练习7：
+    let missing = File::open("no_such_config.txt").unwrap_err();
+    let chain = context("加载配置失败", context("读取 no_such_config.txt 失败", missing));
+    println!("{}", report_error(&chain));
+    // 输出：
+    // 错误: 加载配置失败
+    //   caused by: 读取 no_such_config.txt 失败
+    //   caused by: No such file or directory (os error 2)
+    // 提示: 请检查文件路径是否正确
+    println!("{}", report_error(&"abc".parse::<i32>().unwrap_err())); // 错误: invalid digit found in string / 提示: 输入必须是一个整数
+    println!("{}", report_error(&sensors::parse_reading("12.5X").unwrap_err()));
+    println!("{:?}", report_error_with(&io::Error::other("disk full"), true)); // "\u{1b}[31m错误: disk full\u{1b}[0m"
+
+    // 练习8：
+    let log_path = std::env::temp_dir().join(format!("rust_learn_readings_{}.log", std::process::id()));
+    std::fs::write(&log_path, "20C\n68F\nbroken\n45%\n26C\n")?;
+    let mut log = ReadingLog::open(&log_path)?;
+    // 和一次性解析整个文件的结果对比
+    let (all, _) = sensors::parse_batch(&std::fs::read_to_string(&log_path)?);
+    let scratch = sensors::normalize(&all).temperature_c.unwrap();
+    println!("  打开: 温度 {} 条, 平均 {:.2}°C (从头计算: {:.2}°C), 坏行 {}, offset {}",
+        log.running.count, log.running.mean, scratch.mean, log.malformed, log.last_offset); // 3 条, 22.00, 22.00, 坏行 1, offset 23
+    log.append(&sensors::Reading::TempC(30.0))?;
+    println!("  追加之后: 平均 {:.2}°C, 方差 {:.2}, offset {}", log.running.mean, log.running.variance(), log.last_offset); // 24.00, 18.00, 27
+    let reopened = ReadingLog::open(&log_path)?;
+    println!("  重新打开结果一致: {}", reopened.running == log.running && reopened.count_by_kind == log.count_by_kind); // true
+    // 模拟另一个进程追加了两行完整的读数和半行还没写完的数据
+    OpenOptions::new().append(true).open(&log_path)?.write_all(b"101.3kPa\nnope\n50%\n19")?;
+    println!("  refresh 新增: {}, 坏行 {}, 湿度 {:?} 条", log.refresh()?, log.malformed, log.count_by_kind.get("humidity")); // 2, 2, Some(2)
+    OpenOptions::new().append(true).open(&log_path)?.write_all(b"C\n")?;
+    println!("  补完半行后 refresh: {}, 最高温度 {}", log.refresh()?, log.running.max); // 1, 30
+    println!("  offset 等于文件长度: {}", log.last_offset == std::fs::metadata(&log_path)?.len()); // true
+    std::fs::remove_file(&log_path)?;
+
+    // 练习9：
+    println!("{:?}", parse_time("09:30")); // Ok((9, 30))
+    println!("{:?}", parse_time("9:30"));  // Ok((9, 30))，小时可以只写一位
+    println!("{:?}", parse_time("24:00")); // Err("小时 24 不在 0-23 之间")
+    println!("{:?}", parse_time("9:5"));   // Err("分钟 '5' 必须是两位数字")，9:05 还是 9:50 说不清，所以不接受
+    println!("{:?}", parse_time("0930"));  // Err("'0930' 不是 HH:MM 格式")
+
+    // 练习10：
+    println!("{:?}", minutes_between("09:00", "10:30")); // Ok(90)
+    println!("{:?}", minutes_between("10:30", "09:00")); // Ok(-90)，b 比 a 早时结果是负数
+    println!("{:?}", minutes_between("09:00", "9:5"));   // Err("分钟 '5' 必须是两位数字")，错误来自 parse_time
+
     // 练习2：
     read_username_from_file ()?;
     Ok(())
@@ -105,9 +228,15 @@ fn main() -> Result<(), Box<dyn Error>> {
 // 这是一个返回 Result 的函数
 // `?` 操作符让代码非常简洁
 fn read_username_from_file() -> Result<String, io::Error> {
-    let mut f = File::open("username.txt")?; // 如果 open 失败，? 会立即返回 Err
+    let f = File::open("username.txt")?; // 如果 open 失败，? 会立即返回 Err
+    read_username_from(f)
+}
+
+// 真正的读取逻辑只依赖 Read，这样测试时可以换成 testio 里会出错的 reader（练习5）。
+// read_to_string 会一直读到 EOF，遇到 Interrupted 会自动重试，不会把半截名字当成结果
+fn read_username_from(mut reader: impl Read) -> Result<String, io::Error> {
     let mut s = String::new();
-    f.read_to_string(&mut s)?; // 如果 read_to_string 失败，? 会立即返回 Err
+    reader.read_to_string(&mut s)?; // 如果 read_to_string 失败，? 会立即返回 Err
     Ok(s) // 如果一切顺利，返回 Ok(s)
 }
 
@@ -145,6 +274,1130 @@ fn parse_positive_integer(s :&str) -> Result<i32, String> {
         }
     }
 }
+// 练习3：
+// 把传感器相关的类型和函数放进一个单独的模块，对外只暴露 pub 的部分
+mod sensors {
+    use std::error::Error;
+    use std::fmt;
+
+    // 绝对零度（摄氏度和华氏度两种表示）
+    const ABSOLUTE_ZERO_C: f64 = -273.15;
+    const ABSOLUTE_ZERO_F: f64 = -459.67;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Reading {
+        TempC(f64),
+        TempF(f64),
+        PressureKpa(f64),
+        Humidity(f64),
+    }
+
+    // 每一种失败原因都是一个单独的变体，调用者可以用 match 精确地区分它们
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum SensorError {
+        Empty,
+        MissingUnit(String),
+        UnknownUnit(String),
+        InvalidNumber(String),
+        BelowAbsoluteZero(f64),
+        HumidityOutOfRange(f64),
+        NegativePressure(f64),
+    }
+
+    // 实现 Display，让错误可以用 {} 打印出友好的信息
+    impl fmt::Display for SensorError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                SensorError::Empty => write!(f, "读数为空"),
+                SensorError::MissingUnit(s) => write!(f, "'{}' 缺少单位 (C, F, kPa, %)", s),
+                SensorError::UnknownUnit(u) => write!(f, "未知的单位 '{}'", u),
+                SensorError::InvalidNumber(s) => write!(f, "'{}' 不是一个有效的数字", s),
+                SensorError::BelowAbsoluteZero(v) => write!(f, "温度 {} 低于绝对零度", v),
+                SensorError::HumidityOutOfRange(v) => write!(f, "湿度 {}% 不在 0-100 之间", v),
+                SensorError::NegativePressure(v) => write!(f, "气压 {}kPa 不能为负数", v),
+            }
+        }
+    }
+
+    // 实现 Error trait 之后，SensorError 就可以被 `?` 转换成 Box<dyn Error>
+    impl Error for SensorError {}
+
+    // 解析形如 "21.5C"、"70F"、"101.3kPa"、"45%" 的读数，单位不区分大小写
+    pub fn parse_reading(s: &str) -> Result<Reading, SensorError> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(SensorError::Empty);
+        }
+
+        // 1. 找到第一个字母或 '%'，它前面是数字部分，后面是单位部分
+        let split_at = s
+            .find(|c: char| c.is_alphabetic() || c == '%')
+            .unwrap_or(s.len());
+        let (number, unit) = s.split_at(split_at);
+        let number = number.trim();
+
+        // 2. 解析数字部分
+        let value: f64 = match number.parse() {
+            Ok(v) => v,
+            Err(_) => return Err(SensorError::InvalidNumber(s.to_string())),
+        };
+
+        // 3. 根据单位构造读数，同时检查物理上是否合理
+        match unit.trim().to_lowercase().as_str() {
+            "" => Err(SensorError::MissingUnit(s.to_string())),
+            "c" if value < ABSOLUTE_ZERO_C => Err(SensorError::BelowAbsoluteZero(value)),
+            "c" => Ok(Reading::TempC(value)),
+            "f" if value < ABSOLUTE_ZERO_F => Err(SensorError::BelowAbsoluteZero(value)),
+            "f" => Ok(Reading::TempF(value)),
+            "kpa" if value < 0.0 => Err(SensorError::NegativePressure(value)),
+            "kpa" => Ok(Reading::PressureKpa(value)),
+            "%" if !(0.0..=100.0).contains(&value) => Err(SensorError::HumidityOutOfRange(value)),
+            "%" => Ok(Reading::Humidity(value)),
+            _ => Err(SensorError::UnknownUnit(unit.trim().to_string())),
+        }
+    }
+
+    // 逐行解析，成功的读数和失败的行（行号从 1 开始）分别收集，空行直接跳过
+    pub fn parse_batch(lines: &str) -> (Vec<Reading>, Vec<(usize, SensorError)>) {
+        let mut readings = Vec::new();
+        let mut errors = Vec::new();
+        for (index, line) in lines.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match parse_reading(line) {
+                Ok(reading) => readings.push(reading),
+                Err(e) => errors.push((index + 1, e)),
+            }
+        }
+        (readings, errors)
+    }
+
+    // parse_reading 的反方向，format_reading 的结果总能被 parse_reading 解析回来
+    pub fn format_reading(reading: &Reading) -> String {
+        match *reading {
+            Reading::TempC(v) => format!("{}C", v),
+            Reading::TempF(v) => format!("{}F", v),
+            Reading::PressureKpa(v) => format!("{}kPa", v),
+            Reading::Humidity(v) => format!("{}%", v),
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct QuantityStats {
+        pub min: f64,
+        pub mean: f64,
+        pub max: f64,
+        pub count: usize,
+    }
+
+    // 每种物理量一份统计，没有对应读数时为 None
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct NormalizedStats {
+        pub temperature_c: Option<QuantityStats>,
+        pub pressure_kpa: Option<QuantityStats>,
+        pub humidity: Option<QuantityStats>,
+    }
+
+    fn stats_of(values: &[f64]) -> Option<QuantityStats> {
+        if values.is_empty() {
+            return None;
+        }
+        let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        Some(QuantityStats { min, mean, max, count: values.len() })
+    }
+
+    // 先把所有温度统一换算成摄氏度，再按物理量分别计算最小值、平均值和最大值
+    pub fn normalize(readings: &[Reading]) -> NormalizedStats {
+        let mut temps = Vec::new();
+        let mut pressures = Vec::new();
+        let mut humidities = Vec::new();
+        for reading in readings {
+            match *reading {
+                Reading::TempC(c) => temps.push(c),
+                Reading::TempF(f) => temps.push((f - 32.0) * 5.0 / 9.0),
+                Reading::PressureKpa(p) => pressures.push(p),
+                Reading::Humidity(h) => humidities.push(h),
+            }
+        }
+        NormalizedStats {
+            temperature_c: stats_of(&temps),
+            pressure_kpa: stats_of(&pressures),
+            humidity: stats_of(&humidities),
+        }
+    }
+}
+
+// 练习4：
+// 为目标文件生成一个同目录下的临时文件名，例如 `.data.txt.tmp.1234.0`。
+// 进程号 + 递增计数器保证同一个进程里多次写入也不会撞名。
+fn temp_path_for(path: &Path) -> PathBuf {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.tmp.{}.{}", file_name, std::process::id(), n))
+}
+
+// “先写临时文件，再重命名”的通用实现，写入的内容由闭包决定。
+// 任何一步出错都会删除临时文件并把错误返回，目标文件要么是旧内容，要么是完整的新内容。
+fn atomic_write_with<F>(path: &Path, write_contents: F) -> Result<(), io::Error>
+where
+    F: FnOnce(&mut dyn Write) -> io::Result<()>,
+{
+    let temp = temp_path_for(path);
+    let result = (|| {
+        let mut file = File::create(&temp)?;
+        write_contents(&mut file)?;
+        file.flush()?;
+        // sync_all 确保数据真正落盘之后才进行重命名
+        file.sync_all()?;
+        std::fs::rename(&temp, path)
+    })();
+
+    if result.is_err() {
+        // 清理失败时留下的临时文件，这里的错误可以忽略
+        let _ = std::fs::remove_file(&temp);
+        return result;
+    }
+
+    // 尽力同步父目录，让“重命名”这个操作本身也落盘；有的平台不支持打开目录，所以忽略错误
+    if let Some(parent) = path.parent()
+        && let Ok(dir) = File::open(parent)
+    {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+fn atomic_write(path: &Path, contents: &[u8]) -> Result<(), io::Error> {
+    atomic_write_with(path, |w| w.write_all(contents))
+}
+
+fn write_string_atomic(path: &Path, contents: &str) -> Result<(), io::Error> {
+    atomic_write(path, contents.as_bytes())
+}
+
+// 对临时性错误（Interrupted、WouldBlock）进行重试，其他错误立即返回。
+// 最多尝试 attempts 次，每次失败后等待 backoff_ms 毫秒；全部失败时返回最后一次的错误。
+fn retry<T, F>(attempts: u32, backoff_ms: u64, mut op: F) -> io::Result<T>
+where
+    F: FnMut() -> io::Result<T>,
+{
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(e) => {
+                let transient = matches!(e.kind(), io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock);
+                if !transient || attempt >= attempts {
+                    return Err(e);
+                }
+                thread::sleep(Duration::from_millis(backoff_ms));
+                attempt += 1;
+            }
+        }
+    }
+}
+
+fn atomic_write_with_retry(path: &Path, contents: &[u8], attempts: u32, backoff_ms: u64) -> Result<(), io::Error> {
+    retry(attempts, backoff_ms, || atomic_write(path, contents))
+}
+
+// 练习5：
+// 用来“制造” io 错误的包装类型，这样就能稳定地测试各种出错路径
+mod testio {
+    use std::io::{self, Read, Write};
+
+    // 正常写入，直到写满 fail_after_bytes 个字节，之后的每次写入都返回 kind 类型的错误
+    pub struct FailingWriter<W: Write> {
+        inner: W,
+        fail_after_bytes: usize,
+        kind: io::ErrorKind,
+        written: usize,
+    }
+
+    impl<W: Write> FailingWriter<W> {
+        pub fn new(inner: W, fail_after_bytes: usize, kind: io::ErrorKind) -> FailingWriter<W> {
+            FailingWriter { inner, fail_after_bytes, kind, written: 0 }
+        }
+
+        // 到目前为止真正写进 inner 的字节数
+        pub fn written(&self) -> usize {
+            self.written
+        }
+    }
+
+    impl<W: Write> Write for FailingWriter<W> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let remaining = self.fail_after_bytes - self.written;
+            if remaining == 0 {
+                return Err(io::Error::new(self.kind, "injected write failure"));
+            }
+            // 预算不够时只写一部分，这正是 write 允许的“短写”
+            let n = self.inner.write(&buf[..buf.len().min(remaining)])?;
+            self.written += n;
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.inner.flush()
+        }
+    }
+
+    // 每次 read 最多只返回 max_per_call 个字节，用来暴露“以为一次 read 就能填满缓冲区”的错误假设
+    pub struct ShortReader<R: Read> {
+        inner: R,
+        max_per_call: usize,
+    }
+
+    impl<R: Read> ShortReader<R> {
+        pub fn new(inner: R, max_per_call: usize) -> ShortReader<R> {
+            ShortReader { inner, max_per_call: max_per_call.max(1) }
+        }
+    }
+
+    impl<R: Read> Read for ShortReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let limit = buf.len().min(self.max_per_call);
+            self.inner.read(&mut buf[..limit])
+        }
+    }
+
+    // 第 1、3、5…… 次调用返回 ErrorKind::Interrupted，其余调用正常读取
+    pub struct InterruptingReader<R: Read> {
+        inner: R,
+        calls: usize,
+    }
+
+    impl<R: Read> InterruptingReader<R> {
+        pub fn new(inner: R) -> InterruptingReader<R> {
+            InterruptingReader { inner, calls: 0 }
+        }
+
+        pub fn calls(&self) -> usize {
+            self.calls
+        }
+    }
+
+    impl<R: Read> Read for InterruptingReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.calls += 1;
+            if self.calls % 2 == 1 {
+                return Err(io::Error::new(io::ErrorKind::Interrupted, "injected interrupt"));
+            }
+            self.inner.read(buf)
+        }
+    }
+}
+
+// 练习6：
+// 展开后最多允许的数字个数。没有上限的话 "0-4294967295" 会试图分配 16 GiB 内存
+const MAX_EXPANDED: u64 = 1_000_000;
+
+// 把 "1-3,5,7-9" 这样的描述展开成 [1, 2, 3, 5, 7, 8, 9]，结果排序并去重。
+// 上限按去重之前的个数计算，因为内存是在去重之前分配的
+fn expand_ranges(s: &str) -> Result<Vec<u32>, String> {
+    // 解析单个数字，出错时把原始文本放进错误信息里
+    let parse_number = |text: &str| -> Result<u32, String> {
+        text.trim().parse::<u32>().map_err(|_| format!("无法解析的数字 '{}'", text.trim()))
+    };
+
+    let mut numbers = Vec::new();
+    let mut total: u64 = 0;
+    for token in s.split(',') {
+        let token = token.trim();
+        if token.is_empty() {
+            return Err(String::from("存在空的项"));
+        }
+        match token.split_once('-') {
+            Some((start, end)) => {
+                let start = parse_number(start)?;
+                let end = parse_number(end)?;
+                if start > end {
+                    return Err(format!("区间 '{}' 的起点大于终点", token));
+                }
+                // 先检查再展开，用 u64 计算个数，0-4294967295 这样的区间也不会溢出
+                total += (end - start) as u64 + 1;
+                if total > MAX_EXPANDED {
+                    return Err(format!("展开后超过 {} 个数字", MAX_EXPANDED));
+                }
+                numbers.extend(start..=end);
+            }
+            None => {
+                total += 1;
+                if total > MAX_EXPANDED {
+                    return Err(format!("展开后超过 {} 个数字", MAX_EXPANDED));
+                }
+                numbers.push(parse_number(token)?);
+            }
+        }
+    }
+    numbers.sort();
+    numbers.dedup();
+    Ok(numbers)
+}
+
+// 练习7：
+// 给一个底层错误加上一句“正在做什么”的说明，并通过 source() 保留原来的错误，形成错误链
+#[derive(Debug)]
+struct Context {
+    message: String,
+    source: Box<dyn Error + 'static>,
+}
+
+impl std::fmt::Display for Context {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for Context {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+fn context<E: Error + 'static>(message: &str, error: E) -> Context {
+    Context { message: message.to_string(), source: Box::new(error) }
+}
+
+// 用 downcast_ref 判断错误的具体类型，为常见的错误给出一句提示
+fn hint_for(e: &(dyn Error + 'static)) -> Option<&'static str> {
+    if let Some(io_error) = e.downcast_ref::<io::Error>() {
+        return match io_error.kind() {
+            io::ErrorKind::NotFound => Some("请检查文件路径是否正确"),
+            io::ErrorKind::PermissionDenied => Some("请检查文件的读写权限"),
+            _ => None,
+        };
+    }
+    if e.downcast_ref::<std::num::ParseIntError>().is_some() {
+        return Some("输入必须是一个整数");
+    }
+    if e.downcast_ref::<sensors::SensorError>().is_some() {
+        return Some("读数的格式是 <数值><单位>，例如 21.5C、45%、101.3kPa");
+    }
+    None
+}
+
+// 先打印最外层错误，再沿着 source() 逐层打印原因；最后附上能找到的第一条提示。
+// color 为 true 时用 ANSI 转义码把第一行标成红色
+fn report_error_with(e: &(dyn Error + 'static), color: bool) -> String {
+    let headline = format!("错误: {}", e);
+    let mut out = if color { format!("\x1b[31m{}\x1b[0m", headline) } else { headline };
+    let mut hint = hint_for(e);
+    let mut current = e.source();
+    while let Some(cause) = current {
+        out.push_str(&format!("\n  caused by: {}", cause));
+        hint = hint.or_else(|| hint_for(cause));
+        current = cause.source();
+    }
+    if let Some(hint) = hint {
+        out.push_str(&format!("\n提示: {}", hint));
+    }
+    out
+}
+
+fn report_error(e: &(dyn Error + 'static)) -> String {
+    report_error_with(e, false)
+}
+
+// main 返回的错误类型。Rust 会用 Debug 格式打印 main 返回的错误，
+// 所以这里让 Debug 输出 report_error 的结果，而不是难读的 Os { code: 2, ... }
+struct Reported(Box<dyn Error + 'static>);
+
+impl std::fmt::Debug for Reported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", report_error(self.0.as_ref()))
+    }
+}
+
+// 有了这个 From 实现，main 里的 `?` 可以把任何错误自动转换成 Reported
+impl<E: Error + 'static> From<E> for Reported {
+    fn from(error: E) -> Self {
+        Reported(Box::new(error))
+    }
+}
+
+// 练习8：
+// 一遍扫描就能得到的统计量，用 Welford 算法更新平均值和方差，
+// 不需要保存所有数据，可以一条一条地累加
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+struct Summary {
+    count: usize,
+    mean: f64,
+    m2: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Summary {
+    fn push(&mut self, x: f64) {
+        if self.count == 0 {
+            self.min = x;
+            self.max = x;
+        }
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (x - self.mean);
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+    }
+
+    // 总体方差；没有数据时为 0
+    fn variance(&self) -> f64 {
+        if self.count == 0 { 0.0 } else { self.m2 / self.count as f64 }
+    }
+}
+
+#[derive(Debug)]
+enum LogError {
+    Io(io::Error),
+    // 文件比我们已经读到的位置还短，说明被别人截断或替换了
+    Truncated { len: u64, offset: u64 },
+}
+
+impl std::fmt::Display for LogError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogError::Io(e) => write!(f, "读写日志失败: {}", e),
+            LogError::Truncated { len, offset } => {
+                write!(f, "日志文件只有 {} 字节，但已经读到了第 {} 字节，文件可能被截断了", len, offset)
+            }
+        }
+    }
+}
+
+impl Error for LogError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LogError::Io(e) => Some(e),
+            LogError::Truncated { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for LogError {
+    fn from(e: io::Error) -> Self {
+        LogError::Io(e)
+    }
+}
+
+// 读数日志：每行一条读数，格式和 parse_reading 接受的一样，例如 "21.5C"。
+// running 统计换算成摄氏度之后的温度，count_by_kind 统计每种读数的条数。
+// last_offset 记录已经处理到文件的哪个字节，refresh 只读取它之后的内容
+#[derive(Debug)]
+struct ReadingLog {
+    path: PathBuf,
+    running: Summary,
+    count_by_kind: HashMap<String, usize>,
+    last_offset: u64,
+    malformed: usize,
+}
+
+impl ReadingLog {
+    // 打开（不存在就创建）日志文件，并把已有的行全部统计一遍
+    fn open(path: &Path) -> Result<ReadingLog, LogError> {
+        OpenOptions::new().create(true).append(true).open(path)?;
+        let mut log = ReadingLog {
+            path: path.to_path_buf(),
+            running: Summary::default(),
+            count_by_kind: HashMap::new(),
+            last_offset: 0,
+            malformed: 0,
+        };
+        log.refresh()?;
+        Ok(log)
+    }
+
+    // 追加一行，然后用 refresh 把它（以及别的进程在这之前追加的行）计入统计
+    fn append(&mut self, reading: &sensors::Reading) -> Result<(), LogError> {
+        let mut file = OpenOptions::new().append(true).open(&self.path)?;
+        writeln!(file, "{}", sensors::format_reading(reading))?;
+        self.refresh()?;
+        Ok(())
+    }
+
+    // 只读取 last_offset 之后的完整行，返回新吸收的读数条数。
+    // 最后一行如果还没写完（没有换行符），留到下一次再读
+    fn refresh(&mut self) -> Result<usize, LogError> {
+        let mut file = File::open(&self.path)?;
+        let len = file.metadata()?.len();
+        if len < self.last_offset {
+            return Err(LogError::Truncated { len, offset: self.last_offset });
+        }
+        file.seek(SeekFrom::Start(self.last_offset))?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        let complete = match bytes.iter().rposition(|&b| b == b'\n') {
+            Some(i) => i + 1,
+            None => return Ok(0),
+        };
+
+        let mut absorbed = 0;
+        for line in String::from_utf8_lossy(&bytes[..complete]).lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match sensors::parse_reading(line) {
+                Ok(reading) => {
+                    self.absorb(&reading);
+                    absorbed += 1;
+                }
+                Err(_) => self.malformed += 1,
+            }
+        }
+        self.last_offset += complete as u64;
+        Ok(absorbed)
+    }
+
+    fn absorb(&mut self, reading: &sensors::Reading) {
+        let kind = match *reading {
+            sensors::Reading::TempC(c) => {
+                self.running.push(c);
+                "temperature"
+            }
+            sensors::Reading::TempF(f) => {
+                self.running.push((f - 32.0) * 5.0 / 9.0);
+                "temperature"
+            }
+            sensors::Reading::PressureKpa(_) => "pressure",
+            sensors::Reading::Humidity(_) => "humidity",
+        };
+        *self.count_by_kind.entry(kind.to_string()).or_insert(0) += 1;
+    }
+}
+
+// 练习9：
+// 解析 "HH:MM" 格式的时间。小时可以写一位或两位（"9:30" 和 "09:30" 都可以），
+// 分钟必须正好两位，因为 "9:5" 既可能是 9:05 也可能是 9:50
+fn parse_time(s: &str) -> Result<(u8, u8), String> {
+    let s = s.trim();
+    let (hours, minutes) = s.split_once(':').ok_or_else(|| format!("'{}' 不是 HH:MM 格式", s))?;
+
+    let all_digits = |text: &str| !text.is_empty() && text.chars().all(|c| c.is_ascii_digit());
+    if !all_digits(hours) || hours.len() > 2 {
+        return Err(format!("小时 '{}' 必须是一位或两位数字", hours));
+    }
+    if !all_digits(minutes) || minutes.len() != 2 {
+        return Err(format!("分钟 '{}' 必须是两位数字", minutes));
+    }
+
+    // 上面已经检查过只有数字且最多两位，这里的 parse 不会失败
+    let hours: u8 = hours.parse().unwrap();
+    let minutes: u8 = minutes.parse().unwrap();
+    if hours > 23 {
+        return Err(format!("小时 {} 不在 0-23 之间", hours));
+    }
+    if minutes > 59 {
+        return Err(format!("分钟 {} 不在 0-59 之间", minutes));
+    }
+    Ok((hours, minutes))
+}
+
+// 练习10：
+// 从 a 到 b 经过了多少分钟，b 比 a 早时返回负数。解析错误直接用 ? 传给调用者。
+// 两个时间被当成同一天，不会跨过午夜："23:30" 到 "00:15" 是 -1395 而不是 45；
+// 需要跨午夜的调用者可以自己用 rem_euclid(24 * 60) 换算成 45
+fn minutes_between(a: &str, b: &str) -> Result<i64, String> {
+    let to_minutes = |(hours, minutes): (u8, u8)| hours as i64 * 60 + minutes as i64;
+    let start = to_minutes(parse_time(a)?);
+    let end = to_minutes(parse_time(b)?);
+    Ok(end - start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sensors::{Reading, SensorError};
+
+    // 练习3
+    #[test]
+    fn parse_reading_accepts_each_suffix_case_insensitively() {
+        assert_eq!(sensors::parse_reading("21.5C"), Ok(Reading::TempC(21.5)));
+        assert_eq!(sensors::parse_reading("21.5c"), Ok(Reading::TempC(21.5)));
+        assert_eq!(sensors::parse_reading("70F"), Ok(Reading::TempF(70.0)));
+        assert_eq!(sensors::parse_reading("70f"), Ok(Reading::TempF(70.0)));
+        assert_eq!(sensors::parse_reading("101.3kPa"), Ok(Reading::PressureKpa(101.3)));
+        assert_eq!(sensors::parse_reading("101.3KPA"), Ok(Reading::PressureKpa(101.3)));
+        assert_eq!(sensors::parse_reading(" 45 % "), Ok(Reading::Humidity(45.0)));
+    }
+
+    #[test]
+    fn parse_reading_boundary_values() {
+        // 正好等于绝对零度、0% 和 100% 都是合法的
+        assert_eq!(sensors::parse_reading("-273.15C"), Ok(Reading::TempC(-273.15)));
+        assert_eq!(sensors::parse_reading("-459.67F"), Ok(Reading::TempF(-459.67)));
+        assert_eq!(sensors::parse_reading("100%"), Ok(Reading::Humidity(100.0)));
+        assert_eq!(sensors::parse_reading("0%"), Ok(Reading::Humidity(0.0)));
+        assert_eq!(sensors::parse_reading("0kPa"), Ok(Reading::PressureKpa(0.0)));
+        // 越过边界一点点就报错
+        assert_eq!(sensors::parse_reading("-273.16C"), Err(SensorError::BelowAbsoluteZero(-273.16)));
+        assert_eq!(sensors::parse_reading("-460F"), Err(SensorError::BelowAbsoluteZero(-460.0)));
+        assert_eq!(sensors::parse_reading("100.1%"), Err(SensorError::HumidityOutOfRange(100.1)));
+        assert_eq!(sensors::parse_reading("-1%"), Err(SensorError::HumidityOutOfRange(-1.0)));
+        assert_eq!(sensors::parse_reading("-0.5kPa"), Err(SensorError::NegativePressure(-0.5)));
+    }
+
+    #[test]
+    fn parse_reading_rejects_bare_numbers_and_bad_input() {
+        assert_eq!(sensors::parse_reading("42"), Err(SensorError::MissingUnit("42".to_string())));
+        assert_eq!(sensors::parse_reading("   "), Err(SensorError::Empty));
+        assert_eq!(sensors::parse_reading("12.5X"), Err(SensorError::UnknownUnit("X".to_string())));
+        assert_eq!(sensors::parse_reading("abcC"), Err(SensorError::InvalidNumber("abcC".to_string())));
+        assert_eq!(sensors::parse_reading("C"), Err(SensorError::InvalidNumber("C".to_string())));
+    }
+
+    #[test]
+    fn parse_batch_reports_errors_with_line_numbers() {
+        let batch = "20C\n68F\nabcC\n\n30%\n120%\n101.3KPA\n7";
+        let (readings, errors) = sensors::parse_batch(batch);
+        assert_eq!(readings, vec![
+            Reading::TempC(20.0),
+            Reading::TempF(68.0),
+            Reading::Humidity(30.0),
+            Reading::PressureKpa(101.3),
+        ]);
+        // 空行（第 4 行）被跳过，但仍然占一个行号
+        assert_eq!(errors, vec![
+            (3, SensorError::InvalidNumber("abcC".to_string())),
+            (6, SensorError::HumidityOutOfRange(120.0)),
+            (8, SensorError::MissingUnit("7".to_string())),
+        ]);
+    }
+
+    #[test]
+    fn format_reading_round_trips_through_parse_reading() {
+        for reading in [Reading::TempC(-3.5), Reading::TempF(98.6), Reading::PressureKpa(101.3), Reading::Humidity(0.0)] {
+            assert_eq!(sensors::parse_reading(&sensors::format_reading(&reading)), Ok(reading));
+        }
+    }
+
+    #[test]
+    fn normalize_converts_fahrenheit_before_computing_stats() {
+        // 212F = 100C，32F = 0C，和 10C 一起：最小 0，最大 100，平均 (0 + 10 + 100) / 3
+        let readings = [Reading::TempF(212.0), Reading::TempC(10.0), Reading::TempF(32.0), Reading::Humidity(40.0), Reading::Humidity(60.0)];
+        let stats = sensors::normalize(&readings);
+        let temps = stats.temperature_c.unwrap();
+        assert_eq!(temps.count, 3);
+        assert_eq!(temps.min, 0.0);
+        assert_eq!(temps.max, 100.0);
+        assert!((temps.mean - 110.0 / 3.0).abs() < 1e-9);
+        let humidity = stats.humidity.unwrap();
+        assert_eq!((humidity.min, humidity.mean, humidity.max, humidity.count), (40.0, 50.0, 60.0, 2));
+        assert_eq!(stats.pressure_kpa, None);
+
+        let empty = sensors::normalize(&[]);
+        assert_eq!((empty.temperature_c, empty.pressure_kpa, empty.humidity), (None, None, None));
+    }
+
+    // 每个测试用自己的目录，测试并行运行时不会互相干扰，也方便检查残留的临时文件
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rust_learn_{}_{}", std::process::id(), name));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn entries_of(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    // 练习4
+    #[test]
+    fn interrupted_atomic_write_keeps_the_old_contents() {
+        let dir = temp_dir("atomic_interrupted");
+        let target = dir.join("data.txt");
+        write_string_atomic(&target, "intact").unwrap();
+        // 写了 3 个字节之后磁盘“坏了”
+        let result = atomic_write_with(&target, |w| {
+            let mut w = testio::FailingWriter::new(w, 3, io::ErrorKind::WriteZero);
+            w.write_all(b"corrupted!")
+        });
+        let contents = std::fs::read_to_string(&target).unwrap();
+        let entries = entries_of(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::WriteZero);
+        assert_eq!(contents, "intact");
+        assert_eq!(entries, vec!["data.txt"]);
+    }
+
+    #[test]
+    fn successful_overwrites_leave_no_temp_files_and_last_write_wins() {
+        let dir = temp_dir("atomic_overwrite");
+        let target = dir.join("data.txt");
+        for i in 0..5 {
+            atomic_write(&target, format!("version {}", i).as_bytes()).unwrap();
+        }
+        atomic_write_with_retry(&target, b"final", 3, 0).unwrap();
+        let contents = std::fs::read_to_string(&target).unwrap();
+        let entries = entries_of(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(contents, "final");
+        assert_eq!(entries, vec!["data.txt"]);
+    }
+
+    #[test]
+    fn temp_paths_are_unique_siblings() {
+        let target = Path::new("/some/dir/data.txt");
+        let (a, b) = (temp_path_for(target), temp_path_for(target));
+        assert_ne!(a, b);
+        assert_eq!(a.parent(), target.parent());
+        assert!(a.file_name().unwrap().to_string_lossy().starts_with(".data.txt.tmp."));
+    }
+
+    #[test]
+    fn retry_gives_up_after_the_configured_attempts_with_the_last_error() {
+        let mut calls = 0;
+        let result: io::Result<()> = retry(3, 0, || {
+            calls += 1;
+            Err(io::Error::new(io::ErrorKind::Interrupted, format!("attempt {}", calls)))
+        });
+        let error = result.unwrap_err();
+        assert_eq!(calls, 3);
+        assert_eq!(error.kind(), io::ErrorKind::Interrupted);
+        assert_eq!(error.to_string(), "attempt 3");
+    }
+
+    #[test]
+    fn retry_stops_on_permanent_errors_and_returns_success() {
+        let mut calls = 0;
+        let permanent: io::Result<()> = retry(5, 0, || {
+            calls += 1;
+            Err(io::Error::new(io::ErrorKind::NotFound, "gone"))
+        });
+        assert_eq!((calls, permanent.unwrap_err().kind()), (1, io::ErrorKind::NotFound));
+
+        let mut calls = 0;
+        let recovered = retry(5, 0, || {
+            calls += 1;
+            if calls < 3 { Err(io::Error::new(io::ErrorKind::WouldBlock, "busy")) } else { Ok(calls) }
+        });
+        assert_eq!(recovered.unwrap(), 3);
+    }
+
+    #[test]
+    fn atomic_write_with_retry_returns_the_final_error() {
+        let dir = temp_dir("atomic_retry");
+        // 父目录不存在：NotFound 不是临时错误，不会重试
+        let result = atomic_write_with_retry(&dir.join("missing").join("data.txt"), b"x", 3, 0);
+        let entries = entries_of(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::NotFound);
+        assert!(entries.is_empty());
+    }
+
+    // 练习5
+    #[test]
+    fn failing_writer_counts_bytes_until_the_budget_runs_out() {
+        let mut sink = Vec::new();
+        let mut failing = testio::FailingWriter::new(&mut sink, 5, io::ErrorKind::Other);
+        // 预算不够时是“短写”：第一次 4 个字节全部写入，第二次只剩 1 个字节的预算
+        assert_eq!(failing.write(b"abcd").unwrap(), 4);
+        assert_eq!(failing.write(b"efgh").unwrap(), 1);
+        assert_eq!(failing.written(), 5);
+        let error = failing.write(b"ijkl").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::Other);
+        assert_eq!(failing.written(), 5);
+        assert_eq!(sink, b"abcde");
+
+        let mut sink = Vec::new();
+        let mut failing = testio::FailingWriter::new(&mut sink, 5, io::ErrorKind::WriteZero);
+        assert_eq!(failing.write_all(b"hello world").unwrap_err().kind(), io::ErrorKind::WriteZero);
+        assert_eq!(failing.written(), 5);
+        assert_eq!(sink, b"hello");
+    }
+
+    #[test]
+    fn interrupting_reader_fails_every_other_call() {
+        let mut reader = testio::InterruptingReader::new(io::Cursor::new("abc"));
+        let mut buf = [0u8; 8];
+        assert_eq!(reader.read(&mut buf).unwrap_err().kind(), io::ErrorKind::Interrupted);
+        assert_eq!(reader.read(&mut buf).unwrap(), 3);
+        assert_eq!(reader.read(&mut buf).unwrap_err().kind(), io::ErrorKind::Interrupted);
+        assert_eq!(reader.read(&mut buf).unwrap(), 0);
+        assert_eq!(reader.calls(), 4);
+    }
+
+    #[test]
+    fn short_reader_chunks_reassemble_to_the_original() {
+        let text = "chunked reads reassemble";
+        let mut reader = testio::ShortReader::new(io::Cursor::new(text), 3);
+        let mut buf = [0u8; 16];
+        // 缓冲区再大，一次也只给 3 个字节
+        assert_eq!(reader.read(&mut buf).unwrap(), 3);
+        assert_eq!(&buf[..3], b"chu");
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).unwrap();
+        assert_eq!(format!("chu{}", rest), text);
+
+        // max_per_call 为 0 时按 1 处理，否则 read 会一直返回 0，看起来像 EOF
+        let mut one_at_a_time = testio::ShortReader::new(io::Cursor::new("xy"), 0);
+        assert_eq!(one_at_a_time.read(&mut buf).unwrap(), 1);
+    }
+
+    #[test]
+    fn read_username_survives_short_and_interrupted_reads() {
+        let reader = testio::InterruptingReader::new(testio::ShortReader::new(io::Cursor::new("alice"), 2));
+        assert_eq!(read_username_from(reader).unwrap(), "alice");
+        assert_eq!(read_username_from(io::Cursor::new("")).unwrap(), "");
+
+        // 不是 Interrupted 的错误照常返回
+        struct Broken;
+        impl Read for Broken {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::PermissionDenied, "locked"))
+            }
+        }
+        assert_eq!(read_username_from(Broken).unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+    }
+
+    // 练习8
+    #[test]
+    fn summary_matches_the_two_pass_formulas() {
+        let values = [20.0, 20.0, 26.0, 30.0];
+        let mut summary = Summary::default();
+        values.iter().for_each(|&x| summary.push(x));
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let variance = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / values.len() as f64;
+        assert_eq!((summary.count, summary.min, summary.max), (4, 20.0, 30.0));
+        assert!((summary.mean - mean).abs() < 1e-9);
+        assert!((summary.variance() - variance).abs() < 1e-9);
+        assert_eq!(Summary::default().variance(), 0.0);
+    }
+
+    #[test]
+    fn opening_a_log_matches_a_from_scratch_computation() {
+        let dir = temp_dir("log_open");
+        let path = dir.join("readings.log");
+        let fixture = "20C\n68F\nbroken\n45%\n\n26C\n101.3kPa\n";
+        std::fs::write(&path, fixture).unwrap();
+        let log = ReadingLog::open(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let (all, errors) = sensors::parse_batch(fixture);
+        let scratch = sensors::normalize(&all).temperature_c.unwrap();
+        assert_eq!(log.running.count, scratch.count);
+        assert_eq!((log.running.min, log.running.max), (scratch.min, scratch.max));
+        assert!((log.running.mean - scratch.mean).abs() < 1e-9);
+        assert_eq!(log.malformed, errors.len());
+        assert_eq!(log.count_by_kind.get("temperature"), Some(&3));
+        assert_eq!(log.count_by_kind.get("humidity"), Some(&1));
+        assert_eq!(log.count_by_kind.get("pressure"), Some(&1));
+        assert_eq!(log.last_offset, fixture.len() as u64);
+    }
+
+    #[test]
+    fn opening_a_missing_log_creates_it_empty() {
+        let dir = temp_dir("log_missing");
+        let path = dir.join("readings.log");
+        let log = ReadingLog::open(&path).unwrap();
+        let exists = path.exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(exists);
+        assert_eq!((log.running.count, log.malformed, log.last_offset), (0, 0, 0));
+        assert!(log.count_by_kind.is_empty());
+    }
+
+    #[test]
+    fn append_then_reopen_gives_the_same_statistics() {
+        let dir = temp_dir("log_append");
+        let path = dir.join("readings.log");
+        std::fs::write(&path, "20C\n68F\nbroken\n45%\n26C\n").unwrap();
+        let mut log = ReadingLog::open(&path).unwrap();
+        assert_eq!(log.last_offset, 23);
+        log.append(&sensors::Reading::TempC(30.0)).unwrap();
+        log.append(&sensors::Reading::PressureKpa(99.5)).unwrap();
+        let reopened = ReadingLog::open(&path).unwrap();
+        let len = std::fs::metadata(&path).unwrap().len();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // 20、20、26、30 的平均值是 24，总体方差是 18
+        assert_eq!(log.running.count, 4);
+        assert!((log.running.mean - 24.0).abs() < 1e-9);
+        assert!((log.running.variance() - 18.0).abs() < 1e-9);
+        assert_eq!(log.last_offset, 23 + "30C\n".len() as u64 + "99.5kPa\n".len() as u64);
+        assert_eq!(log.last_offset, len);
+        assert_eq!(reopened.running, log.running);
+        assert_eq!(reopened.count_by_kind, log.count_by_kind);
+        assert_eq!((reopened.malformed, reopened.last_offset), (log.malformed, log.last_offset));
+    }
+
+    #[test]
+    fn refresh_picks_up_external_appends_and_waits_for_complete_lines() {
+        let dir = temp_dir("log_refresh");
+        let path = dir.join("readings.log");
+        std::fs::write(&path, "20C\n").unwrap();
+        let mut log = ReadingLog::open(&path).unwrap();
+        let append = |bytes: &[u8]| OpenOptions::new().append(true).open(&path).unwrap().write_all(bytes).unwrap();
+
+        // 没有新内容时什么都不做
+        assert_eq!(log.refresh().unwrap(), 0);
+        assert_eq!(log.last_offset, 4);
+
+        // 另一个进程追加了两行完整的读数、一行坏数据和半行还没写完的数据
+        append(b"101.3kPa\nnope\n50%\n19");
+        assert_eq!(log.refresh().unwrap(), 2);
+        assert_eq!(log.malformed, 1);
+        assert_eq!(log.count_by_kind.get("humidity"), Some(&1));
+        assert_eq!(log.last_offset, "20C\n101.3kPa\nnope\n50%\n".len() as u64);
+        assert_eq!(log.running.count, 1);
+
+        // 补完半行之后才会被计入
+        append(b"C\n");
+        assert_eq!(log.refresh().unwrap(), 1);
+        assert_eq!(log.running.count, 2);
+        assert_eq!(log.running.max, 20.0);
+        assert_eq!(log.running.min, 19.0);
+        let len = std::fs::metadata(&path).unwrap().len();
+        assert_eq!(log.last_offset, len);
+
+        // 文件被截断时报错，而不是从错误的位置继续读
+        std::fs::write(&path, "1C\n").unwrap();
+        let truncated = log.refresh();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(truncated, Err(LogError::Truncated { len: 3, offset }) if offset == len));
+    }
+
+    // 练习6
+    #[test]
+    fn expand_ranges_sorts_and_dedups() {
+        assert_eq!(expand_ranges("1-3,5,7-9"), Ok(vec![1, 2, 3, 5, 7, 8, 9]));
+        assert_eq!(expand_ranges("5, 1-2, 2"), Ok(vec![1, 2, 5]));
+        assert_eq!(expand_ranges("4-4"), Ok(vec![4]));
+        assert_eq!(expand_ranges("4294967295"), Ok(vec![u32::MAX]));
+    }
+
+    #[test]
+    fn expand_ranges_rejects_malformed_tokens() {
+        assert_eq!(expand_ranges("3-1"), Err("区间 '3-1' 的起点大于终点".to_string()));
+        assert_eq!(expand_ranges("1,x"), Err("无法解析的数字 'x'".to_string()));
+        assert_eq!(expand_ranges("1-"), Err("无法解析的数字 ''".to_string()));
+        assert_eq!(expand_ranges("-1"), Err("无法解析的数字 ''".to_string()));
+        assert_eq!(expand_ranges("1,,2"), Err("存在空的项".to_string()));
+        assert_eq!(expand_ranges(""), Err("存在空的项".to_string()));
+    }
+
+    #[test]
+    fn expand_ranges_caps_the_total_size() {
+        let too_many = Err(format!("展开后超过 {} 个数字", MAX_EXPANDED));
+        assert_eq!(expand_ranges("0-4294967295"), too_many);
+        // 正好等于上限可以，多一个就不行；重复的区间也按展开后的个数算
+        assert_eq!(expand_ranges("1-1000000").map(|v| v.len()), Ok(1_000_000));
+        assert_eq!(expand_ranges("1-1000000,7"), too_many);
+        assert_eq!(expand_ranges("1-600000,1-600000"), too_many);
+    }
+
+    // 练习9
+    #[test]
+    fn parse_time_accepts_valid_times() {
+        assert_eq!(parse_time("09:30"), Ok((9, 30)));
+        assert_eq!(parse_time("9:30"), Ok((9, 30)));
+        assert_eq!(parse_time("00:00"), Ok((0, 0)));
+        assert_eq!(parse_time("23:59"), Ok((23, 59)));
+        assert_eq!(parse_time(" 12:05 "), Ok((12, 5)));
+    }
+
+    #[test]
+    fn parse_time_rejects_out_of_range_values() {
+        assert_eq!(parse_time("24:00"), Err("小时 24 不在 0-23 之间".to_string()));
+        assert_eq!(parse_time("99:00"), Err("小时 99 不在 0-23 之间".to_string()));
+        assert_eq!(parse_time("12:60"), Err("分钟 60 不在 0-59 之间".to_string()));
+    }
+
+    #[test]
+    fn parse_time_rejects_malformed_input() {
+        assert_eq!(parse_time("0930"), Err("'0930' 不是 HH:MM 格式".to_string()));
+        assert_eq!(parse_time(""), Err("'' 不是 HH:MM 格式".to_string()));
+        // 分钟只有一位时说不清是 9:05 还是 9:50，所以不接受
+        assert_eq!(parse_time("9:5"), Err("分钟 '5' 必须是两位数字".to_string()));
+        assert_eq!(parse_time("ab:30"), Err("小时 'ab' 必须是一位或两位数字".to_string()));
+        assert_eq!(parse_time("+9:30"), Err("小时 '+9' 必须是一位或两位数字".to_string()));
+        assert_eq!(parse_time("009:30"), Err("小时 '009' 必须是一位或两位数字".to_string()));
+        assert_eq!(parse_time("09:3x"), Err("分钟 '3x' 必须是两位数字".to_string()));
+        assert_eq!(parse_time("09:30:00"), Err("分钟 '30:00' 必须是两位数字".to_string()));
+        assert_eq!(parse_time("０9:30"), Err("小时 '０9' 必须是一位或两位数字".to_string()));
+    }
+
+    // 练习10
+    #[test]
+    fn minutes_between_is_signed() {
+        assert_eq!(minutes_between("09:00", "10:30"), Ok(90));
+        assert_eq!(minutes_between("10:30", "09:00"), Ok(-90));
+        assert_eq!(minutes_between("9:00", "09:00"), Ok(0));
+        assert_eq!(minutes_between("00:00", "23:59"), Ok(1439));
+    }
+
+    #[test]
+    fn minutes_between_does_not_wrap_past_midnight() {
+        // 同一天内计算，跨午夜的结果是负数；需要的话由调用者换算
+        assert_eq!(minutes_between("23:30", "00:15"), Ok(-1395));
+        assert_eq!(minutes_between("23:30", "00:15").map(|m| m.rem_euclid(24 * 60)), Ok(45));
+    }
+
+    #[test]
+    fn minutes_between_propagates_parse_errors_from_either_side() {
+        assert_eq!(minutes_between("09:00", "9:5"), Err("分钟 '5' 必须是两位数字".to_string()));
+        assert_eq!(minutes_between("24:00", "09:00"), Err("小时 24 不在 0-23 之间".to_string()));
+        // 两边都错时返回先解析的 a 的错误
+        assert_eq!(minutes_between("0930", "xx"), Err("'0930' 不是 HH:MM 格式".to_string()));
+    }
+
+    // 练习7
+    #[test]
+    fn report_error_prints_a_three_deep_chain() {
+        let missing = io::Error::new(io::ErrorKind::NotFound, "no such file");
+        let chain = context("加载配置失败", context("读取 config.txt 失败", missing));
+        assert_eq!(
+            report_error(&chain),
+            "错误: 加载配置失败\n  caused by: 读取 config.txt 失败\n  caused by: no such file\n提示: 请检查文件路径是否正确"
+        );
+    }
+
+    #[test]
+    fn report_error_picks_a_hint_by_error_type() {
+        let last_line = |e: &(dyn Error + 'static)| report_error(e).lines().last().unwrap().to_string();
+        assert_eq!(last_line(&io::Error::new(io::ErrorKind::NotFound, "x")), "提示: 请检查文件路径是否正确");
+        assert_eq!(last_line(&io::Error::new(io::ErrorKind::PermissionDenied, "x")), "提示: 请检查文件的读写权限");
+        assert_eq!(last_line(&"abc".parse::<i32>().unwrap_err()), "提示: 输入必须是一个整数");
+        assert_eq!(
+            last_line(&sensors::parse_reading("12.5X").unwrap_err()),
+            "提示: 读数的格式是 <数值><单位>，例如 21.5C、45%、101.3kPa"
+        );
+        // 不认识的错误没有提示；提示可以来自链条中间的任意一层
+        assert_eq!(report_error(&io::Error::other("disk full")), "错误: disk full");
+        let wrapped = context("导入读数失败", sensors::SensorError::Empty);
+        assert_eq!(report_error(&wrapped).lines().count(), 3);
+        assert!(report_error(&wrapped).ends_with("101.3kPa"));
+    }
+
+    #[test]
+    fn report_error_colors_only_the_headline_when_asked() {
+        let chain = context("保存失败", io::Error::new(io::ErrorKind::PermissionDenied, "denied"));
+        let plain = report_error_with(&chain, false);
+        let colored = report_error_with(&chain, true);
+        assert_eq!(plain, report_error(&chain));
+        assert!(!plain.contains('\x1b'));
+        assert_eq!(colored, format!("\x1b[31m错误: 保存失败\x1b[0m{}", &plain["错误: 保存失败".len()..]));
+        assert_eq!(colored.matches("\x1b[31m").count(), 1);
+    }
+
+    #[test]
+    fn main_errors_are_printed_through_report_error() {
+        let reported = Reported::from(io::Error::new(io::ErrorKind::NotFound, "username.txt"));
+        assert_eq!(format!("{:?}", reported), "错误: username.txt\n提示: 请检查文件路径是否正确");
+    }
+}
+
 /*
  * =====================================================================================
  * 练习挑战 (Challenge Section)
@@ -164,4 +1417,57 @@ fn parse_positive_integer(s :&str) -> Result<i32, String> {
  *    修改 `main` 函数的签名，然后在 `main` 中直接调用 `read_username_from_file()?` 并打印结果，
  *    体会 `?` 带来的便利。
  *
+ * 3. 传感器读数解析:
+ *    在一个 `sensors` 模块中定义 `enum Reading { TempC(f64), TempF(f64), PressureKpa(f64), Humidity(f64) }`
+ *    和一个错误枚举 `SensorError`。
+ *    - `parse_reading(s: &str) -> Result<Reading, SensorError>` 解析 "21.5C"、"70F"、"101.3kPa"、"45%"
+ *      这样的字符串（单位不区分大小写）。没有单位的纯数字返回 `MissingUnit`，
+ *      低于绝对零度的温度、不在 0-100 之间的湿度要返回各自对应的错误变体。
+ *    - `parse_batch(lines: &str)` 逐行解析，收集成功的读数和带行号的错误。
+ *    - `normalize(readings: &[Reading])` 把温度统一换算成摄氏度，并计算每种物理量的最小值、平均值和最大值。
+ *
+ * 4. 原子写文件:
+ *    直接 `File::create` 然后写入，如果中途出错，文件就只剩下一半内容。
+ *    编写 `atomic_write(path: &Path, contents: &[u8]) -> Result<(), io::Error>`：
+ *    先写到同目录下的临时文件，`flush` 并 `sync_all` 之后再用 `fs::rename` 覆盖目标文件，
+ *    出错时删除临时文件。再编写 `write_string_atomic` 便捷函数，
+ *    以及 `atomic_write_with_retry(path, contents, attempts, backoff_ms)`，
+ *    对 `Interrupted`、`WouldBlock` 这样的临时性错误进行重试。
+ *
+ *
+ * 5. 制造 io 错误:
+ *    真实的 io 错误很难触发，所以出错路径往往没人测试。在一个 `testio` 模块中编写三个包装类型：
+ *    - `FailingWriter<W: Write>`：写满指定的字节数之后返回指定类型的错误；
+ *    - `ShortReader<R: Read>`：每次 `read` 最多只返回几个字节；
+ *    - `InterruptingReader`：每隔一次调用返回 `ErrorKind::Interrupted`。
+ *    用它们验证：原子写入中途失败不会破坏目标文件，`read_to_string` 能正确处理短读和 `Interrupted`。
+ *
+ * 6. 展开数字区间:
+ *    编写函数 `expand_ranges(s: &str) -> Result<Vec<u32>, String>`，把用逗号分隔的数字和
+ *    用连字符表示的区间展开成排好序、没有重复的 `Vec`。遇到无法解析的项或者起点大于终点的区间时返回 `Err`。
+ *    例如 "1-3,5,7-9" 展开成 [1, 2, 3, 5, 7, 8, 9]。用 `?` 把内部的解析错误直接传出去。
+ *    展开后的数字个数要有上限，"0-4294967295" 这样的输入应该返回 `Err` 而不是耗尽内存。
+ *
+ * 7. 友好的错误报告:
+ *    编写 `report_error(e: &(dyn Error + 'static)) -> String`：先输出最外层错误的 Display，
+ *    再沿着 `source()` 逐层输出 "caused by: ..."，最后用 `downcast_ref` 识别常见错误类型并附上一句提示
+ *    （例如文件不存在时提示检查路径）。再让 `main` 返回的错误也通过它来打印，
+ *    而不是默认的 `{:?}` 格式。
+ *
+ * 8. 可以追加的读数日志:
+ *    每行保存一条读数（格式和 `parse_reading` 接受的一样），编写 `ReadingLog`：
+ *    - `open(path)` 解析已有的行，用 Welford 算法一遍算出温度的平均值、方差、最小值和最大值，
+ *      并统计每种读数的条数，解析失败的行计数后跳过；
+ *    - `append(&mut self, reading)` 追加一行并增量更新统计；
+ *    - `refresh(&mut self)` 只读取上次读到的位置（`last_offset`）之后的内容，返回新增的读数条数，
+ *      这样别的进程追加的数据也能被统计进来，而不用从头重读整个文件。
+ *
+ * 9. 解析时间:
+ *    编写 `parse_time(s: &str) -> Result<(u8, u8), String>`，把 "HH:MM" 解析成小时（0-23）和分钟（0-59），
+ *    格式错误或超出范围时返回描述性的错误。例如 "09:30" 得到 (9, 30)，"24:00" 返回错误。
+ *    想一想 "9:5" 应不应该接受，并在注释里写清楚你的选择。
+ *
+ * 10. 两个时间相差多少分钟:
+ *    利用 `parse_time` 编写 `minutes_between(a: &str, b: &str) -> Result<i64, String>`，
+ *    返回从 a 到 b 的分钟数（可以是负数）。例如 "09:00" 到 "10:30" 是 90，反过来是 -90。
  */
\ No newline at end of file
@@ -153,6 +153,71 @@ fn main() {
     // 使用闭包过滤出长度大于4的字符串
     let long_strings = filter(&strings, |s| s.len() > 4);
     println!("长字符串是: {:?}", long_strings); // 输出: ["hello", "world", "awesome"]
+
+    // 练习3：
+    let text = "hello   rust  world";
+    println!("{}", map_words(text, |w| w.to_uppercase())); // 输出: HELLO RUST WORLD
+    println!("{}", map_words(text, |w| w.chars().rev().collect())); // 输出: olleh tsur dlrow
+
+    // 练习4：
+    let mut tasks: Slab<&str> = Slab::new();
+    let write = tasks.insert("write code");
+    let test = tasks.insert("run tests");
+    println!("{:?}", tasks.get(write)); // Some("write code")
+    println!("{:?}", tasks.remove(write)); // Some("write code")
+    println!("{:?}", tasks.remove(write)); // None，同一个键不能删除两次
+    // 新元素复用了刚释放的槽位，但代数（generation）变了，所以旧键不会读到新元素
+    let deploy = tasks.insert("deploy");
+    println!("{:?} {:?}", write, deploy); // Key { index: 0, generation: 0 } Key { index: 0, generation: 1 }
+    println!("{:?}", tasks.get(write)); // None
+    if let Some(task) = tasks.get_mut(test) {
+        *task = "run all tests";
+    }
+    for (key, task) in tasks.iter() {
+        println!("{} -> {}", key.index, task); // 0 -> deploy，1 -> run all tests
+    }
+    println!("len = {}, capacity = {}", tasks.len(), tasks.capacity()); // len = 2, capacity = 2
+
+    // 练习5：
+    println!("{}", longest_run(&[1, 1, 0, 1, 1, 1], &1)); // 3
+    println!("{}", longest_run(&[1, 1, 0, 1, 1, 1], &7)); // 0
+    println!("{}", longest_run(&["a", "b", "b"], &"b"));   // 2
+
+    // 练习6：
+    let cards: Vec<u32> = (1..=8).collect();
+    let first = shuffle(&cards, &mut Lcg::new(42));
+    let again = shuffle(&cards, &mut Lcg::new(42));
+    println!("{:?}", first); // [7, 8, 1, 6, 4, 3, 2, 5]
+    println!("同一个种子结果相同: {}", first == again); // true
+    let mut sorted = first.clone();
+    sorted.sort();
+    println!("还是原来那些元素: {}", sorted == cards); // true
+    let names = shuffle(&["Ann", "Bob", "Cid"], &mut Lcg::new(7)); // 泛型函数，换成字符串也一样能用
+    println!("{:?}", names); // ["Ann", "Cid", "Bob"]
+
+    // 练习7：
+    let mut recent = RingBuffer::new(3);
+    for n in 1..=5 {
+        recent.push(n); // 容量是 3，放入 4 和 5 时会覆盖最旧的 1 和 2
+    }
+    println!("{:?}", recent.iter().collect::<Vec<_>>()); // [3, 4, 5]，从旧到新
+    println!("{:?} {:?}", recent.pop(), recent.len()); // Some(3) 2，pop 取出最旧的元素
+    recent.push(6);
+    recent.push(7);
+    println!("{:?}", recent.iter().collect::<Vec<_>>()); // [5, 6, 7]
+    let mut words = RingBuffer::new(2);
+    words.push(String::from("old"));
+    words.push(String::from("new"));
+    println!("{:?} {:?} {:?}", words.pop(), words.pop(), words.pop()); // Some("old") Some("new") None
+
+    // 练习8：
+    print!("{}", format_column(&[7, 42, 1000, -5]));
+    //    7
+    //   42
+    // 1000
+    //   -5
+    println!("{:?}", format_column(&["a", "bbb"])); // "  a\nbbb\n"
+    println!("{:?}", format_column::<f64>(&[])); // ""
 }
 
 // 练习2：
@@ -170,6 +235,412 @@ where
     }
     result // 6. 返回结果
 }
+// 练习3：
+// 对每个单词调用闭包 f，再用单个空格把结果拼接起来（多余的空白会被合并）
+fn map_words<F: Fn(&str) -> String>(text: &str, f: F) -> String {
+    text.split_whitespace()
+        .map(&f)
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+// 练习4：
+// 用“槽位下标 + 代数”作为键：槽位被释放再复用后代数会加 1，旧键就会失效
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Key {
+    index: usize,
+    generation: u32,
+}
+
+struct Entry<T> {
+    generation: u32,
+    // None 表示这个槽位现在是空闲的
+    value: Option<T>,
+}
+
+// 简单的 arena（竞技场）存储：所有元素放在一个 Vec 里，用下标直接访问，不需要哈希
+struct Slab<T> {
+    entries: Vec<Entry<T>>,
+    // 空闲槽位的下标，insert 时优先复用
+    free: Vec<usize>,
+    len: usize,
+}
+
+impl<T> Slab<T> {
+    fn new() -> Self {
+        Slab { entries: Vec::new(), free: Vec::new(), len: 0 }
+    }
+
+    fn insert(&mut self, value: T) -> Key {
+        self.len += 1;
+        if let Some(index) = self.free.pop() {
+            let entry = &mut self.entries[index];
+            entry.value = Some(value);
+            return Key { index, generation: entry.generation };
+        }
+        self.entries.push(Entry { generation: 0, value: Some(value) });
+        Key { index: self.entries.len() - 1, generation: 0 }
+    }
+
+    fn get(&self, key: Key) -> Option<&T> {
+        match self.entries.get(key.index) {
+            Some(entry) if entry.generation == key.generation => entry.value.as_ref(),
+            _ => None,
+        }
+    }
+
+    fn get_mut(&mut self, key: Key) -> Option<&mut T> {
+        match self.entries.get_mut(key.index) {
+            Some(entry) if entry.generation == key.generation => entry.value.as_mut(),
+            _ => None,
+        }
+    }
+
+    fn remove(&mut self, key: Key) -> Option<T> {
+        let entry = self.entries.get_mut(key.index)?;
+        if entry.generation != key.generation {
+            return None;
+        }
+        let value = entry.value.take()?;
+        // 代数用 wrapping_add：同一个槽位被复用 2^32 次后会绕回 0，
+        // 到那时一个非常古老的键理论上可能重新生效，对这里的用途来说可以接受
+        entry.generation = entry.generation.wrapping_add(1);
+        self.free.push(key.index);
+        self.len -= 1;
+        Some(value)
+    }
+
+    // 按槽位顺序遍历所有元素，空闲的槽位会被跳过
+    fn iter(&self) -> impl Iterator<Item = (Key, &T)> {
+        self.entries.iter().enumerate().filter_map(|(index, entry)| {
+            entry
+                .value
+                .as_ref()
+                .map(|value| (Key { index, generation: entry.generation }, value))
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    // 已经分配的槽位数（包括空闲的）
+    fn capacity(&self) -> usize {
+        self.entries.len()
+    }
+}
+// 练习5：
+// 只需要能用 == 比较，所以约束是 PartialEq
+fn longest_run<T: PartialEq>(slice: &[T], value: &T) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    for item in slice {
+        if item == value {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    longest
+}
+// 练习6：
+// 线性同余生成器（LCG）：state = state * a + c（溢出时回绕）。
+// 同样的种子总是产生同样的序列，不适合加密，但用来做可重复的实验足够了
+struct Lcg {
+    state: u64,
+}
+
+impl Lcg {
+    fn new(seed: u64) -> Lcg {
+        Lcg { state: seed }
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        self.state = self.state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        // 低位的周期很短，取高 32 位
+        (self.state >> 32) as u32
+    }
+
+    // [0, bound) 之间的数，bound 必须大于 0
+    fn below(&mut self, bound: usize) -> usize {
+        (((self.next_u32() as u64) * (bound as u64)) >> 32) as usize
+    }
+}
+
+// Fisher–Yates 洗牌：从后往前，把第 i 个元素和 [0, i] 中随机的一个交换。
+// 原切片不变，返回打乱后的新 Vec，所以需要 T: Clone
+fn shuffle<T: Clone>(slice: &[T], rng: &mut Lcg) -> Vec<T> {
+    let mut result = slice.to_vec();
+    for i in (1..result.len()).rev() {
+        let j = rng.below(i + 1);
+        result.swap(i, j);
+    }
+    result
+}
+// 练习7：
+// 固定容量的环形缓冲区。head 是最旧元素的下标，新元素放在 (head + len) % cap 的位置，
+// 满了以后新元素覆盖最旧的元素，head 向后移一位。空的槽位是 None，所以 T 不需要 Default 或 Clone
+struct RingBuffer<T> {
+    data: Vec<Option<T>>,
+    head: usize,
+    len: usize,
+    cap: usize,
+}
+
+impl<T> RingBuffer<T> {
+    fn new(cap: usize) -> Self {
+        assert!(cap > 0, "RingBuffer 的容量必须大于 0");
+        RingBuffer { data: (0..cap).map(|_| None).collect(), head: 0, len: 0, cap }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn push(&mut self, value: T) {
+        let tail = (self.head + self.len) % self.cap;
+        self.data[tail] = Some(value);
+        if self.len == self.cap {
+            // 刚刚覆盖了最旧的元素，最旧的位置往后移
+            self.head = (self.head + 1) % self.cap;
+        } else {
+            self.len += 1;
+        }
+    }
+
+    // 取出最旧的元素
+    fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let value = self.data[self.head].take();
+        self.head = (self.head + 1) % self.cap;
+        self.len -= 1;
+        value
+    }
+
+    // 从最旧到最新依次返回元素的引用
+    fn iter(&self) -> impl Iterator<Item = &T> {
+        (0..self.len).filter_map(move |i| self.data[(self.head + i) % self.cap].as_ref())
+    }
+}
+// 练习8：
+// 只要能用 {} 打印就可以，所以约束是 Display。先把每一项转换成字符串才能知道它的宽度，
+// 宽度按字符数计算，而不是字节数
+fn format_column<T: Display>(items: &[T]) -> String {
+    let texts: Vec<String> = items.iter().map(|item| item.to_string()).collect();
+    let width = texts.iter().map(|text| text.chars().count()).max().unwrap_or(0);
+    let mut out = String::new();
+    for text in &texts {
+        out.push_str(&format!("{:>width$}\n", text, width = width));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 练习3
+    #[test]
+    fn map_words_applies_the_closure_to_each_word() {
+        let text = "hello   rust  world";
+        assert_eq!(map_words(text, |w| w.to_uppercase()), "HELLO RUST WORLD");
+        assert_eq!(map_words(text, |w| w.chars().rev().collect()), "olleh tsur dlrow");
+        // 闭包可以捕获外部变量
+        let suffix = "!";
+        assert_eq!(map_words("hi there", |w| format!("{}{}", w, suffix)), "hi! there!");
+    }
+
+    #[test]
+    fn map_words_collapses_whitespace() {
+        assert_eq!(map_words("  a\tb\n c  ", |w| w.to_string()), "a b c");
+        assert_eq!(map_words("", |w| w.to_string()), "");
+        assert_eq!(map_words("   ", |_| String::from("x")), "");
+        assert_eq!(map_words("你好 世界", |w| w.chars().rev().collect()), "好你 界世");
+    }
+
+    // 练习4
+    #[test]
+    fn stale_keys_do_not_see_reused_slots() {
+        let mut slab = Slab::new();
+        let write = slab.insert("write code");
+        assert_eq!(slab.remove(write), Some("write code"));
+        let deploy = slab.insert("deploy");
+        assert_eq!(deploy, Key { index: write.index, generation: write.generation + 1 });
+        assert_eq!(slab.get(write), None);
+        assert_eq!(slab.get_mut(write), None);
+        assert_eq!(slab.remove(write), None);
+        assert_eq!(slab.get(deploy), Some(&"deploy"));
+        // 下标越界的键也只是返回 None
+        assert_eq!(slab.get(Key { index: 99, generation: 0 }), None);
+    }
+
+    #[test]
+    fn remove_is_idempotent() {
+        let mut slab = Slab::new();
+        let a = slab.insert(1);
+        let b = slab.insert(2);
+        assert_eq!(slab.remove(a), Some(1));
+        assert_eq!(slab.remove(a), None);
+        assert_eq!(slab.remove(a), None);
+        // 重复删除不会把槽位放进空闲列表两次，也不会让 len 变少
+        assert_eq!(slab.len(), 1);
+        let c = slab.insert(3);
+        let d = slab.insert(4);
+        assert_eq!((c.index, d.index), (a.index, 2));
+        assert_eq!((slab.len(), slab.capacity()), (3, 3));
+        assert_eq!(slab.get(b), Some(&2));
+    }
+
+    #[test]
+    fn iteration_skips_free_slots() {
+        let mut slab = Slab::new();
+        let keys: Vec<Key> = (0..5).map(|n| slab.insert(n * 10)).collect();
+        slab.remove(keys[1]);
+        slab.remove(keys[3]);
+        if let Some(value) = slab.get_mut(keys[4]) {
+            *value += 1;
+        }
+        let items: Vec<(usize, i32)> = slab.iter().map(|(key, &value)| (key.index, value)).collect();
+        assert_eq!(items, vec![(0, 0), (2, 20), (4, 41)]);
+        // iter 返回的键和 insert 返回的一样，可以直接拿来访问
+        assert!(slab.iter().all(|(key, value)| slab.get(key) == Some(value)));
+        assert_eq!((slab.len(), slab.capacity()), (3, 5));
+    }
+
+    #[test]
+    fn generation_wraps_around_on_overflow() {
+        let mut slab = Slab::new();
+        let first = slab.insert("first");
+        // 直接把代数设成最大值，模拟这个槽位已经被复用了 2^32 - 1 次
+        slab.entries[first.index].generation = u32::MAX;
+        let old = Key { index: first.index, generation: u32::MAX };
+        assert_eq!(slab.remove(old), Some("first"));
+        let reused = slab.insert("reused");
+        assert_eq!(reused.generation, 0);
+        assert_eq!(slab.get(old), None);
+        // 绕回之后，代数为 0 的古老键会重新生效，这是注释里说明过的取舍
+        assert_eq!(slab.get(first), Some(&"reused"));
+    }
+
+    // 练习5
+    #[test]
+    fn longest_run_finds_the_longest_streak() {
+        assert_eq!(longest_run(&[1, 1, 0, 1, 1, 1], &1), 3);
+        assert_eq!(longest_run(&[1, 1, 1, 0, 1], &1), 3);
+        assert_eq!(longest_run(&[0, 1, 0, 1, 0], &1), 1);
+        assert_eq!(longest_run(&["a", "b", "b"], &"b"), 2);
+    }
+
+    #[test]
+    fn longest_run_of_missing_value_or_empty_slice_is_zero() {
+        assert_eq!(longest_run(&[1, 1, 0, 1, 1, 1], &7), 0);
+        assert_eq!(longest_run::<i32>(&[], &1), 0);
+        assert_eq!(longest_run(&[2.5, 2.5], &2.5), 2);
+    }
+
+    // 练习6
+    #[test]
+    fn shuffle_is_deterministic_for_a_fixed_seed() {
+        let cards: Vec<u32> = (1..=8).collect();
+        let first = shuffle(&cards, &mut Lcg::new(42));
+        assert_eq!(first, vec![7, 8, 1, 6, 4, 3, 2, 5]);
+        assert_eq!(shuffle(&cards, &mut Lcg::new(42)), first);
+        assert_eq!(shuffle(&["Ann", "Bob", "Cid"], &mut Lcg::new(7)), vec!["Ann", "Cid", "Bob"]);
+        // 原切片不变
+        assert_eq!(cards, (1..=8).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn shuffle_returns_a_permutation() {
+        let cards: Vec<u32> = (0..100).collect();
+        let mut rng = Lcg::new(2024);
+        for _ in 0..20 {
+            let mut shuffled = shuffle(&cards, &mut rng);
+            shuffled.sort();
+            assert_eq!(shuffled, cards);
+        }
+        assert!(shuffle::<u32>(&[], &mut rng).is_empty());
+        assert_eq!(shuffle(&[9], &mut rng), vec![9]);
+    }
+
+    #[test]
+    fn lcg_below_stays_in_range() {
+        let mut rng = Lcg::new(1);
+        assert!((0..1000).all(|_| rng.below(7) < 7));
+        assert!((0..100).all(|_| rng.below(1) == 0));
+        // 不同的种子给出不同的序列
+        assert_ne!(Lcg::new(1).next_u32(), Lcg::new(2).next_u32());
+    }
+
+    // 练习7
+    #[test]
+    fn ring_buffer_overwrites_the_oldest_when_full() {
+        let mut recent = RingBuffer::new(3);
+        for n in 1..=5 {
+            recent.push(n);
+        }
+        assert_eq!(recent.len(), 3);
+        assert_eq!(recent.iter().copied().collect::<Vec<_>>(), vec![3, 4, 5]);
+        assert_eq!(recent.pop(), Some(3));
+        assert_eq!(recent.len(), 2);
+    }
+
+    #[test]
+    fn ring_buffer_wraps_around_the_end_of_storage() {
+        let mut buffer = RingBuffer::new(3);
+        buffer.push(1);
+        buffer.push(2);
+        assert_eq!(buffer.pop(), Some(1));
+        // head 在下标 1，新元素会写到下标 0 上
+        buffer.push(3);
+        buffer.push(4);
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+        buffer.push(5);
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![3, 4, 5]);
+        assert_eq!((buffer.pop(), buffer.pop(), buffer.pop(), buffer.pop()), (Some(3), Some(4), Some(5), None));
+        assert_eq!(buffer.len(), 0);
+        buffer.push(6);
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![6]);
+    }
+
+    #[test]
+    fn ring_buffer_works_with_non_clone_values_and_capacity_one() {
+        let mut words = RingBuffer::new(1);
+        words.push(String::from("old"));
+        words.push(String::from("new"));
+        assert_eq!(words.pop(), Some(String::from("new")));
+        assert_eq!(words.pop(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "容量必须大于 0")]
+    fn ring_buffer_rejects_zero_capacity() {
+        RingBuffer::<i32>::new(0);
+    }
+
+    // 练习8
+    #[test]
+    fn format_column_right_aligns_to_the_widest_item() {
+        assert_eq!(format_column(&[7, 42, 1000, -5]), "   7\n  42\n1000\n  -5\n");
+        assert_eq!(format_column(&["a", "bbb"]), "  a\nbbb\n");
+        assert_eq!(format_column(&[1.5, 10.25]), "  1.5\n10.25\n");
+    }
+
+    #[test]
+    fn format_column_counts_chars_not_bytes() {
+        // "你好" 是 2 个字符、6 个字节，按字符数对齐
+        assert_eq!(format_column(&["你好", "abc"]), " 你好\nabc\n");
+    }
+
+    #[test]
+    fn format_column_of_nothing_is_empty() {
+        assert_eq!(format_column::<f64>(&[]), "");
+        assert_eq!(format_column(&[""]), "\n");
+    }
+}
+
 /*
  * =====================================================================================
  * 练习挑战 (Challenge Section)
@@ -188,4 +659,34 @@ where
  *    (我们还没有正式学习闭包，但你可以把它看作一个可以捕获环境的匿名函数。
  *    例如 `|&x| x > 5` 就是一个判断数字是否大于5的闭包。)
  *
+ *
+ * 3. 对每个单词应用闭包:
+ *    编写函数 `map_words<F: Fn(&str) -> String>(text: &str, f: F) -> String`，
+ *    对每个以空白分隔的单词调用 `f`，再用单个空格重新拼接。
+ *    试着传入把单词转成大写的闭包，以及把单词反转的闭包。
+ *
+ * 4. 泛型 arena 存储 `Slab<T>`:
+ *    用一个 `Vec` 保存所有元素，`insert` 返回 `Key { index, generation }`。
+ *    `remove` 释放槽位并把它的代数加 1，之后的 `insert` 会复用这个槽位，
+ *    这样旧的键在 `get`/`get_mut`/`remove` 时就会被识别出来并返回 `None`。
+ *    再实现 `iter`（跳过空闲槽位）、`len` 和 `capacity`。
+ *
+ * 5. 最长的连续段:
+ *    编写泛型函数 `longest_run<T: PartialEq>(slice: &[T], value: &T) -> usize`，
+ *    返回切片中连续等于 `value` 的最长一段的长度。
+ *    例如 `[1, 1, 0, 1, 1, 1]` 中 1 的最长连续段长度是 3；`value` 不存在时返回 0。
+ *
+ * 6. 可重复的洗牌:
+ *    先写一个简单的线性同余随机数生成器 `Lcg`（`new(seed)` 和 `next_u32`），
+ *    再编写 `shuffle<T: Clone>(slice: &[T], rng: &mut Lcg) -> Vec<T>`，用 Fisher–Yates 算法打乱到新的 `Vec` 中。
+ *    同一个种子必须得到同样的结果，结果里的元素和原切片完全一样，只是顺序不同。
+ *
+ * 7. 环形缓冲区:
+ *    定义 `struct RingBuffer<T> { data: Vec<Option<T>>, head: usize, len: usize, cap: usize }`，
+ *    `push` 在满了的时候覆盖最旧的元素，`pop` 取出最旧的元素，`iter` 从旧到新返回所有元素。
+ *    例如容量为 3 时依次放入 1 到 5，剩下的是 [3, 4, 5]。
+ *
+ * 8. 右对齐的一列:
+ *    编写 `format_column<T: Display>(items: &[T]) -> String`，每项占一行，
+ *    按最宽的一项右对齐。例如 [7, 42, 1000] 会得到 "   7\n  42\n1000\n"。
  */
\ No newline at end of file